@@ -0,0 +1,561 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+};
+
+use anki_utill::{
+    anki::{
+        anki_changeset::{Changeset, FieldDiff, NoteChange},
+        anki_connect::AnkiConnect,
+        anki_connect_error::AnkiConnectError,
+        anki_note::{AnkiNote, ID},
+    },
+    config::Config,
+    dict::{dict_cache::DictionaryCache, dict_index::TagBank},
+    entry::{parse_wordlist, Word},
+};
+use regex::Regex;
+
+use super::{load_tags, Options, Response};
+
+/// Loads word data from the configured word list and writes it to Anki notes.
+/// Fails if any of the notes could not be written.
+pub fn run(config: &Config, options: &Options) -> Response<()> {
+    let wordlist_save_path = &config.paths.wordlist;
+
+    println!("Loading words from {}", wordlist_save_path.display());
+    let data = fs::read_to_string(wordlist_save_path)?;
+
+    let words = parse_wordlist(&data)?;
+    let tags = load_tags(config)?;
+
+    // The index finds the words of notes whose furigana is split differently
+    let index = DictionaryCache::read(&config.paths.index).ok();
+    if index.is_some() {
+        println!("Using index {}", config.paths.index.display());
+    }
+
+    let anki = config.connect()?;
+
+    let failures = write_words(&anki, config, options, &words, index.as_ref(), &tags)?;
+    if !failures.is_empty() {
+        return Err(format!("{} notes failed", failures.len()).into());
+    }
+
+    Ok(())
+}
+
+/// Handles writing of words to Anki by first planning every change, then applying the changeset.
+/// The changeset is printed in full on a dry run and not applied.
+/// Returns the words that failed to be written together with the error.
+fn write_words(
+    anki: &AnkiConnect,
+    config: &Config,
+    options: &Options,
+    words: &HashMap<String, Word>,
+    index: Option<&DictionaryCache>,
+    tags: &TagBank,
+) -> Response<Vec<(String, AnkiConnectError)>> {
+    println!("\nGetting Notes info.");
+    let notes = anki.notes_info(&anki.find_notes(&config.word_query())?)?;
+    let suspended: HashSet<ID> = anki
+        .find_cards(&format!("{} is:suspended", config.word_query()))?
+        .into_iter()
+        .collect();
+
+    println!("Planning changes.");
+    let changeset = plan_words(words, index, tags, &notes, &suspended, config, options);
+
+    if options.dry_run {
+        if options.json {
+            println!("{}", serde_json::to_string_pretty(&changeset)?);
+        } else {
+            println!("{}", changeset);
+        }
+        return Ok(Vec::new());
+    }
+
+    println!("  {}", changeset.summary());
+    if changeset.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    println!("Applying changes.");
+    let failures = changeset.apply(anki)?;
+
+    report_failures(&failures);
+    Ok(failures)
+}
+
+/// Prints every note that could not be written to Anki together with the reason.
+pub(super) fn report_failures(failures: &[(String, AnkiConnectError)]) {
+    if failures.is_empty() {
+        println!("\nAll notes were written successfully.");
+        return;
+    }
+
+    println!("\n{} notes failed:", failures.len());
+    for (word, err) in failures {
+        println!("  {}: {}", word, err);
+    }
+}
+
+/// Plans the changes bringing the Anki notes in line with the word list.
+///
+/// For each existing note:
+/// - Extracts the word from the word field
+/// - Checks if word exists in provided `words` map, looking it up in the index by its kanji form
+///   and reading if the furigana differs
+/// - Updates fields (word, meaning, examples) if needed
+/// - Manages tags by removing old ones and adding new ones
+/// - Unsuspends the suspended cards of words in the list
+/// - Suspends the cards of notes that don't match any word
+///
+/// Words without a note are added, at most `limit` of them.
+fn plan_words(
+    words: &HashMap<String, Word>,
+    index: Option<&DictionaryCache>,
+    tags: &TagBank,
+    notes: &[AnkiNote],
+    suspended: &HashSet<ID>,
+    config: &Config,
+    options: &Options,
+) -> Changeset {
+    let field = &config.fields;
+    let re = Regex::new(r"] ").unwrap();
+
+    let mut changeset = Changeset::default();
+    let mut existing: HashSet<String> = HashSet::new();
+
+    for note in notes.iter() {
+        // Extract word from the word field
+        let note_id = note.noteId.unwrap();
+        let note_cards = note.cards.clone().unwrap();
+        let word = re
+            .replace_all(note.fields.get(&field.word).unwrap(), "]")
+            .to_string();
+
+        let found = index
+            .and_then(|index| index.find(&word))
+            .and_then(|found| words.get(&found.furigana));
+        let Some(word_data) = words.get(&word).or(found) else {
+            // No matching word found, suspend the note
+            let cards = note_cards
+                .into_iter()
+                .filter(|card| !suspended.contains(card))
+                .collect();
+            changeset.suspend(&word, cards);
+            continue;
+        };
+
+        let mut change = NoteChange::new(&word, note_id);
+
+        // Update word field if changed
+        if note.fields[&field.word] != word_data.furigana {
+            change.fields.push(FieldDiff {
+                field: field.word.clone(),
+                old: note.fields[&field.word].clone(),
+                new: word_data.furigana.clone(),
+            });
+        }
+
+        // Update meaning field if changed
+        let meaning = word_data.meaning_html_with_tags(tags);
+        if note.fields[&field.meaning] != meaning {
+            change.fields.push(FieldDiff {
+                field: field.meaning.clone(),
+                old: note.fields[&field.meaning].clone(),
+                new: meaning,
+            });
+        }
+
+        // Update the optional fields if the note type has them and they changed
+        let optional = [
+            (&field.romaji, word_data.romaji()),
+            (&field.pitch, word_data.pitch_html()),
+            (&field.conjugations, word_data.conjugations_html()),
+        ];
+        for (name, new) in optional {
+            let Some(name) = name else {
+                continue;
+            };
+
+            match note.fields.get(name) {
+                Some(old) if *old != new => change.fields.push(FieldDiff {
+                    field: name.clone(),
+                    old: old.clone(),
+                    new,
+                }),
+                _ => {}
+            }
+        }
+
+        // Update examples field if empty
+        let examples = word_data.examples_html();
+        if note.fields[&field.sentences].is_empty() && !examples.is_empty() {
+            change.fields.push(FieldDiff {
+                field: field.sentences.clone(),
+                old: String::new(),
+                new: examples,
+            });
+        }
+
+        // Manage tags: remove old ones and add new ones, keeping the review tag of the audio stage
+        let word_tags = word_data.get_all_tags();
+
+        change.remove_tags = note
+            .tags
+            .iter()
+            .filter(|tag| !word_tags.contains(tag.as_str()))
+            .filter(|tag| **tag != config.audio.rejected_tag)
+            .cloned()
+            .collect();
+
+        change.add_tags = word_tags
+            .iter()
+            .filter(|tag| !note.tags.contains(&(**tag).to_owned()))
+            .map(|tag| tag.to_string())
+            .collect();
+        change.add_tags.sort_unstable();
+
+        changeset.update(change);
+
+        // Unsuspend note if it is in the word list
+        let cards = note_cards
+            .into_iter()
+            .filter(|card| suspended.contains(card))
+            .collect();
+        changeset.unsuspend(&word, cards);
+
+        existing.insert(word_data.furigana.clone());
+    }
+
+    // Add the missing words in a stable order
+    let mut missing: Vec<&Word> = words
+        .values()
+        .filter(|word| !existing.contains(&word.furigana))
+        .collect();
+    missing.sort_unstable_by(|a, b| a.furigana.cmp(&b.furigana));
+
+    for word in options.limited(&missing) {
+        // Prepare fields for new note
+        let mut fields: HashMap<String, String> = HashMap::new();
+
+        fields.insert(field.word.clone(), word.furigana.clone());
+        fields.insert(field.meaning.clone(), word.meaning_html_with_tags(tags));
+        fields.insert(field.sentences.clone(), word.examples_html());
+        if let Some(romaji) = &field.romaji {
+            fields.insert(romaji.clone(), word.romaji());
+        }
+        if let Some(pitch) = &field.pitch {
+            fields.insert(pitch.clone(), word.pitch_html());
+        }
+        if let Some(conjugations) = &field.conjugations {
+            fields.insert(conjugations.clone(), word.conjugations_html());
+        }
+
+        let mut tags: Vec<String> = word
+            .get_all_tags()
+            .iter()
+            .map(|tag| tag.to_string())
+            .collect();
+        tags.sort_unstable();
+
+        changeset.add(
+            &word.furigana,
+            AnkiNote {
+                modelName: config.deck.word_model.clone(),
+                deckName: config.deck.name.clone().into(),
+                tags,
+                fields,
+
+                ..AnkiNote::default()
+            },
+        );
+    }
+
+    changeset
+}
+
+#[cfg(test)]
+mod tests {
+    use anki_utill::{
+        anki::anki_connect_mock::MockAnkiConnect,
+        entry::{Glossary, Word},
+        pitch,
+    };
+
+    use super::*;
+
+    /// Creates a word with a single meaning and frequency tag.
+    fn word(furigana: &str, meaning: &str) -> Word {
+        Word::new(
+            0,
+            furigana.to_owned(),
+            vec![Glossary::new(
+                0,
+                HashSet::from(["n".to_owned()]),
+                vec![meaning.to_owned()],
+            )],
+            HashSet::from(["JLPT-N5".to_owned()]),
+            HashSet::new(),
+        )
+    }
+
+    /// Returns a config using a note type with custom field names.
+    fn config() -> Config {
+        let mut config = Config::default();
+        config.deck.name = "Japanese".to_owned();
+        config.deck.word_model = "Word".to_owned();
+        config.fields.word = "Expression".to_owned();
+        config.fields.meaning = "Meaning".to_owned();
+        config.fields.audio = "Audio".to_owned();
+        config.fields.sentences = "Sentences".to_owned();
+        config
+    }
+
+    /// Starts a mock with an outdated note and a note that is no longer in the word list.
+    fn setup() -> (MockAnkiConnect, ID, ID) {
+        let mock = MockAnkiConnect::start().unwrap();
+
+        let (cat, dog) = mock.with_collection(|collection| {
+            collection.add_deck("Japanese");
+            collection.add_model("Word", &["Expression", "Meaning", "Audio", "Sentences"]);
+
+            let cat = collection
+                .add_note(
+                    "Japanese",
+                    "Word",
+                    &[("Expression", "猫[ねこ]"), ("Meaning", "old")],
+                    &["old-tag", "audio-rejected"],
+                )
+                .unwrap();
+            let dog = collection
+                .add_note("Japanese", "Word", &[("Expression", "犬[いぬ]")], &[])
+                .unwrap();
+
+            (cat, dog)
+        });
+
+        (mock, cat, dog)
+    }
+
+    /// Tests that existing notes are updated, stale notes suspended and new words added.
+    #[test]
+    fn sync_words() {
+        let (mock, cat, dog) = setup();
+        let anki = mock.connect().unwrap();
+
+        let words = HashMap::from([
+            ("猫[ねこ]".to_owned(), word("猫[ねこ]", "cat")),
+            ("鳥[とり]".to_owned(), word("鳥[とり]", "bird")),
+        ]);
+
+        assert!(write_words(
+            &anki,
+            &config(),
+            &Options::default(),
+            &words,
+            None,
+            &TagBank::default()
+        )
+        .unwrap()
+        .is_empty());
+
+        let collection = mock.collection();
+        let cat = collection.note(cat).unwrap();
+        assert_eq!(cat.fields["Meaning"], "[ n ] cat");
+        let mut tags = cat.tags.clone();
+        tags.sort();
+        assert_eq!(tags, vec!["JLPT-N5", "audio-rejected", "n"]);
+
+        let dog = collection.note(dog).unwrap();
+        assert!(collection.card(dog.cards[0]).unwrap().suspended);
+
+        let bird = collection.find_notes("\"Expression:鳥[とり]\"");
+        assert_eq!(bird.len(), 1);
+        assert_eq!(collection.field(bird[0], "Meaning"), Some("[ n ] bird"));
+
+        // A second run has nothing left to add or update
+        mock.clear_requests();
+        assert!(write_words(
+            &anki,
+            &config(),
+            &Options::default(),
+            &words,
+            None,
+            &TagBank::default()
+        )
+        .unwrap()
+        .is_empty());
+        let actions = mock.actions();
+        assert!(!actions.contains(&"addNote".to_owned()));
+        assert!(!actions.contains(&"updateNoteFields".to_owned()));
+    }
+
+    /// Tests that failed notes are reported with their word.
+    #[test]
+    fn report_failed_words() {
+        let (mock, _, _) = setup();
+        let anki = mock.connect().unwrap();
+        mock.fail_action("addNote", "collection is not available");
+
+        let words = HashMap::from([("鳥[とり]".to_owned(), word("鳥[とり]", "bird"))]);
+        let failures = write_words(
+            &anki,
+            &config(),
+            &Options::default(),
+            &words,
+            None,
+            &TagBank::default(),
+        )
+        .unwrap();
+
+        assert_eq!(failures.len(), 1);
+        assert_eq!(failures[0].0, "鳥[とり]");
+    }
+
+    /// Tests that a dry run only reads from Anki and respects the limit.
+    #[test]
+    fn dry_run() {
+        let (mock, cat, _) = setup();
+        let anki = mock.connect().unwrap();
+        mock.clear_requests();
+
+        let words = HashMap::from([
+            ("猫[ねこ]".to_owned(), word("猫[ねこ]", "cat")),
+            ("鳥[とり]".to_owned(), word("鳥[とり]", "bird")),
+            ("魚[さかな]".to_owned(), word("魚[さかな]", "fish")),
+        ]);
+        let options = Options {
+            dry_run: true,
+            limit: Some(1),
+            ..Options::default()
+        };
+
+        assert!(write_words(
+            &anki,
+            &config(),
+            &options,
+            &words,
+            None,
+            &TagBank::default()
+        )
+        .unwrap()
+        .is_empty());
+
+        assert_eq!(mock.actions(), vec!["findNotes", "notesInfo", "findCards"]);
+        assert_eq!(mock.collection().notes.len(), 2);
+        assert_eq!(mock.collection().field(cat, "Meaning"), Some("old"));
+    }
+
+    /// Tests that the plan only lists real changes and unsuspends suspended words.
+    #[test]
+    fn plan_changes() {
+        let mut config = config();
+        config.fields.romaji = Some("Romaji".to_owned());
+        config.fields.pitch = Some("Pitch".to_owned());
+        config.fields.conjugations = Some("Conjugations".to_owned());
+        let note = |id: ID, word: &str, meaning: &str, tags: &[&str]| AnkiNote {
+            noteId: Some(id),
+            modelName: "Word".to_owned(),
+            tags: tags.iter().map(|tag| tag.to_string()).collect(),
+            fields: HashMap::from([
+                ("Expression".to_owned(), word.to_owned()),
+                ("Meaning".to_owned(), meaning.to_owned()),
+                ("Sentences".to_owned(), String::new()),
+            ]),
+            cards: Some(vec![id * 10]),
+            ..AnkiNote::default()
+        };
+
+        let notes = vec![
+            note(1, "猫[ねこ]", "[ n ] cat", &["JLPT-N5", "n"]),
+            note(2, "犬[いぬ]", "dog", &[]),
+            note(3, "鳥[とり]", "[ n ] bird", &["JLPT-N5", "n"]),
+        ];
+        let mut words = HashMap::from([
+            ("猫[ねこ]".to_owned(), word("猫[ねこ]", "cat")),
+            ("鳥[とり]".to_owned(), word("鳥[とり]", "bird")),
+            ("魚[さかな]".to_owned(), word("魚[さかな]", "fish")),
+        ]);
+        words.get_mut("魚[さかな]").unwrap().pitch = vec![0];
+
+        let changeset = plan_words(
+            &words,
+            None,
+            &TagBank::default(),
+            &notes,
+            &HashSet::from([30]),
+            &config,
+            &Options::default(),
+        );
+
+        assert!(changeset.update.is_empty());
+        assert_eq!(changeset.suspend.len(), 1);
+        assert_eq!(changeset.suspend[0].cards, vec![20]);
+        assert_eq!(changeset.unsuspend.len(), 1);
+        assert_eq!(changeset.unsuspend[0].label, "鳥[とり]");
+        assert_eq!(changeset.add.len(), 1);
+        assert_eq!(changeset.add[0].label, "魚[さかな]");
+        assert_eq!(changeset.add[0].note.tags, vec!["JLPT-N5", "n"]);
+        assert_eq!(changeset.add[0].note.fields["Romaji"], "sakana");
+        assert_eq!(
+            changeset.add[0].note.fields["Pitch"],
+            pitch::pitch_html("さかな", 0)
+        );
+        // Nouns do not inflect
+        assert_eq!(changeset.add[0].note.fields["Conjugations"], "");
+    }
+
+    /// Tests that a note whose furigana is split differently is matched through the index.
+    #[test]
+    fn plan_with_index() {
+        let furigana = "日本[にほん] 語[ご]";
+        let words = HashMap::from([(furigana.to_owned(), word(furigana, "Japanese"))]);
+        let index = DictionaryCache::new(
+            Default::default(),
+            words.clone(),
+            HashMap::new(),
+            Default::default(),
+            HashMap::new(),
+        );
+        let notes = vec![AnkiNote {
+            noteId: Some(1),
+            fields: HashMap::from([
+                ("Expression".to_owned(), "日本語[にほんご]".to_owned()),
+                ("Meaning".to_owned(), words[furigana].meaning_html()),
+                ("Sentences".to_owned(), String::new()),
+            ]),
+            tags: vec!["JLPT-N5".to_owned(), "n".to_owned()],
+            cards: Some(vec![10]),
+            ..AnkiNote::default()
+        }];
+
+        let changeset = plan_words(
+            &words,
+            None,
+            &TagBank::default(),
+            &notes,
+            &HashSet::new(),
+            &config(),
+            &Options::default(),
+        );
+        assert_eq!(changeset.suspend.len(), 1);
+        assert_eq!(changeset.add.len(), 1);
+
+        let changeset = plan_words(
+            &words,
+            Some(&index),
+            &TagBank::default(),
+            &notes,
+            &HashSet::new(),
+            &config(),
+            &Options::default(),
+        );
+        assert!(changeset.suspend.is_empty());
+        assert!(changeset.add.is_empty());
+        assert_eq!(changeset.update.len(), 1);
+        assert_eq!(changeset.update[0].fields[0].new, furigana);
+    }
+}
@@ -0,0 +1,265 @@
+use std::{
+    collections::HashMap,
+    io::{self, Write},
+};
+
+use anki_utill::{
+    anki::{anki_connect::AnkiConnect, anki_note::AnkiNote},
+    audio::audio_source::{AudioChain, RejectedAudio},
+    config::{AudioConfig, Config, WordFields},
+    japanese::JapaneseStr,
+};
+use regex::Regex;
+
+use super::{Options, Response};
+
+/// Adds audio to every word note with an empty audio field, trying the configured audio sources
+/// in order. Each source waits out its own rate limit. Notes whose audio was all rejected are
+/// tagged for review. Fails if any audio could not be added.
+pub fn run(config: &Config, options: &Options) -> Response<()> {
+    println!("Fetching anki info");
+    let anki = config.connect()?;
+    let query = format!(
+        "{} \"{}:\"",
+        options.note_query(config),
+        config.fields.audio
+    );
+    let notes = anki.notes_info(&anki.find_notes(&query)?)?;
+    let notes = options.limited(&notes);
+
+    let mut chain = AudioChain::from_config(config)?;
+    if chain.is_empty() {
+        return Err("No audio source available".into());
+    }
+    println!("Audio sources: {}", chain.names().join(", "));
+
+    if options.dry_run {
+        println!("Would look up audio of {} notes:", notes.len());
+        for note in notes {
+            let (kanji, reading) = note_word(&config.fields, note);
+            println!("  {} ({})", kanji, reading);
+        }
+        return Ok(());
+    }
+
+    println!("Adding audio to {} notes. ", notes.len());
+    let mut missing = 0;
+    let mut rejected = 0;
+    let mut failed = 0;
+    for (i, note) in notes.iter().enumerate() {
+        // Progress tracking every 5% of total notes
+        if i % (notes.len() / 20).max(1) == 0 {
+            print!(
+                "\n{:>3}% Notes ",
+                ((i as f32 / notes.len() as f32) * 100.0).round()
+            );
+            io::stdout().flush().unwrap();
+        }
+
+        match add_audio(&anki, &config.fields, &config.audio, &mut chain, note) {
+            Ok(AudioOutcome::Added) => print!("+"),
+            Ok(AudioOutcome::Missing) => {
+                print!(".");
+                missing += 1;
+            }
+            Ok(AudioOutcome::Rejected(audio)) => {
+                print!("!");
+                for audio in audio {
+                    println!(
+                        "\nRejected audio of {} from {} ({}): {}",
+                        note.fields[&config.fields.word], audio.source, audio.hash, audio.reason
+                    );
+                }
+                rejected += 1;
+            }
+            Err(err) => {
+                println!(
+                    "\nFailed to add audio to {}: {}",
+                    note.fields[&config.fields.word], err
+                );
+                failed += 1;
+            }
+        }
+        io::stdout().flush().unwrap();
+    }
+
+    println!();
+    if missing > 0 {
+        println!("No audio found for {} notes", missing);
+    }
+    if rejected > 0 {
+        println!(
+            "Audio of {} notes rejected, tagged {}",
+            rejected, config.audio.rejected_tag
+        );
+    }
+    if failed > 0 {
+        return Err(format!("{} notes failed", failed).into());
+    }
+
+    Ok(())
+}
+
+/// Returns the `(kanji, reading)` of the word of a note.
+fn note_word(fields: &WordFields, note: &AnkiNote) -> (String, String) {
+    let word = &note.fields[&fields.word];
+    let regex = Regex::new(r"\s").unwrap();
+    let word = regex.replace_all(word, "").to_string();
+
+    (word.to_kanji(), word.to_kana())
+}
+
+/// The outcome of adding audio to a note.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum AudioOutcome {
+    /// Audio was added.
+    Added,
+    /// No source had audio of the word.
+    Missing,
+    /// Every audio found was rejected, the note was tagged for review.
+    Rejected(Vec<RejectedAudio>),
+}
+
+/// Looks up the audio of a note, stores it in the media folder and adds it to the audio field.
+/// A note whose audio was all rejected is tagged with the rejected tag, which is removed again
+/// once valid audio is added.
+fn add_audio(
+    anki: &AnkiConnect,
+    fields: &WordFields,
+    audio: &AudioConfig,
+    chain: &mut AudioChain,
+    note: &AnkiNote,
+) -> Response<AudioOutcome> {
+    let (kanji, reading) = note_word(fields, note);
+    let note_id = note.noteId.unwrap();
+    let lookup = chain.lookup(&kanji, &reading)?;
+
+    let Some(clip) = lookup.clip else {
+        if lookup.rejected.is_empty() {
+            return Ok(AudioOutcome::Missing);
+        }
+
+        anki.add_tags(&[note_id], &audio.rejected_tag)?;
+        return Ok(AudioOutcome::Rejected(lookup.rejected));
+    };
+
+    let filename = anki.store_media_file(&clip.filename, &clip.data)?;
+    anki.update_note_fields(
+        note_id,
+        &HashMap::from([(fields.audio.clone(), format!("[sound:{}]", filename))]),
+    )?;
+    if note.tags.contains(&audio.rejected_tag) {
+        anki.remove_tags(&[note_id], &audio.rejected_tag)?;
+    }
+
+    Ok(AudioOutcome::Added)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use anki_utill::{
+        anki::anki_connect_mock::MockAnkiConnect,
+        audio::{audio_local::LocalAudio, audio_validate::Rejection},
+    };
+
+    use super::*;
+
+    /// Tests that audio found in a local directory is stored and added to the note.
+    #[test]
+    fn local_audio() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        fs::write(dir.join("日本_にほん.mp3"), "nihon").unwrap();
+
+        let mock = MockAnkiConnect::start().unwrap();
+        let note = mock.with_collection(|collection| {
+            collection.add_deck("My Deck 4.0");
+            collection.add_model("JP Card V4", &["1 Word", "3 Audio"]);
+            collection
+                .add_note(
+                    "My Deck 4.0",
+                    "JP Card V4",
+                    &[("1 Word", "日本[にほん]")],
+                    &[],
+                )
+                .unwrap()
+        });
+
+        let anki = mock.connect().unwrap();
+        let note = anki.notes_info(&[note]).unwrap().remove(0);
+        let mut chain = AudioChain::new(vec![Box::new(LocalAudio::open(dir).unwrap())]);
+
+        let fields = WordFields::default();
+        assert_eq!(
+            note_word(&fields, &note),
+            ("日本".to_owned(), "にほん".to_owned())
+        );
+        let audio = AudioConfig::default();
+        assert_eq!(
+            add_audio(&anki, &fields, &audio, &mut chain, &note).unwrap(),
+            AudioOutcome::Added
+        );
+
+        let collection = mock.collection();
+        assert_eq!(
+            collection.field(note.noteId.unwrap(), "3 Audio"),
+            Some("[sound:Local_日本「にほん」.mp3]")
+        );
+        assert_eq!(collection.media["Local_日本「にほん」.mp3"], b"nihon");
+
+        let mut note = note;
+        note.fields
+            .insert("1 Word".to_owned(), "猫[ねこ]".to_owned());
+        assert_eq!(
+            add_audio(&anki, &fields, &audio, &mut chain, &note).unwrap(),
+            AudioOutcome::Missing
+        );
+    }
+
+    /// Tests that a note whose audio is all rejected is tagged, and untagged once audio is added.
+    #[test]
+    fn rejected_audio() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        fs::write(
+            dir.join("猫_ねこ.mp3"),
+            "<html><body>Not found</body></html>",
+        )
+        .unwrap();
+
+        let mock = MockAnkiConnect::start().unwrap();
+        let id = mock.with_collection(|collection| {
+            collection.add_deck("My Deck 4.0");
+            collection.add_model("JP Card V4", &["1 Word", "3 Audio"]);
+            collection
+                .add_note("My Deck 4.0", "JP Card V4", &[("1 Word", "猫[ねこ]")], &[])
+                .unwrap()
+        });
+
+        let anki = mock.connect().unwrap();
+        let note = anki.notes_info(&[id]).unwrap().remove(0);
+        let mut chain = AudioChain::new(vec![Box::new(LocalAudio::open(dir).unwrap())]);
+
+        let fields = WordFields::default();
+        let audio = AudioConfig::default();
+        let AudioOutcome::Rejected(rejected) =
+            add_audio(&anki, &fields, &audio, &mut chain, &note).unwrap()
+        else {
+            panic!("Audio not rejected");
+        };
+        assert_eq!(rejected[0].source, "Local");
+        assert_eq!(rejected[0].reason, Rejection::HtmlPage);
+        assert_eq!(mock.collection().notes[&id].tags, vec!["audio-rejected"]);
+        assert_eq!(mock.collection().field(id, "3 Audio"), Some(""));
+
+        fs::write(dir.join("猫_ねこ.mp3"), "neko").unwrap();
+        let note = anki.notes_info(&[id]).unwrap().remove(0);
+        assert_eq!(
+            add_audio(&anki, &fields, &audio, &mut chain, &note).unwrap(),
+            AudioOutcome::Added
+        );
+        assert!(mock.collection().notes[&id].tags.is_empty());
+    }
+}
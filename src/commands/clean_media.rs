@@ -0,0 +1,146 @@
+use anki_utill::{
+    anki::{
+        anki_connect::AnkiConnect,
+        anki_media::{unused_media, ALL_NOTES},
+    },
+    config::Config,
+};
+
+use super::{Options, Response};
+
+/// The patterns of the media files written by the stages. Other media files are never deleted,
+/// as they were not added by the stages.
+const MEDIA_PATTERNS: [&str; 5] = [
+    "JapanesePod101_*",
+    "Library_*",
+    "Local_*",
+    "Forvo_*",
+    "kanjivg_*",
+];
+
+/// Deletes the media files written by the stages that no note references anymore. Notes of
+/// other decks keep the files they reference too.
+pub fn run(config: &Config, options: &Options) -> Response<()> {
+    println!("Fetching anki info");
+    let anki = config.connect()?;
+
+    delete_unused(&anki, config, options)
+}
+
+/// Deletes the unused media files, see [`find_unused`]. They are only printed on a dry run.
+fn delete_unused(anki: &AnkiConnect, config: &Config, options: &Options) -> Response<()> {
+    println!(
+        "Checking the media of {} against every note of the collection",
+        config.deck_query()
+    );
+    let unused = find_unused(anki)?;
+    if unused.is_empty() {
+        println!("No unused media files.");
+        return Ok(());
+    }
+
+    if options.dry_run {
+        println!("Would delete {} media files:", unused.len());
+        for filename in &unused {
+            println!("  {}", filename);
+        }
+        return Ok(());
+    }
+
+    println!("Deleting {} media files.", unused.len());
+    for filename in &unused {
+        anki.delete_media_file(filename)?;
+    }
+
+    Ok(())
+}
+
+/// Returns the media files matching [`MEDIA_PATTERNS`] no note of the collection references.
+fn find_unused(anki: &AnkiConnect) -> Response<Vec<String>> {
+    Ok(unused_media(anki, ALL_NOTES, &MEDIA_PATTERNS)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use anki_utill::anki::anki_connect_mock::MockAnkiConnect;
+
+    use super::*;
+
+    /// Tests that only the media files of the stages no note of any deck references are deleted.
+    #[test]
+    fn clean_media() {
+        let mock = MockAnkiConnect::start().unwrap();
+        let mut config = Config::default();
+        config.deck.name = "Japanese".to_owned();
+
+        mock.with_collection(|collection| {
+            collection.add_deck("Japanese");
+            collection.add_deck("Other");
+            collection.add_model("JP Card V4", &["1 Word", "3 Audio"]);
+            collection
+                .add_note(
+                    "Japanese",
+                    "JP Card V4",
+                    &[
+                        ("1 Word", "猫[ねこ]"),
+                        ("3 Audio", "[sound:JapanesePod101_猫「ねこ」.mp3]"),
+                    ],
+                    &[],
+                )
+                .unwrap();
+            collection
+                .add_note(
+                    "Other",
+                    "JP Card V4",
+                    &[
+                        ("1 Word", "鳥[とり]"),
+                        ("3 Audio", "[sound:JapanesePod101_鳥「とり」.mp3]"),
+                    ],
+                    &[],
+                )
+                .unwrap();
+
+            for filename in [
+                "JapanesePod101_猫「ねこ」.mp3",
+                "JapanesePod101_犬「いぬ」.mp3",
+                "JapanesePod101_鳥「とり」.mp3",
+                "kanjivg_0732b.svg",
+                "other.mp3",
+            ] {
+                collection.media.insert(filename.to_owned(), Vec::new());
+            }
+        });
+
+        let anki = mock.connect().unwrap();
+        assert_eq!(
+            find_unused(&anki).unwrap(),
+            vec!["JapanesePod101_犬「いぬ」.mp3", "kanjivg_0732b.svg"]
+        );
+        // The notes are fetched once for all patterns
+        let actions = mock.actions();
+        assert_eq!(
+            actions
+                .iter()
+                .filter(|action| *action == "notesInfo")
+                .count(),
+            1
+        );
+
+        let options = Options {
+            dry_run: true,
+            ..Options::default()
+        };
+        delete_unused(&anki, &config, &options).unwrap();
+        assert_eq!(mock.collection().media.len(), 5);
+
+        delete_unused(&anki, &config, &Options::default()).unwrap();
+        assert_eq!(
+            mock.collection().media.keys().collect::<Vec<_>>(),
+            vec![
+                "JapanesePod101_猫「ねこ」.mp3",
+                "JapanesePod101_鳥「とり」.mp3",
+                "other.mp3"
+            ]
+        );
+    }
+}
@@ -1,31 +1,66 @@
-// cargo run --bin dictionary
-
-use std::{cmp::Ordering, collections::HashMap, fs, path::Path};
+use std::{cmp::Ordering, collections::HashMap, fs};
 
 use anki_utill::{
+    config::Config,
     dict::{
-        dict_parser::{convert_data, convert_word_data, parse_directory},
+        dict_cache::{hash_inputs, DictionaryCache, InputHashes},
+        dict_index::TagBank,
+        dict_parser::{
+            convert_archive_kanji, convert_archive_words, open_directory, WordConverter,
+        },
+        edrdg::{
+            edrdg_entry::{convert_xml_kanji, convert_xml_words, xml_files},
+            edrdg_radicals::{component_files, convert_component_kanji},
+        },
         jitendex::jitendex_word::JitendexWord,
-        jmnedict::jmnedict_entry::JmnedictEntry,
+        jmnedict::{jmnedict_entry::JmnedictEntry, jmnedict_word::remap_tag},
+        progress::PrintProgress,
     },
     entry::Word,
     japanese::JapaneseStr,
 };
 use regex::Regex;
 
-fn main() {
+use super::{Options, Response};
+
+/// Builds the word and kanji lists from the configured dictionaries and saves them as JSON.
+/// The converted dictionaries are kept in a binary index and only converted again when an
+/// archive changes. Nothing is saved on a dry run.
+pub fn run(config: &Config, options: &Options) -> Response<()> {
     // Define output file paths
-    let wordlist_save_path = "./result/wordlist.json";
-    let kanjilist_save_path = "./result/kanjilist.json";
+    let wordlist_save_path = &config.paths.wordlist;
+    let kanjilist_save_path = &config.paths.kanjilist;
+    let tags_save_path = &config.paths.tags;
+
+    // Reuse the compiled index while the dictionaries are unchanged
+    let inputs = hash_inputs(&[
+        &config.paths.dictionaries,
+        &config.paths.examples,
+        &config.paths.components,
+    ])?;
+    let index = match DictionaryCache::load(&config.paths.index, &inputs) {
+        Some(index) => {
+            println!("Using index {}\n", config.paths.index.display());
+            index
+        }
+        None => {
+            let index = build_index(config, inputs)?;
 
-    // Parse dictionary entries from jmnedict directory
-    let entries = parse_directory::<JmnedictEntry>(Path::new("./input/dictionaries")).unwrap();
-    let (kanji, words) = convert_data(&entries);
+            if !options.dry_run {
+                println!("Saving index to {}\n", config.paths.index.display());
+                index.write(&config.paths.index)?;
+            }
 
-    // Parse example sentences from jitendex directory
-    println!("Parsing examples:");
-    let entries = parse_directory::<JitendexWord>(Path::new("./input/examples")).unwrap();
-    let exampes = convert_word_data(&kanji, &entries);
+            index
+        }
+    };
+    let DictionaryCache {
+        words,
+        kanji,
+        tags,
+        examples: exampes,
+        ..
+    } = index;
 
     println!("Filtering words...");
     // Filter words based on the filter_words function
@@ -39,8 +74,8 @@ fn main() {
 
     // Add examples to filtered words if they exist in the examples data
     for (_, word) in words.iter_mut() {
-        if let Some(example) = exampes.get(&word.furigana) {
-            word.examples = example.examples.clone();
+        if let Some(examples) = exampes.get(&word.furigana) {
+            word.examples = examples.clone();
         }
     }
 
@@ -52,8 +87,18 @@ fn main() {
         ((words.len() as f32 / before_count as f32) * 1000.0).round() / 10.0
     );
 
+    if options.dry_run {
+        println!(
+            "Would save {} words, {} kanji and {} tags",
+            words.len(),
+            kanji.len(),
+            tags.len()
+        );
+        return Ok(());
+    }
+
     // Save filtered word data to JSON file
-    println!("Saving result to {}\n", wordlist_save_path);
+    println!("Saving result to {}\n", wordlist_save_path.display());
 
     let save_data: Vec<String> = words
         .iter()
@@ -63,11 +108,10 @@ fn main() {
     fs::write(
         wordlist_save_path,
         format!("{{\n{}\n}}", save_data.join(",\n")),
-    )
-    .unwrap();
+    )?;
 
     // Save kanji data to JSON file
-    println!("Saving result to {}\n", kanjilist_save_path);
+    println!("Saving result to {}\n", kanjilist_save_path.display());
 
     let save_data: Vec<String> = kanji
         .iter()
@@ -77,8 +121,50 @@ fn main() {
     fs::write(
         kanjilist_save_path,
         format!("{{\n{}\n}}", save_data.join(",\n")),
-    )
-    .unwrap();
+    )?;
+
+    // Save tag definitions to JSON file
+    println!("Saving result to {}\n", tags_save_path.display());
+    fs::write(tags_save_path, serde_json::to_string_pretty(&tags)?)?;
+
+    Ok(())
+}
+
+/// Converts the configured dictionaries and example dictionaries into an index.
+fn build_index(config: &Config, inputs: InputHashes) -> Response<DictionaryCache> {
+    let mut progress = PrintProgress::default();
+
+    // Read the Yomitan dictionaries bank by bank and the EDRDG XML files entry by entry
+    let mut dictionaries = open_directory(&config.paths.dictionaries)?;
+    let xml = xml_files(&config.paths.dictionaries)?;
+
+    let mut kanji = convert_archive_kanji::<JmnedictEntry, _>(&mut dictionaries, &mut progress)?;
+    convert_xml_kanji(&xml, &mut kanji, &mut progress)?;
+
+    // Attach the radicals and components of the kanji
+    let components = component_files(&config.paths.components)?;
+    convert_component_kanji(&components, &mut kanji, &mut progress)?;
+
+    let mut converter = WordConverter::new(&kanji);
+    converter.add_archives::<JmnedictEntry, _>(&mut dictionaries, &mut progress)?;
+    convert_xml_words(&xml, &mut converter, &mut progress)?;
+    let words = converter.finish();
+
+    // Collect the tag definitions under the names tags get on words
+    let mut tags = TagBank::default();
+    for dictionary in dictionaries {
+        tags.extend(dictionary.tags.renamed(remap_tag));
+    }
+
+    // Parse example sentences from jitendex directory
+    println!("Parsing examples:");
+    let mut examples = open_directory(&config.paths.examples)?;
+    let examples = convert_archive_words::<JitendexWord, _>(&kanji, &mut examples, &mut progress)?
+        .into_values()
+        .map(|word| (word.furigana, word.examples))
+        .collect();
+
+    Ok(DictionaryCache::new(inputs, words, kanji, tags, examples))
 }
 
 /// Filters words based on JLPT tags and compound status.
@@ -0,0 +1,605 @@
+use std::collections::HashMap;
+use std::io::Write;
+use std::ops::Range;
+use std::{collections::HashSet, error::Error, io, time};
+
+use anki_utill::tatoeba::tatoeba_search::{TatoebaOrigin, TatoebaSort};
+use anki_utill::{
+    anki::{anki_connect::AnkiConnect, anki_connect_error::AnkiConnectError, anki_note::AnkiNote},
+    config::{Config, WordFields},
+    japanese::{conjugate, deinflect, JapaneseStr, WordClass},
+    tatoeba::{
+        tatoeba_cache::TatoebaCache,
+        tatoeba_corpus::TatoebaCorpus,
+        tatoeba_search::{TatoebaEntry, TatoebaSearch},
+    },
+};
+use regex::{Captures, Regex};
+
+use super::{Options, Response};
+
+/// Entry point of the examples stage.
+///
+/// Builds five `TatoebaSearch` configurations, connects to an
+/// Anki‑deck and iterates over all notes that match a given query.
+/// The sentences are searched in the offline Tatoeba corpus when its
+/// exports are in `paths.tatoeba_corpus`, otherwise through the API.
+/// API responses are saved in the Tatoeba cache, so re-runs only fetch
+/// searches not seen within `tatoeba.cache_days`.
+/// For each note it calls [`process_note`] to enrich the sentences
+/// field with up to *count* new examples.  
+/// Progress is reported every ~2 % of the total notes.
+/// Fails if any of the notes could not be updated.
+pub fn run(config: &Config, options: &Options) -> Response<()> {
+    let mut search1 = TatoebaSearch::new("jpn", "eng");
+    search1.word_count = (Some(3), Some(30));
+    search1.is_orphan = Some(false);
+    search1.is_unapproved = Some(false);
+    search1.is_native = Some(true);
+    search1.origin = Some(TatoebaOrigin::Original);
+
+    search1.trans_is_direct = Some(true);
+    search1.trans_is_orphan = Some(false);
+    search1.trans_is_unapproved = Some(false);
+    search1.trans_count = Some(true);
+
+    search1.sort = Some(TatoebaSort::Shortest);
+    search1.limit = Some(25);
+
+    let mut search2 = search1.clone();
+    search2.origin = None;
+
+    let mut search3 = search1.clone();
+    search3.is_orphan = None;
+    search3.is_unapproved = None;
+    search3.trans_is_orphan = None;
+    search3.trans_is_unapproved = None;
+
+    let mut search4 = search3.clone();
+    search4.origin = None;
+
+    let mut search5 = search4.clone();
+    search5.is_native = None;
+
+    let search = vec![search1, search2, search3, search4, search5];
+    /* let search = vec![search5]; */
+    let source = ExampleSource::from_config(config, options.dry_run)?;
+
+    // Connect to Anki and fetch note information
+    let fields = &config.fields;
+
+    println!("Fetching anki info");
+    let anki = config.connect()?;
+    let notes = anki.notes_info(&anki.find_notes(&options.note_query(config))?)?;
+    let notes = options.limited(&notes);
+
+    /* let note = notes
+        .iter()
+        .filter(|note| note.fields[&fields.sentences].is_empty())
+        .nth(1)
+        .unwrap();
+    process_note(&anki, &search, fields, note, 15, false); */
+    /* process_note(&anki, &search, fields, &notes[0], 15, false); */
+
+    println!("Adding examples to {} notes. ", notes.len());
+    let mut failed = 0;
+    for (i, note) in notes.iter().enumerate() {
+        // Progress tracking every 2% of total notes
+        if i % (notes.len() / 50).max(1) == 0 {
+            print!(
+                "\n{:>3}% Notes ",
+                ((i as f32 / notes.len() as f32) * 100.0).round()
+            );
+            io::stdout().flush().unwrap();
+        }
+
+        print!("|");
+
+        if let Err(err) = process_note(&anki, &search, &source, fields, note, 15, options.dry_run) {
+            print!("\nFailed to update {}: {}", note.fields[&fields.word], err);
+            failed += 1;
+        }
+    }
+
+    println!();
+    if failed > 0 {
+        return Err(format!("{} notes failed", failed).into());
+    }
+
+    Ok(())
+}
+
+/// Where the example sentences are searched.
+enum ExampleSource {
+    /// The Tatoeba API, through the response cache.
+    Api(TatoebaCache),
+    /// The offline Tatoeba corpus.
+    Corpus(Box<TatoebaCorpus>),
+}
+
+impl ExampleSource {
+    /// Reads the Tatoeba corpus if its exports are present, otherwise uses the API. A dry run
+    /// does not save new responses of the API.
+    fn from_config(config: &Config, dry_run: bool) -> Response<Self> {
+        let dir = &config.paths.tatoeba_corpus;
+        let exports = ["sentences_detailed.csv", "sentences.csv"];
+        if exports.iter().any(|name| dir.join(name).is_file()) {
+            println!("Reading Tatoeba corpus");
+            let corpus = TatoebaCorpus::open(dir, &["jpn", "eng"])?;
+            return Ok(Self::Corpus(Box::new(corpus)));
+        }
+
+        let cache = TatoebaCache::new(&config.paths.tatoeba_cache, config.tatoeba.cache_ttl());
+        Ok(Self::Api(if dry_run { cache.read_only() } else { cache }))
+    }
+
+    /// Returns the sentences matching a search, fetching the pages of the API lazily.
+    fn search<'a>(
+        &'a self,
+        search: &'a TatoebaSearch,
+        word: &'a str,
+    ) -> Box<dyn Iterator<Item = Result<TatoebaEntry, Box<dyn Error>>> + 'a> {
+        match self {
+            Self::Api(cache) => Box::new(search.search_iter_cached(
+                cache,
+                word,
+                Some(time::Duration::from_millis(333)),
+            )),
+            Self::Corpus(corpus) => Box::new(corpus.search(search, word).into_iter().map(Ok)),
+        }
+    }
+}
+
+/// Augments a single Anki note with additional example sentences.
+///
+/// The function first parses any examples that are already present in the
+/// sentences field to avoid duplicates.  It then iterates over each
+/// `TatoebaSearch` configuration until either *count* examples have been
+/// collected or all searches are exhausted.  Each candidate example is
+/// filtered for duplicate content, language correctness and proper
+/// conjugation highlighting before being appended.
+///
+/// # Arguments
+///
+/// * `anki` – Connection used to update the note.
+/// * `search` – Slice of `TatoebaSearch` objects that provide query settings.
+/// * `source` – Where the searches are answered.
+/// * `fields` – The names of the note fields.
+/// * `note` – The Anki note to be processed.
+/// * `count` – Maximum number of examples to keep in the field.
+/// * `dry_run` – Only print the examples instead of writing them to Anki.
+fn process_note(
+    anki: &AnkiConnect,
+    search: &[TatoebaSearch],
+    source: &ExampleSource,
+    fields: &WordFields,
+    note: &AnkiNote,
+    count: usize,
+    dry_run: bool,
+) -> Result<(), AnkiConnectError> {
+    // Parse already stored examples so we can avoid duplicates.
+    let mut examples = parse_examples(fields, note);
+    /* let mut examples: Vec<(String, String)> = Vec::new(); */
+    let mut filter: HashSet<String> = examples.iter().map(|(jp, _)| get_filter_key(jp)).collect();
+
+    for search in search {
+        if examples.len() >= count {
+            break;
+        }
+
+        // Indicate progress to the user.
+        print!("+");
+        io::stdout().flush().unwrap();
+
+        // Retrieve sentences matching the target word.
+        for example in source.search(search, &note.fields[&fields.word]) {
+            // A failed search ends, the next search may still find examples
+            let example = match example {
+                Ok(example) => example,
+                Err(err) => {
+                    print!(
+                        "\nTatoeba search for {} failed: {}",
+                        note.fields[&fields.word], err
+                    );
+                    break;
+                }
+            };
+
+            /* --- Build transcription candidate -------------------------------- */
+            let mut transcriptions: Vec<String> = example
+                .transcriptions
+                .into_iter()
+                .filter_map(|e| format_tatoeba_response(fields, note, &e.text))
+                .collect();
+
+            if transcriptions.is_empty() {
+                continue;
+            }
+
+            // Prefer the longest transcription (most complete).
+            transcriptions.sort_unstable_by_key(|e| std::cmp::Reverse(e.len()));
+            let transcription = transcriptions.remove(0);
+
+            /* --- Build translation candidate --------------------------------- */
+            let mut translations: Vec<String> = example
+                .translations
+                .into_iter()
+                .flatten()
+                .map(|e| e.text)
+                .collect();
+
+            if translations.is_empty() {
+                continue;
+            }
+
+            translations.sort_unstable_by_key(|e| std::cmp::Reverse(e.len()));
+            let translation = translations.remove(0);
+
+            /* --- Avoid duplicate or overly similar examples ----------------- */
+            let key = get_filter_key(&transcription);
+
+            if filter.contains(&key) {
+                continue;
+            } else {
+                filter.insert(key);
+            }
+
+            examples.push((transcription, translation));
+
+            print!("-");
+            io::stdout().flush().unwrap();
+
+            if examples.len() >= count {
+                break;
+            }
+        }
+    }
+
+    /* ----------------------------------------------------------------------- */
+    // Inform the user how many examples were added when we fell short.
+    if examples.len() < count {
+        print!("{}", examples.len());
+    }
+
+    /* println!("\nWord: ");
+    for (jp, en) in &examples {
+        println!("\n{jp}\n{en}");
+    } */
+
+    /* ----------------------------------------------------------------------- */
+    // Join all examples into a single string and write back to Anki.
+    let examples = examples
+        .iter()
+        .map(|(jp, en)| format!("{jp}<br>{en}"))
+        .reduce(|a, b| a + "<br><br>" + &b)
+        .unwrap_or("".to_owned());
+
+    if dry_run {
+        print!("\n{}: {}", note.fields[&fields.word], examples);
+        return Ok(());
+    }
+
+    let mut update: HashMap<String, String> = HashMap::new();
+    update.insert(fields.sentences.clone(), examples);
+    anki.update_note_fields(note.noteId.unwrap(), &update)
+}
+
+/// Creates a “filter key” used to de‑duplicate example sentences.
+///
+/// The key is produced by:
+/// 1. Removing all whitespace.
+/// 2. Stripping any HTML tags.
+/// 3. Keeping only kanji characters (the rest are discarded).
+///
+/// This keeps the comparison lightweight while still distinguishing
+/// distinct examples that differ in content or form.
+fn get_filter_key(str: &str) -> String {
+    let regex = Regex::new(r"\s").unwrap();
+    let str = strip_html(str).to_kanji();
+    regex.replace_all(&str, "").to_string()
+}
+
+/// Parses the sentences field of a note into `(jp, en)` pairs.
+///
+/// The parsing logic follows the format produced by this program:
+/// * `&nbsp;` → space
+/// * `<br>`   → newline
+/// * Lines are split into Japanese and English parts using a regex that
+///   captures optional English translation.
+///
+/// Returns an empty vector if the field contains no examples.
+fn parse_examples(fields: &WordFields, note: &AnkiNote) -> Vec<(String, String)> {
+    let str = &note.fields[&fields.sentences];
+
+    // Replace HTML‑specific entities with plain text.
+    let regex = Regex::new(r"&nbsp;").unwrap();
+    let str = regex.replace_all(str, " ").to_string();
+
+    let regex = Regex::new(r"<br>").unwrap();
+    let str = regex.replace_all(&str, "\n").to_string();
+
+    // Capture a Japanese line and an optional English translation.
+    let regex =
+        Regex::new(r"(?:^|\n)[ 	]*([^\n| 	][^\n|]*?)[ 	]*(?:\n[ 	]*([^\n]+?)[ 	]*)?(?:\n|$)")
+            .unwrap();
+    let matches = regex.captures_iter(&str);
+
+    let out = matches
+        .filter_map(|mat| {
+            let jp = mat[1].to_owned();
+            let en = mat.get(2)?.as_str().to_owned();
+
+            // Highlight the target word in the Japanese example.
+            let jp = rehighlight_word(fields, note, &jp);
+
+            jp.map(|jp| (jp, en))
+        })
+        .collect();
+
+    out
+}
+
+/// Formats a raw Tatoeba transcription into the same style as used by this program.
+///
+/// * Removes alphabetic characters (English words are ignored).
+/// * Replaces `[kanji|kana]` syntax with “kanji [kana]” highlighting.
+/// * Finally highlights the target word within the note.
+fn format_tatoeba_response(fields: &WordFields, note: &AnkiNote, str: &str) -> Option<String> {
+    // Ignore transcriptions that contain English letters.
+    let regex = Regex::new(r"[A-Za-z]").unwrap();
+    if regex.is_match(str) {
+        return None;
+    }
+
+    // Replace `[kanji|kana]` syntax with highlighted form.
+    let regex = Regex::new(r"\[((?:[^\[\]\|]+\|?)+)\]").unwrap();
+
+    let str = regex
+        .replace_all(str, |caps: &Captures| -> String {
+            let caps = &caps[1];
+            let mut caps: Vec<&str> = caps.split('|').collect();
+
+            let kanji = caps.remove(0);
+            let kana = caps;
+
+            let out: String = kanji
+                .chars()
+                .zip(kana.iter().chain(std::iter::repeat(&"")))
+                .map(|(kanji, kana)| {
+                    if kana.is_empty() {
+                        format!("{kanji}")
+                    } else {
+                        format!(" {kanji}[{kana}]")
+                    }
+                })
+                .collect();
+
+            out
+        })
+        .to_string();
+
+    let regex = Regex::new("] +").unwrap();
+    let str = regex.replace_all(&str, "]").to_string().trim().to_owned();
+
+    highlight_word(fields, note, &str)
+}
+
+fn rehighlight_word(fields: &WordFields, note: &AnkiNote, str: &str) -> Option<String> {
+    highlight_word(fields, note, &strip_html(str))
+}
+
+/// Wraps every occurrence of the note's word in a sentence in furigana format with `<b>` tags.
+///
+/// Inflected verbs and adjectives are found with [`deinflect`], using the class given by the
+/// note's tags. Returns `None` if the word does not occur.
+fn highlight_word(fields: &WordFields, note: &AnkiNote, str: &str) -> Option<String> {
+    let forms = get_word_forms(fields, note);
+    // Every form of the word starts with one of the stems
+    let stems: Vec<String> = forms
+        .iter()
+        .map(|(form, class)| get_stem(form, *class))
+        .collect();
+
+    let units = split_units(str);
+    let mut out = String::new();
+    let mut last = 0;
+    let mut start = 0;
+
+    while start < units.len() {
+        let mut text = String::new();
+        let mut found = None;
+
+        // Find the longest run of units that is a form of the word
+        for (end, (_, kanji)) in units.iter().enumerate().skip(start).take(MAX_UNITS) {
+            text += kanji;
+
+            let has_stem = stems.iter().any(|stem| text.starts_with(stem.as_str()));
+            if !has_stem && !stems.iter().any(|stem| stem.starts_with(&text)) {
+                break;
+            }
+
+            if has_stem && is_word_form(&forms, &text) {
+                found = Some(end);
+            }
+        }
+
+        if let Some(end) = found {
+            let range = units[start].0.start..units[end].0.end;
+            out += &str[last..range.start];
+            out += &format!("<b>{}</b>", &str[range.clone()]);
+            last = range.end;
+            start = end + 1;
+        } else {
+            start += 1;
+        }
+    }
+
+    if last == 0 {
+        None
+    } else {
+        Some(out + &str[last..])
+    }
+}
+
+/// The maximum number of units an inflected word spans.
+const MAX_UNITS: usize = 16;
+
+/// Removes any HTML tags from a string.
+///
+/// Used to strip formatting before further processing.
+fn strip_html(str: &str) -> String {
+    let regex = Regex::new(r"<[^<>]*>").unwrap();
+    regex.replace_all(str, "").to_string()
+}
+
+/// Splits a sentence in furigana format into units: kanji blocks together with their reading
+/// and leading space, and single characters.
+///
+/// Returns the byte range and the kanji text of every unit.
+fn split_units(str: &str) -> Vec<(Range<usize>, String)> {
+    let regex = Regex::new(r" ?([^\s\[\]]+?)\[[^\s\[\]]+?\]").unwrap();
+    let mut out = Vec::new();
+    let mut last = 0;
+
+    let push_chars = |out: &mut Vec<(Range<usize>, String)>, from: usize, to: usize| {
+        for (i, char) in str[from..to].char_indices() {
+            let start = from + i;
+            out.push((start..start + char.len_utf8(), char.to_string()));
+        }
+    };
+
+    for caps in regex.captures_iter(str) {
+        let block = caps.get(0).unwrap();
+        push_chars(&mut out, last, block.start());
+        out.push((block.range(), caps[1].to_owned()));
+        last = block.end();
+    }
+    push_chars(&mut out, last, str.len());
+
+    out
+}
+
+/// Returns whether a text in kanji form is the word or one of its inflected forms.
+fn is_word_form(forms: &[(String, Option<WordClass>)], text: &str) -> bool {
+    if forms.iter().any(|(form, _)| form == text) {
+        return true;
+    }
+    if forms.iter().all(|(_, class)| class.is_none()) {
+        return false;
+    }
+
+    deinflect(text).iter().any(|candidate| {
+        forms.iter().any(|(form, class)| {
+            class.is_some() && candidate.term == *form && candidate.matches(*class)
+        })
+    })
+}
+
+/// Returns the part of a dictionary form that does not change when inflected.
+fn get_stem(form: &str, class: Option<WordClass>) -> String {
+    let ending = match class {
+        None | Some(WordClass::NaAdjective) => "",
+        Some(WordClass::Suru) => "する",
+        Some(_) => {
+            let end = form.chars().last().unwrap();
+            &form[form.len() - end.len_utf8()..]
+        }
+    };
+
+    form.strip_suffix(ending).unwrap_or(form).to_owned()
+}
+
+/// Returns the forms of the note's word in kanji form, each with the class used to deinflect it.
+///
+/// The class is taken from the note's tags, see [`WordClass::from_tags`]. The conjugation table
+/// of the word is included as written, so な‑adjectives and the irregular forms of ある and いい
+/// are found too. A する noun also matches on its own.
+fn get_word_forms(fields: &WordFields, note: &AnkiNote) -> Vec<(String, Option<WordClass>)> {
+    let word = note.fields[&fields.word].to_kanji();
+    let class = WordClass::from_tags(note.tags.iter().map(String::as_str));
+
+    let mut forms = match class {
+        Some(WordClass::Suru) if !word.ends_with("する") => {
+            vec![(word.clone(), None), (word.clone() + "する", class)]
+        }
+        Some(WordClass::NaAdjective) => vec![(word.clone(), None)],
+        _ => vec![(word.clone(), class)],
+    };
+
+    if let Some(table) = class.and_then(|class| conjugate(&word, class)) {
+        forms.extend(
+            table
+                .into_iter()
+                .map(|conjugation| (conjugation.form, None)),
+        );
+    }
+
+    forms
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Creates a word note with the given tags.
+    fn note(word: &str, tags: &[&str]) -> AnkiNote {
+        AnkiNote {
+            tags: tags.iter().map(|tag| tag.to_string()).collect(),
+            fields: HashMap::from([(WordFields::default().word, word.to_owned())]),
+            ..AnkiNote::default()
+        }
+    }
+
+    /// Tests that inflected forms of the word are highlighted, including their furigana.
+    #[test]
+    fn highlight_inflected_words() {
+        let fields = WordFields::default();
+        let highlight =
+            |word: &str, tags: &[&str], str: &str| highlight_word(&fields, &note(word, tags), str);
+
+        assert_eq!(
+            highlight(
+                "食[た]べる",
+                &["v1"],
+                "私[わたし]は 魚[さかな]を 食[た]べさせられた。"
+            ),
+            Some("私[わたし]は 魚[さかな]を<b> 食[た]べさせられた</b>。".to_owned())
+        );
+        assert_eq!(
+            highlight("行[い]く", &["v5く-s"], "学校[がっこう]に 行[い]った。"),
+            Some("学校[がっこう]に<b> 行[い]った</b>。".to_owned())
+        );
+        assert_eq!(
+            highlight("来[く]る", &["vくる"], "彼[かれ]は 来[き]ませんでした。"),
+            Some("彼[かれ]は<b> 来[き]ませんでした</b>。".to_owned())
+        );
+        assert_eq!(
+            highlight(
+                "勉強[べんきょう]",
+                &["n", "vする-s"],
+                " 勉強[べんきょう]している。"
+            ),
+            Some("<b> 勉強[べんきょう]している</b>。".to_owned())
+        );
+        assert_eq!(
+            highlight("猫[ねこ]", &["n"], "猫[ねこ]が 好[す]きです。"),
+            Some("<b>猫[ねこ]</b>が 好[す]きです。".to_owned())
+        );
+
+        assert_eq!(
+            highlight("静[しず]か", &["adj-な"], " 静[しず]かではなかった。"),
+            Some("<b> 静[しず]かではなかった</b>。".to_owned())
+        );
+        assert_eq!(
+            highlight("いい", &["adj-いx"], "よかったです。"),
+            Some("<b>よかったです</b>。".to_owned())
+        );
+
+        // Godan verbs with endings that used to panic are only matched as written
+        assert_eq!(
+            highlight("ふ", &["v5ふ"], "ふふ"),
+            Some("<b>ふ</b><b>ふ</b>".to_owned())
+        );
+        assert_eq!(highlight("犬[いぬ]", &["n"], "猫[ねこ]"), None);
+    }
+}
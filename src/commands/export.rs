@@ -0,0 +1,168 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+};
+
+use anki_utill::{
+    anki::apkg::{ApkgDeck, ApkgField, ApkgModel, ApkgTemplate, NoteField},
+    config::Config,
+    entry::{parse_wordlist, Kanji, Word},
+    kanjivg::{self, KanjiVg},
+};
+
+use super::{strokes::load_diagrams, Options, Response};
+
+/// Loads the word and kanji lists and writes them to an offline `.apkg` package that can be
+/// imported into Anki without AnkiConnect. Exports at most `limit` words.
+pub fn run(config: &Config, options: &Options) -> Response<()> {
+    let wordlist_save_path = &config.paths.wordlist;
+    let kanjilist_save_path = &config.paths.kanjilist;
+    let package_save_path = &config.paths.package;
+    let (word_fields, kanji_fields) = (&config.fields, &config.kanji_fields);
+
+    println!("Loading words from {}", wordlist_save_path.display());
+    let data = fs::read_to_string(wordlist_save_path)?;
+    let words = parse_wordlist(&data)?;
+
+    println!("Loading kanji from {}", kanjilist_save_path.display());
+    let data = fs::read_to_string(kanjilist_save_path)?;
+    let kanji_data: HashMap<char, Kanji> = serde_json::from_str(&data)?;
+
+    // The stroke order fields are only exported together with the KanjiVG diagrams
+    let mut kanjivg = None;
+    if word_fields.stroke_order.is_some() || kanji_fields.stroke_order.is_some() {
+        if config.paths.kanjivg.exists() {
+            println!("Opening KanjiVG at {}", config.paths.kanjivg.display());
+            kanjivg = Some(KanjiVg::open(&config.paths.kanjivg)?);
+        } else {
+            println!(
+                "No KanjiVG found at {}, leaving out the stroke order fields.",
+                config.paths.kanjivg.display()
+            );
+        }
+    }
+
+    let mut fields = vec![
+        ApkgField::new(&word_fields.word, NoteField::Word),
+        ApkgField::new(&word_fields.meaning, NoteField::Meaning),
+        ApkgField::new(&word_fields.audio, NoteField::Audio),
+        ApkgField::new(&word_fields.sentences, NoteField::Sentences),
+    ];
+    if let Some(romaji) = &word_fields.romaji {
+        fields.push(ApkgField::new(romaji, NoteField::Romaji));
+    }
+    if let Some(pitch) = &word_fields.pitch {
+        fields.push(ApkgField::new(pitch, NoteField::Pitch));
+    }
+    if let Some(conjugations) = &word_fields.conjugations {
+        fields.push(ApkgField::new(conjugations, NoteField::Conjugations));
+    }
+    if let (Some(stroke_order), Some(_)) = (&word_fields.stroke_order, &kanjivg) {
+        fields.push(ApkgField::new(stroke_order, NoteField::StrokeOrder));
+    }
+
+    let word_model = ApkgModel::new(
+        1_739_000_000_001,
+        &config.deck.word_model,
+        fields,
+        vec![ApkgTemplate::new(
+            "Recognition",
+            &format!("<div class=jp>{{{{kanji:{}}}}}</div>", word_fields.word),
+            &format!(
+                "<div class=jp>{{{{furigana:{}}}}}</div>{{{{{}}}}}<hr id=answer>{{{{{}}}}}<br><br>{{{{furigana:{}}}}}",
+                word_fields.word, word_fields.audio, word_fields.meaning, word_fields.sentences
+            ),
+        )],
+    );
+
+    let mut fields = vec![
+        ApkgField::new(&kanji_fields.kanji, NoteField::Kanji),
+        ApkgField::new(&kanji_fields.meaning, NoteField::KanjiMeaning),
+        ApkgField::new(&kanji_fields.onyomi, NoteField::Onyomi),
+        ApkgField::new(&kanji_fields.kunyomi, NoteField::Kunyomi),
+        ApkgField::new(&kanji_fields.strokes, NoteField::Strokes),
+    ];
+    if let Some(components) = &kanji_fields.components {
+        fields.push(ApkgField::new(components, NoteField::Components));
+    }
+    if let (Some(stroke_order), Some(_)) = (&kanji_fields.stroke_order, &kanjivg) {
+        fields.push(ApkgField::new(stroke_order, NoteField::StrokeOrder));
+    }
+
+    let kanji_model = ApkgModel::new(
+        1_739_000_000_002,
+        &config.deck.kanji_model,
+        fields,
+        vec![ApkgTemplate::new(
+            "Recognition",
+            &format!("<div class=jp>{{{{{}}}}}</div>", kanji_fields.kanji),
+            &format!(
+                "{{{{FrontSide}}}}<hr id=answer>{{{{{}}}}}<br>{{{{{}}}}}<br>{{{{{}}}}}<br>{{{{{}}}}}",
+                kanji_fields.meaning, kanji_fields.onyomi, kanji_fields.kunyomi, kanji_fields.strokes
+            ),
+        )],
+    );
+
+    let mut deck = ApkgDeck::new(1_739_000_000_000, &config.deck.name);
+    let word_model_id = word_model.id;
+    let kanji_model_id = kanji_model.id;
+    deck.add_model(word_model);
+    deck.add_model(kanji_model);
+
+    // Sort the entries so that repeated exports produce the same card order
+    let mut words: Vec<&Word> = words.values().collect();
+    words.sort_unstable_by(|a, b| a.furigana.cmp(&b.furigana));
+    let words = options.limited(&words);
+
+    // Only export kanji that are used by at least one of the words
+    let used: HashSet<char> = words
+        .iter()
+        .flat_map(|word| word.furigana.chars())
+        .collect();
+    let mut kanji: Vec<&Kanji> = kanji_data
+        .values()
+        .filter(|kanji| used.contains(&kanji.kanji))
+        .collect();
+    kanji.sort_unstable_by_key(|kanji| kanji.kanji);
+
+    if options.dry_run {
+        println!(
+            "Would export {} words and {} kanji",
+            words.len(),
+            kanji.len()
+        );
+        return Ok(());
+    }
+
+    println!("Adding {} words and {} kanji", words.len(), kanji.len());
+    for word in words {
+        deck.add_word(word_model_id, word)?;
+    }
+
+    for kanji in &kanji {
+        deck.add_kanji(kanji_model_id, kanji)?;
+    }
+
+    if let Some(kanjivg) = &mut kanjivg {
+        let used = used.into_iter().filter(|c| kanjivg::is_kanji(*c));
+        let diagrams = load_diagrams(kanjivg, used, &kanji_data)?;
+
+        println!("Adding {} stroke order diagrams", diagrams.len());
+        for (c, diagram) in &diagrams {
+            if word_fields.stroke_order.is_some() {
+                deck.add_media(&kanjivg::media_name(*c), diagram.svg.clone().into_bytes());
+            }
+            if kanji_fields.stroke_order.is_some() && kanji_data.contains_key(c) {
+                deck.add_media(
+                    &kanjivg::strip_media_name(*c),
+                    diagram.strip_svg().into_bytes(),
+                );
+            }
+        }
+    }
+
+    println!("Saving result to {}\n", package_save_path.display());
+    deck.write(package_save_path)?;
+
+    Ok(())
+}
@@ -0,0 +1,424 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+};
+
+use anki_utill::{
+    anki::{
+        anki_changeset::{Changeset, FieldDiff, NoteChange},
+        anki_connect::AnkiConnect,
+        anki_connect_error::AnkiConnectError,
+        anki_note::{AnkiNote, ID},
+        apkg::NoteField,
+    },
+    config::Config,
+    dict::dict_index::TagBank,
+    entry::Kanji,
+};
+
+use super::{add::report_failures, load_tags, Options, Response};
+
+/// The maximum number of words shown on a kanji note.
+const EXAMPLE_COUNT: usize = 5;
+
+/// Loads the kanji list and writes the kanji used by the word notes to Anki notes.
+/// Fails if any of the notes could not be written.
+pub fn run(config: &Config, options: &Options) -> Response<()> {
+    let kanjilist_save_path = &config.paths.kanjilist;
+
+    println!("Loading kanji from {}", kanjilist_save_path.display());
+    let data = fs::read_to_string(kanjilist_save_path)?;
+    let kanji: HashMap<char, Kanji> = serde_json::from_str(&data)?;
+    let tags = load_tags(config)?;
+
+    let anki = config.connect()?;
+
+    let failures = write_kanji(&anki, config, options, &kanji, &tags)?;
+    if !failures.is_empty() {
+        return Err(format!("{} notes failed", failures.len()).into());
+    }
+
+    Ok(())
+}
+
+/// Handles writing of kanji to Anki by first planning every change, then applying the
+/// changeset. Only the kanji used by the words of the word deck get a note.
+/// The changeset is printed in full on a dry run and not applied.
+/// Returns the kanji that failed to be written together with the error.
+fn write_kanji(
+    anki: &AnkiConnect,
+    config: &Config,
+    options: &Options,
+    kanji: &HashMap<char, Kanji>,
+    tags: &TagBank,
+) -> Response<Vec<(String, AnkiConnectError)>> {
+    println!("\nGetting word notes.");
+    let words = anki.notes_info(&anki.find_notes(&config.word_query())?)?;
+    let examples = example_words(&words, kanji, &config.fields.word);
+
+    println!("Getting Notes info.");
+    let notes = anki.notes_info(&anki.find_notes(&config.kanji_query())?)?;
+    let suspended: HashSet<ID> = anki
+        .find_cards(&format!("{} is:suspended", config.kanji_query()))?
+        .into_iter()
+        .collect();
+
+    println!("Planning changes.");
+    let changeset = plan_kanji(kanji, tags, &examples, &notes, &suspended, config, options);
+
+    if options.dry_run {
+        if options.json {
+            println!("{}", serde_json::to_string_pretty(&changeset)?);
+        } else {
+            println!("{}", changeset);
+        }
+        return Ok(Vec::new());
+    }
+
+    println!("  {}", changeset.summary());
+    if changeset.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    println!("Applying changes.");
+    let failures = changeset.apply(anki)?;
+
+    report_failures(&failures);
+    Ok(failures)
+}
+
+/// Collects the words of the word notes using each kanji, at most [`EXAMPLE_COUNT`] of them.
+/// Words of lower JLPT levels and shorter words come first.
+fn example_words(
+    notes: &[AnkiNote],
+    kanji: &HashMap<char, Kanji>,
+    word_field: &str,
+) -> HashMap<char, Vec<String>> {
+    let mut words: Vec<(u8, &str)> = notes
+        .iter()
+        .filter_map(|note| {
+            let word = note.fields.get(word_field)?;
+            let level = (1..=5)
+                .rev()
+                .find(|level| note.tags.contains(&format!("JLPT-N{}", level)))
+                .unwrap_or(0);
+
+            Some((level, word.as_str()))
+        })
+        .collect();
+    words.sort_unstable_by(|(level_a, a), (level_b, b)| {
+        level_b
+            .cmp(level_a)
+            .then(a.chars().count().cmp(&b.chars().count()))
+            .then(a.cmp(b))
+    });
+
+    let mut out: HashMap<char, Vec<String>> = HashMap::new();
+    for (_, word) in words {
+        for c in word.chars().filter(|c| kanji.contains_key(c)) {
+            let examples = out.entry(c).or_default();
+
+            if examples.len() < EXAMPLE_COUNT && !examples.iter().any(|w| w == word) {
+                examples.push(word.to_owned());
+            }
+        }
+    }
+
+    out
+}
+
+/// Returns the fields of the note of a kanji as `(name, value)` pairs, leaving out the optional
+/// fields the note type does not have.
+fn kanji_fields(
+    kanji: &Kanji,
+    tags: &TagBank,
+    examples: &[String],
+    config: &Config,
+) -> Vec<(String, String)> {
+    let field = &config.kanji_fields;
+
+    let mut out = vec![
+        (field.kanji.clone(), NoteField::Kanji.kanji_value(kanji)),
+        (field.meaning.clone(), kanji.meaning_html_with_tags(tags)),
+        (field.onyomi.clone(), NoteField::Onyomi.kanji_value(kanji)),
+        (field.kunyomi.clone(), NoteField::Kunyomi.kanji_value(kanji)),
+        (field.strokes.clone(), NoteField::Strokes.kanji_value(kanji)),
+    ];
+    if let Some(name) = &field.examples {
+        out.push((name.clone(), examples.join("<br>")));
+    }
+    if let Some(name) = &field.components {
+        out.push((name.clone(), NoteField::Components.kanji_value(kanji)));
+    }
+
+    out
+}
+
+/// Plans the changes bringing the Anki kanji notes in line with the kanji used by the words.
+///
+/// For each existing note:
+/// - Checks if the kanji is used by any word
+/// - Updates the fields that changed
+/// - Manages tags by removing old ones and adding new ones
+/// - Unsuspends the suspended cards of used kanji
+/// - Suspends the cards of kanji no longer used
+///
+/// Used kanji without a note are added, at most `limit` of them.
+fn plan_kanji(
+    kanji: &HashMap<char, Kanji>,
+    tags: &TagBank,
+    examples: &HashMap<char, Vec<String>>,
+    notes: &[AnkiNote],
+    suspended: &HashSet<ID>,
+    config: &Config,
+    options: &Options,
+) -> Changeset {
+    let kanji_field = &config.kanji_fields.kanji;
+
+    let mut changeset = Changeset::default();
+    let mut existing: HashSet<char> = HashSet::new();
+
+    for note in notes.iter() {
+        let note_id = note.noteId.unwrap();
+        let note_cards = note.cards.clone().unwrap_or_default();
+        let label = note.fields.get(kanji_field).cloned().unwrap_or_default();
+
+        let mut chars = label.chars();
+        let (Some(c), None) = (chars.next(), chars.next()) else {
+            continue;
+        };
+
+        let (Some(kanji_data), Some(words)) = (kanji.get(&c), examples.get(&c)) else {
+            // The kanji is not used by any word, suspend the note
+            let cards = note_cards
+                .into_iter()
+                .filter(|card| !suspended.contains(card))
+                .collect();
+            changeset.suspend(&label, cards);
+            continue;
+        };
+
+        let mut change = NoteChange::new(&label, note_id);
+
+        // Update the fields the note type has that changed
+        for (name, new) in kanji_fields(kanji_data, tags, words, config) {
+            match note.fields.get(&name) {
+                Some(old) if *old != new => change.fields.push(FieldDiff {
+                    field: name,
+                    old: old.clone(),
+                    new,
+                }),
+                _ => {}
+            }
+        }
+
+        // Manage tags: remove old ones and add new ones
+        change.remove_tags = note
+            .tags
+            .iter()
+            .filter(|tag| !kanji_data.tags.contains(*tag))
+            .cloned()
+            .collect();
+
+        change.add_tags = kanji_data
+            .tags
+            .iter()
+            .filter(|tag| !note.tags.contains(tag))
+            .cloned()
+            .collect();
+        change.add_tags.sort_unstable();
+
+        changeset.update(change);
+
+        // Unsuspend note if the kanji is used
+        let cards = note_cards
+            .into_iter()
+            .filter(|card| suspended.contains(card))
+            .collect();
+        changeset.unsuspend(&label, cards);
+
+        existing.insert(c);
+    }
+
+    // Add the missing kanji in a stable order
+    let mut missing: Vec<&Kanji> = examples
+        .keys()
+        .filter(|c| !existing.contains(c))
+        .filter_map(|c| kanji.get(c))
+        .collect();
+    missing.sort_unstable_by_key(|kanji| kanji.kanji);
+
+    for kanji_data in options.limited(&missing) {
+        let fields = kanji_fields(kanji_data, tags, &examples[&kanji_data.kanji], config)
+            .into_iter()
+            .collect();
+
+        let mut tags: Vec<String> = kanji_data.tags.iter().cloned().collect();
+        tags.sort_unstable();
+
+        changeset.add(
+            &kanji_data.kanji.to_string(),
+            AnkiNote {
+                modelName: config.deck.kanji_model.clone(),
+                deckName: config.deck.name.clone().into(),
+                tags,
+                fields,
+
+                ..AnkiNote::default()
+            },
+        );
+    }
+
+    changeset
+}
+
+#[cfg(test)]
+mod tests {
+    use anki_utill::{anki::anki_connect_mock::MockAnkiConnect, dict::dict_index::Tag};
+
+    use super::*;
+
+    /// Creates a kanji with a single meaning, reading and tag.
+    fn kanji(c: char, meaning: &str, onyomi: &str) -> (char, Kanji) {
+        let kanji = Kanji::new(
+            c,
+            HashSet::from([onyomi.to_owned()]),
+            HashSet::new(),
+            vec![meaning.to_owned()],
+            Some(4),
+            HashSet::from(["JLPT-N5".to_owned()]),
+        );
+
+        (c, kanji)
+    }
+
+    /// Returns a config using a kanji note type with an examples field.
+    fn config() -> Config {
+        let mut config = Config::default();
+        config.deck.name = "Japanese".to_owned();
+        config.kanji_fields.examples = Some("6 Examples".to_owned());
+        config
+    }
+
+    /// Tests that kanji notes are added and updated for the kanji of the word notes and the
+    /// notes of kanji no longer used are suspended.
+    #[test]
+    fn sync_kanji() {
+        let mock = MockAnkiConnect::start().unwrap();
+        let config = config();
+
+        let (sun, moon) = mock.with_collection(|collection| {
+            collection.add_deck("Japanese");
+            collection.add_model("JP Card V4", &["1 Word", "2 Meaning"]);
+            collection.add_model(
+                "JP Kanji V4",
+                &[
+                    "1 Kanji",
+                    "2 Meaning",
+                    "3 Onyomi",
+                    "4 Kunyomi",
+                    "5 Strokes",
+                    "6 Examples",
+                ],
+            );
+
+            for (word, tag) in [
+                ("日本[にほん]", "JLPT-N5"),
+                ("日[ひ]", "JLPT-N5"),
+                ("本日[ほんじつ]", "JLPT-N4"),
+            ] {
+                collection
+                    .add_note("Japanese", "JP Card V4", &[("1 Word", word)], &[tag])
+                    .unwrap();
+            }
+
+            let sun = collection
+                .add_note(
+                    "Japanese",
+                    "JP Kanji V4",
+                    &[("1 Kanji", "日"), ("2 Meaning", "old")],
+                    &["old-tag"],
+                )
+                .unwrap();
+            let moon = collection
+                .add_note("Japanese", "JP Kanji V4", &[("1 Kanji", "月")], &[])
+                .unwrap();
+
+            (sun, moon)
+        });
+
+        let kanji = HashMap::from([
+            kanji('日', "day", "ニチ"),
+            kanji('本', "book", "ホン"),
+            kanji('月', "month", "ゲツ"),
+        ]);
+
+        let tags = TagBank::from_iter([Tag::new(
+            "JLPT-N5".to_owned(),
+            "frequent".to_owned(),
+            0,
+            "JLPT level N5".to_owned(),
+            0,
+        )]);
+
+        let anki = mock.connect().unwrap();
+        assert!(
+            write_kanji(&anki, &config, &Options::default(), &kanji, &tags)
+                .unwrap()
+                .is_empty()
+        );
+
+        let collection = mock.collection();
+        let sun = collection.note(sun).unwrap();
+        assert_eq!(
+            sun.fields["2 Meaning"],
+            "day<br><br><small>JLPT-N5: JLPT level N5</small>"
+        );
+        assert_eq!(sun.fields["3 Onyomi"], "ニチ");
+        assert_eq!(
+            sun.fields["6 Examples"],
+            "日[ひ]<br>日本[にほん]<br>本日[ほんじつ]"
+        );
+        assert_eq!(sun.tags, vec!["JLPT-N5"]);
+
+        let moon = collection.note(moon).unwrap();
+        assert!(collection.card(moon.cards[0]).unwrap().suspended);
+
+        let book = collection.find_notes("\"1 Kanji:本\"");
+        assert_eq!(book.len(), 1);
+        assert!(collection
+            .field(book[0], "2 Meaning")
+            .unwrap()
+            .starts_with("book<br>"));
+        assert_eq!(
+            collection.field(book[0], "6 Examples"),
+            Some("日本[にほん]<br>本日[ほんじつ]")
+        );
+    }
+
+    /// Tests that the examples prefer easy words and are limited.
+    #[test]
+    fn examples() {
+        let note = |word: &str, tag: &str| AnkiNote {
+            tags: vec![tag.to_owned()],
+            fields: HashMap::from([("1 Word".to_owned(), word.to_owned())]),
+            ..AnkiNote::default()
+        };
+
+        let mut notes = vec![
+            note("日曜日[にちようび]", "JLPT-N5"),
+            note("日[ひ]", "JLPT-N3"),
+        ];
+        notes.extend((0..6).map(|i| note(&format!("日{}", i), "JLPT-N5")));
+
+        let examples = example_words(
+            &notes,
+            &HashMap::from([kanji('日', "day", "ニチ")]),
+            "1 Word",
+        );
+
+        assert_eq!(examples[&'日'].len(), EXAMPLE_COUNT);
+        assert_eq!(examples[&'日'][0], "日0");
+        assert!(!examples[&'日'].contains(&"日[ひ]".to_owned()));
+    }
+}
@@ -0,0 +1,139 @@
+pub mod add;
+pub mod audio;
+pub mod clean_media;
+pub mod dictionary;
+pub mod examples;
+pub mod export;
+pub mod kanji;
+pub mod order;
+pub mod strokes;
+
+use std::{error::Error, fmt, fs};
+
+use anki_utill::{config::Config, dict::dict_index::TagBank};
+
+/// Represents the result of a stage.
+pub type Response<T> = Result<T, Box<dyn Error>>;
+
+/// The options shared by every stage.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Options {
+    /// Only report what would be changed instead of writing to Anki or to disk.
+    pub dry_run: bool,
+    /// The maximum number of notes a stage processes.
+    pub limit: Option<usize>,
+    /// Extra search terms narrowing the notes a stage processes.
+    pub query: Option<String>,
+    /// Print reports as JSON instead of human readable text.
+    pub json: bool,
+}
+
+impl Options {
+    /// Returns the search query for the word notes, narrowed by the extra search terms.
+    pub fn note_query(&self, config: &Config) -> String {
+        match &self.query {
+            Some(query) => format!("{} ({})", config.word_query(), query),
+            None => config.word_query(),
+        }
+    }
+
+    /// Returns the first `limit` items, or all of them if there is no limit.
+    pub fn limited<'a, T>(&self, items: &'a [T]) -> &'a [T] {
+        let count = self
+            .limit
+            .map_or(items.len(), |limit| limit.min(items.len()));
+        &items[..count]
+    }
+}
+
+/// Loads the tag definitions saved by the `dictionary` stage, or no tags if there are none yet.
+pub fn load_tags(config: &Config) -> Response<TagBank> {
+    let path = &config.paths.tags;
+    if !path.exists() {
+        return Ok(TagBank::default());
+    }
+
+    println!("Loading tags from {}", path.display());
+    Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+}
+
+/// A stage of the pipeline.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stage {
+    /// Builds the word and kanji lists from the dictionaries.
+    Dictionary,
+    /// Adds and updates the word notes in Anki.
+    Add,
+    /// Adds and updates the kanji notes of the kanji used by the word notes.
+    Kanji,
+    /// Attaches the KanjiVG stroke order diagrams to the word and kanji notes.
+    Strokes,
+    /// Reorders the new cards in Anki.
+    Order,
+    /// Adds audio to notes without any.
+    Audio,
+    /// Adds example sentences from Tatoeba.
+    Examples,
+    /// Writes an offline `.apkg` package.
+    Export,
+    /// Deletes the media files of the stages no note of the deck references.
+    CleanMedia,
+}
+
+impl Stage {
+    /// The stages run by `all`, in order.
+    pub const PIPELINE: [Stage; 7] = [
+        Stage::Dictionary,
+        Stage::Add,
+        Stage::Kanji,
+        Stage::Strokes,
+        Stage::Order,
+        Stage::Audio,
+        Stage::Examples,
+    ];
+
+    /// Runs the stage.
+    pub fn run(self, config: &Config, options: &Options) -> Response<()> {
+        match self {
+            Stage::Dictionary => dictionary::run(config, options),
+            Stage::Add => add::run(config, options),
+            Stage::Kanji => kanji::run(config, options),
+            Stage::Strokes => strokes::run(config, options),
+            Stage::Order => order::run(config, options),
+            Stage::Audio => audio::run(config, options),
+            Stage::Examples => examples::run(config, options),
+            Stage::Export => export::run(config, options),
+            Stage::CleanMedia => clean_media::run(config, options),
+        }
+    }
+}
+
+impl fmt::Display for Stage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Stage::Dictionary => "dictionary",
+            Stage::Add => "add",
+            Stage::Kanji => "kanji",
+            Stage::Strokes => "strokes",
+            Stage::Order => "order",
+            Stage::Audio => "audio",
+            Stage::Examples => "examples",
+            Stage::Export => "export",
+            Stage::CleanMedia => "clean-media",
+        };
+
+        write!(f, "{name}")
+    }
+}
+
+/// Runs the stages in order, stopping at the first one that fails.
+pub fn run_all(stages: &[Stage], config: &Config, options: &Options) -> Response<()> {
+    for stage in stages {
+        println!("\n=== {stage} ===");
+        stage
+            .run(config, options)
+            .map_err(|err| format!("Stage '{stage}' failed: {err}"))?;
+    }
+
+    Ok(())
+}
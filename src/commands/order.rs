@@ -0,0 +1,557 @@
+use std::{
+    cmp::Ordering,
+    collections::{HashMap, HashSet},
+    fs,
+};
+
+use anki_utill::{
+    anki::{
+        anki_connect::AnkiConnect,
+        anki_connect_batch::BatchAction,
+        anki_note::{AnkiNote, ID},
+    },
+    config::Config,
+    entry::Kanji,
+};
+
+use super::{Options, Response};
+
+/// Loads the kanji list and reorders the new cards in Anki.
+pub fn run(config: &Config, options: &Options) -> Response<()> {
+    let kanjilist_save_path = &config.paths.kanjilist;
+
+    // Load kanji data from JSON file
+    println!("Loading kanji from {}", kanjilist_save_path.display());
+    let data = fs::read_to_string(kanjilist_save_path)?;
+    let kanji: HashMap<char, Kanji> = serde_json::from_str(&data)?;
+
+    // Connect to Anki and fetch note information
+    let anki = config.connect()?;
+
+    order_cards(&anki, config, options, &kanji)
+}
+
+/// Reorders the new cards of the word notes in Anki by JLPT level and kanji complexity, placing
+/// the cards of every kanji note right before the first word using the kanji, and after the
+/// kanji it is made of.
+/// Fetches the notes and their active cards, sorts them and sets the due position of every card.
+/// Fails if any of the cards could not be updated.
+fn order_cards(
+    anki: &AnkiConnect,
+    config: &Config,
+    options: &Options,
+    kanji: &HashMap<char, Kanji>,
+) -> Response<()> {
+    let word_field = &config.fields.word;
+    let note_query = options.note_query(config);
+
+    println!("Fetching anki info");
+    let notes = anki.notes_info(&anki.find_notes(&note_query)?)?;
+
+    let kanji_notes = anki.notes_info(&anki.find_notes(&config.kanji_query())?)?;
+
+    // Get active cards from Anki
+    let mut cards: HashSet<ID> = HashSet::new();
+    for query in [&note_query, &config.kanji_query()] {
+        let query = format!("{} is:new -is:suspended -is:buried", query);
+        cards.extend(anki.find_cards(&query)?);
+    }
+
+    // Sort and group notes by JLPT level, kanji complexity, and interleaved kana
+    println!("Sorting cards");
+    let sorted: Vec<AnkiNote> = sort_jlpt_level(notes)
+        .into_iter()
+        .rev()
+        .map(|notes| sort_by_kanji(notes, kanji, word_field))
+        .map(|notes| sort_order(notes, kanji))
+        .flat_map(|(kana, kanji)| flatten_jlpt(kana, kanji))
+        .collect();
+    let sorted = place_kanji(
+        sorted,
+        kanji_notes,
+        kanji,
+        word_field,
+        &config.kanji_fields.kanji,
+    );
+
+    // Update Anki cards with new due dates based on sorted order
+    println!("Applying sorted list to anki");
+    let mut batch = anki.batch();
+    for (i, note) in sorted.into_iter().enumerate() {
+        let card: Vec<ID> = note
+            .cards
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|card| cards.contains(card))
+            .collect();
+
+        for id in card {
+            batch.push(BatchAction::SetSpecificValueOfCard {
+                card: id,
+                properties: vec![("due".to_owned(), ((i + 1) as i32).into())],
+            });
+        }
+    }
+
+    let count = batch.len();
+    if options.dry_run {
+        println!("  Would update {} cards", count);
+        return Ok(());
+    }
+
+    let failed = batch
+        .send()?
+        .into_iter()
+        .filter(|result| result.is_err())
+        .count();
+
+    println!("  Updated {}/{} cards", count - failed, count);
+    if failed > 0 {
+        return Err(format!("{} cards failed", failed).into());
+    }
+
+    Ok(())
+}
+
+/// Places every kanji note right before the first word note using the kanji, with the notes of
+/// the kanji it is made of before it. Kanji notes not used by any of the word notes come last,
+/// sorted by kanji.
+fn place_kanji(
+    words: Vec<AnkiNote>,
+    notes: Vec<AnkiNote>,
+    kanji: &HashMap<char, Kanji>,
+    word_field: &str,
+    kanji_field: &str,
+) -> Vec<AnkiNote> {
+    let mut notes: HashMap<char, AnkiNote> = notes
+        .into_iter()
+        .filter_map(|note| Some((note.fields.get(kanji_field)?.chars().next()?, note)))
+        .collect();
+
+    let mut out = Vec::new();
+    for word in words {
+        let text = word.fields.get(word_field).cloned().unwrap_or_default();
+        for c in text.chars() {
+            take_kanji(c, &mut notes, kanji, &mut out);
+        }
+        out.push(word);
+    }
+
+    let mut rest: Vec<char> = notes.keys().copied().collect();
+    rest.sort_unstable();
+    for c in rest {
+        take_kanji(c, &mut notes, kanji, &mut out);
+    }
+
+    out
+}
+
+/// Moves the note of a kanji to the output, preceded by the notes of the kanji it is made of
+/// that were not placed yet.
+fn take_kanji(
+    c: char,
+    notes: &mut HashMap<char, AnkiNote>,
+    kanji: &HashMap<char, Kanji>,
+    out: &mut Vec<AnkiNote>,
+) {
+    let Some(note) = notes.remove(&c) else {
+        return;
+    };
+
+    if let Some(kanji_data) = kanji.get(&c) {
+        for part in kanji_data.parts() {
+            take_kanji(part, notes, kanji, out);
+        }
+    }
+
+    out.push(note);
+}
+
+/// Flattens and interleaves Kana and Kanji notes based on JLPT level spacing requirements.
+/// Interleaves notes from kana and kanji vectors such that each Kana note is repeated as per fill_count.
+/// This ensures balanced practice between different script types while maintaining spaced repetition.
+///
+/// # Arguments
+/// * `kana` - Vector of AnkiNotes for Kana (e.g., Hiragana, Katakana)
+/// * `kanji` - Vector of vectors containing AnkiNotes grouped by JLPT level
+///
+/// # Returns
+/// A single vector containing interleaved Kana and Kanji notes according to spacing requirements.
+fn flatten_jlpt(mut kana: Vec<AnkiNote>, kanji: Vec<Vec<AnkiNote>>) -> Vec<AnkiNote> {
+    let mut out = Vec::new();
+
+    // Calculate how many kana notes should be interleaved per kanji group
+    let fill_count = kana.len() as f32 / kanji.len() as f32;
+    let mut count: f32 = 0.0;
+
+    for note in kanji.into_iter() {
+        out.extend_from_slice(&note);
+
+        // Add a kana note when the fill threshold is met
+        count += fill_count;
+        while count >= 1.0 {
+            out.push(kana.remove(0));
+            count -= 1.0;
+        }
+    }
+
+    // Add any remaining kana notes
+    out.extend_from_slice(&kana);
+
+    /* println!();
+    for note in out.iter() {
+        print!("{} ", note.fields["1 Word"])
+    }
+    println!(); */
+
+    /* println!("{}", kana.len()); */
+
+    out
+}
+
+/// Sorts AnkiNotes by JLPT level and Kanji complexity for optimal spaced repetition scheduling.
+/// Notes are first grouped by their JLPT level, then within each group sorted by the number of kanji strokes
+/// and frequency. This ensures notes with complex or frequently used kanji are practiced earlier.
+///
+/// # Arguments
+/// * `notes` - HashMap where keys represent JLPT levels (as strings) and values are vectors of AnkiNotes
+/// * `kanji` - Kanji data structure containing stroke counts for each character, unused in this function
+fn sort_order(
+    mut notes: HashMap<String, Vec<AnkiNote>>,
+    kanji: &HashMap<char, Kanji>,
+) -> (Vec<AnkiNote>, Vec<Vec<AnkiNote>>) {
+    let kana = notes.remove("").unwrap_or_default();
+
+    // Group notes by JLPT level
+    let mut notes: Vec<(String, Vec<AnkiNote>)> =
+        notes.into_iter().filter(|(k, _)| !k.is_empty()).collect();
+
+    // Count frequency of each kanji across all notes for prioritization
+    let kanji_filter: HashMap<char, u32> =
+        notes.iter().fold(HashMap::new(), |mut res, (kan, _)| {
+            for kan in kan.chars() {
+                if let Some(count) = res.get(&kan) {
+                    res.insert(kan, count + 1);
+                } else {
+                    res.insert(kan, 1);
+                }
+            }
+            res
+        });
+
+    // Keep only kanji that appear more than once
+    let kanji_filter: HashSet<char> = kanji_filter
+        .into_iter()
+        .filter(|(_, count)| *count > 1)
+        .map(|(kan, _)| kan)
+        .collect();
+
+    // Sort notes by complexity and frequency of kanji
+    notes.sort_unstable_by_key(|(kan, _)| {
+        let count = kan.len();
+        let strokes: u32 = kan
+            .chars()
+            .map(|k| kanji.get(&k).map_or(0, |k| k.strokes.unwrap_or(0) as u32))
+            .sum();
+
+        // Extract and sort kanji based on complexity and frequency
+        let mut kanji_list: Vec<char> = kan
+            .chars()
+            .filter(|kan| kanji_filter.contains(kan))
+            .collect();
+
+        if kan.is_empty() {
+            kanji_list = kan
+                .chars()
+                .filter(|kan| kanji_filter.contains(kan))
+                .collect();
+        }
+
+        kanji_list.sort_unstable_by(|a, b| {
+            let strokes = kanji
+                .get(a)
+                .map_or(u8::MAX, |e| e.strokes.unwrap_or(u8::MAX))
+                .cmp(
+                    &kanji
+                        .get(b)
+                        .map_or(u8::MAX, |e| e.strokes.unwrap_or(u8::MAX)),
+                );
+
+            if strokes != Ordering::Equal {
+                return strokes;
+            }
+
+            a.cmp(b)
+        });
+
+        let kan = kanji_list.last().unwrap_or(&' ');
+        let kan_strokes = kanji
+            .get(kan)
+            .map_or(u8::MAX, |e| e.strokes.unwrap_or(u8::MAX));
+
+        // Create a sortable key combining stroke count and word properties
+        let key = format!("{:0>3}{}{:0>3}{:0>4}", kan_strokes, kan, count, strokes);
+
+        key
+    });
+
+    /* println!();
+    for (kanji, _) in notes.iter() {
+        print!("{} ", kanji)
+    }
+    println!(); */
+
+    let notes = notes.into_iter().map(|(_, notes)| notes).collect();
+
+    (kana, notes)
+}
+
+/// Groups AnkiNotes based on their Kanji composition for targeted spaced repetition.
+/// Notes are grouped together if they contain the same set of Kanji characters, ignoring order.
+///
+/// # Arguments
+/// * `notes` - Vector of AnkiNotes to be grouped
+/// * `kanji` - Mapping from Kanji characters to their properties (unused in this function)
+/// * `word_field` - The name of the field containing the word
+///
+/// # Returns
+/// A HashMap where keys are sorted strings of Kanji characters and values are vectors of notes sharing those Kanji.
+fn sort_by_kanji(
+    notes: Vec<AnkiNote>,
+    kanji: &HashMap<char, Kanji>,
+    word_field: &str,
+) -> HashMap<String, Vec<AnkiNote>> {
+    let mut out: HashMap<String, Vec<AnkiNote>> = HashMap::new();
+
+    for note in notes.into_iter() {
+        // Extract the unique Kanji characters from the note's word field
+        let key = get_kanji(&note, kanji, word_field);
+
+        // Group notes by their Kanji composition
+        if let Some(entry) = out.get_mut(&key) {
+            entry.push(note);
+        } else {
+            out.insert(key, vec![note]);
+        }
+    }
+
+    // Sort the groups and their contents for consistency
+    out = out
+        .into_iter()
+        .map(|(key, notes)| (key, sort_notes(notes, word_field)))
+        .collect();
+
+    out
+}
+
+fn get_kanji(note: &AnkiNote, kanji: &HashMap<char, Kanji>, word_field: &str) -> String {
+    // Filter only the Kanji characters present in the global Kanji map
+    let mut vec: Vec<char> = note.fields[word_field]
+        .chars()
+        .filter(|c| kanji.contains_key(c))
+        .collect();
+
+    // Sort the Kanji to ensure consistent grouping regardless of order
+    vec.sort_unstable();
+    vec.iter().collect()
+}
+
+/// Split notes into vectors based on JLPT level (N1-N5)
+/// Returns an array where each index corresponds to a JLPT level,
+/// with index 0 being non-JLPT, up to index 5 being N1.
+fn sort_jlpt_level(notes: Vec<AnkiNote>) -> [Vec<AnkiNote>; 6] {
+    // Initialize an array of empty vectors for each JLPT level
+    let mut out = [
+        Vec::new(), // non-JLPT
+        Vec::new(), // N1
+        Vec::new(), // N2
+        Vec::new(), // N3
+        Vec::new(), // N4
+        Vec::new(), // N5
+    ];
+
+    // Distribute notes into their respective JLPT level vectors
+    for note in notes {
+        out[get_jlpt_level(&note).unwrap_or(0) as usize].push(note);
+    }
+
+    out
+}
+
+/// Determine the JLPT level from note tags
+/// Returns Some(level) if a JLPT tag is found, None otherwise.
+fn get_jlpt_level(note: &AnkiNote) -> Option<u8> {
+    // Check each tag for JLPT levels in descending order
+    if note.tags.contains(&"JLPT-N1".into()) {
+        Some(1)
+    } else if note.tags.contains(&"JLPT-N2".into()) {
+        Some(2)
+    } else if note.tags.contains(&"JLPT-N3".into()) {
+        Some(3)
+    } else if note.tags.contains(&"JLPT-N4".into()) {
+        Some(4)
+    } else if note.tags.contains(&"JLPT-N5".into()) {
+        Some(5)
+    } else {
+        None
+    }
+}
+
+/// Simple sort function that sorts notes alphabetically by their word field
+fn sort_notes(mut notes: Vec<AnkiNote>, word_field: &str) -> Vec<AnkiNote> {
+    // Sort notes by the word field in ascending order
+    notes.sort_by(|a, b| a.fields[word_field].cmp(&b.fields[word_field]));
+    notes
+}
+
+#[cfg(test)]
+mod tests {
+    use anki_utill::anki::anki_connect_mock::MockAnkiConnect;
+
+    use super::*;
+
+    /// Tests that new cards are ordered by JLPT level and suspended cards are left alone.
+    #[test]
+    fn order_new_cards() {
+        let mock = MockAnkiConnect::start().unwrap();
+
+        let (n4, n5, suspended) = mock.with_collection(|collection| {
+            collection.add_deck("My Deck 4.0");
+            collection.add_model("JP Card V4", &["1 Word", "2 Meaning"]);
+
+            let mut add = |word: &str, tag: &str| {
+                let note = collection
+                    .add_note("My Deck 4.0", "JP Card V4", &[("1 Word", word)], &[tag])
+                    .unwrap();
+                collection.note(note).unwrap().cards[0]
+            };
+
+            let n4 = add("漢字[かんじ]", "JLPT-N4");
+            let n5 = add("日[ひ]", "JLPT-N5");
+            let suspended = add("月[つき]", "JLPT-N5");
+
+            collection.cards.get_mut(&suspended).unwrap().suspended = true;
+            collection.cards.get_mut(&suspended).unwrap().due = 100;
+
+            (n4, n5, suspended)
+        });
+
+        let kanji: HashMap<char, Kanji> = "漢字日月"
+            .chars()
+            .map(|c| {
+                let kanji = Kanji::new(
+                    c,
+                    HashSet::new(),
+                    HashSet::new(),
+                    Vec::new(),
+                    Some(4),
+                    HashSet::new(),
+                );
+                (c, kanji)
+            })
+            .collect();
+
+        let anki = mock.connect().unwrap();
+        order_cards(&anki, &Config::default(), &Options::default(), &kanji).unwrap();
+
+        let collection = mock.collection();
+        assert!(collection.card(n5).unwrap().due < collection.card(n4).unwrap().due);
+        assert_eq!(collection.card(suspended).unwrap().due, 100);
+        assert!(mock
+            .requests()
+            .iter()
+            .any(|request| request.action == "multi"));
+    }
+
+    /// Tests that kanji cards come right before the first word using them.
+    #[test]
+    fn kanji_before_words() {
+        let mock = MockAnkiConnect::start().unwrap();
+
+        let (word, used, unused) = mock.with_collection(|collection| {
+            collection.add_deck("My Deck 4.0");
+            collection.add_model("JP Card V4", &["1 Word", "2 Meaning"]);
+            collection.add_model("JP Kanji V4", &["1 Kanji", "2 Meaning"]);
+
+            let mut add = |model: &str, field: (&str, &str)| {
+                let note = collection
+                    .add_note("My Deck 4.0", model, &[field], &["JLPT-N5"])
+                    .unwrap();
+                collection.note(note).unwrap().cards[0]
+            };
+
+            let unused = add("JP Kanji V4", ("1 Kanji", "月"));
+            let used = add("JP Kanji V4", ("1 Kanji", "日"));
+            let word = add("JP Card V4", ("1 Word", "日[ひ]"));
+
+            (word, used, unused)
+        });
+
+        let kanji: HashMap<char, Kanji> = "日月"
+            .chars()
+            .map(|c| {
+                let kanji = Kanji::new(
+                    c,
+                    HashSet::new(),
+                    HashSet::new(),
+                    Vec::new(),
+                    Some(4),
+                    HashSet::new(),
+                );
+                (c, kanji)
+            })
+            .collect();
+
+        let anki = mock.connect().unwrap();
+        order_cards(&anki, &Config::default(), &Options::default(), &kanji).unwrap();
+
+        let collection = mock.collection();
+        let due = |card: ID| collection.card(card).unwrap().due;
+        assert_eq!(due(used) + 1, due(word));
+        assert!(due(word) < due(unused));
+    }
+
+    /// Tests that the kanji a kanji is made of come before it.
+    #[test]
+    fn components_first() {
+        let note = |field: &str, text: &str| AnkiNote {
+            fields: HashMap::from([(field.to_owned(), text.to_owned())]),
+            ..AnkiNote::default()
+        };
+        let text = |notes: &[AnkiNote]| -> Vec<String> {
+            notes
+                .iter()
+                .map(|note| note.fields.values().next().unwrap().clone())
+                .collect()
+        };
+
+        let mut bright = Kanji::new(
+            '明',
+            HashSet::new(),
+            HashSet::new(),
+            Vec::new(),
+            Some(8),
+            HashSet::new(),
+        );
+        bright.ids = Some("⿰日月".to_owned());
+        let kanji = HashMap::from([('明', bright)]);
+
+        let sorted = place_kanji(
+            vec![note("1 Word", "明日[あした]"), note("1 Word", "月[つき]")],
+            vec![
+                note("1 Kanji", "月"),
+                note("1 Kanji", "明"),
+                note("1 Kanji", "日"),
+                note("1 Kanji", "木"),
+            ],
+            &kanji,
+            "1 Word",
+            "1 Kanji",
+        );
+
+        assert_eq!(
+            text(&sorted),
+            vec!["日", "月", "明", "明日[あした]", "月[つき]", "木"]
+        );
+    }
+}
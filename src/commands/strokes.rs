@@ -0,0 +1,314 @@
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    fs,
+};
+
+use anki_utill::{
+    anki::{
+        anki_changeset::{Changeset, FieldDiff, NoteChange},
+        anki_connect::AnkiConnect,
+        anki_connect_error::AnkiConnectError,
+        anki_note::AnkiNote,
+    },
+    config::Config,
+    entry::Kanji,
+    kanjivg::{self, KanjiVg, StrokeOrder},
+};
+
+use super::{add::report_failures, Options, Response};
+
+/// Loads the kanji list and attaches the KanjiVG stroke order diagrams to the word and kanji
+/// notes. Skipped if neither note type has a stroke order field or there is no KanjiVG release.
+/// Fails if any of the notes could not be written.
+pub fn run(config: &Config, options: &Options) -> Response<()> {
+    if config.fields.stroke_order.is_none() && config.kanji_fields.stroke_order.is_none() {
+        println!("No stroke order field configured, skipping.");
+        return Ok(());
+    }
+
+    let kanjivg_path = &config.paths.kanjivg;
+    if !kanjivg_path.exists() {
+        println!("No KanjiVG found at {}, skipping.", kanjivg_path.display());
+        return Ok(());
+    }
+
+    let kanjilist_save_path = &config.paths.kanjilist;
+
+    println!("Loading kanji from {}", kanjilist_save_path.display());
+    let data = fs::read_to_string(kanjilist_save_path)?;
+    let kanji: HashMap<char, Kanji> = serde_json::from_str(&data)?;
+
+    println!("Opening KanjiVG at {}", kanjivg_path.display());
+    let mut kanjivg = KanjiVg::open(kanjivg_path)?;
+
+    let anki = config.connect()?;
+
+    let failures = write_strokes(&anki, config, options, &mut kanjivg, &kanji)?;
+    if !failures.is_empty() {
+        return Err(format!("{} notes failed", failures.len()).into());
+    }
+
+    Ok(())
+}
+
+/// Handles writing of the stroke order fields by first planning every change, then storing the
+/// SVG files of the changed notes and applying the changeset.
+/// The changeset is printed in full on a dry run and not applied.
+/// Returns the notes that failed to be written together with the error.
+fn write_strokes(
+    anki: &AnkiConnect,
+    config: &Config,
+    options: &Options,
+    kanjivg: &mut KanjiVg,
+    kanji: &HashMap<char, Kanji>,
+) -> Response<Vec<(String, AnkiConnectError)>> {
+    let mut words = Vec::new();
+    if config.fields.stroke_order.is_some() {
+        println!("\nGetting word notes.");
+        words = anki.notes_info(&anki.find_notes(&options.note_query(config))?)?;
+    }
+
+    let mut kanji_notes = Vec::new();
+    if config.kanji_fields.stroke_order.is_some() {
+        println!("Getting kanji notes.");
+        kanji_notes = anki.notes_info(&anki.find_notes(&config.kanji_query())?)?;
+    }
+
+    let words = options.limited(&words);
+    let kanji_notes = options.limited(&kanji_notes);
+
+    println!("Reading stroke order diagrams.");
+    let used: BTreeSet<char> = words
+        .iter()
+        .filter_map(|note| note.fields.get(&config.fields.word))
+        .chain(
+            kanji_notes
+                .iter()
+                .filter_map(|note| note.fields.get(&config.kanji_fields.kanji)),
+        )
+        .flat_map(|text| text.chars())
+        .filter(|c| kanjivg::is_kanji(*c))
+        .collect();
+    let diagrams = load_diagrams(kanjivg, used, kanji)?;
+
+    println!("Planning changes.");
+    let (changeset, media) = plan_strokes(words, kanji_notes, &diagrams, config);
+
+    if options.dry_run {
+        if options.json {
+            println!("{}", serde_json::to_string_pretty(&changeset)?);
+        } else {
+            println!("{}", changeset);
+            println!("Would store {} media files", media.len());
+        }
+        return Ok(Vec::new());
+    }
+
+    println!("  {}", changeset.summary());
+    if changeset.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    println!("Storing {} media files.", media.len());
+    for (filename, svg) in &media {
+        anki.store_media_file(filename, svg.as_bytes())?;
+    }
+
+    println!("Applying changes.");
+    let failures = changeset.apply(anki)?;
+
+    report_failures(&failures);
+    Ok(failures)
+}
+
+/// Reads the stroke order diagrams of the kanji KanjiVG has and reports the kanji whose stroke
+/// count differs from the one of KANJIDIC.
+pub(super) fn load_diagrams(
+    kanjivg: &mut KanjiVg,
+    chars: impl IntoIterator<Item = char>,
+    kanji: &HashMap<char, Kanji>,
+) -> Response<BTreeMap<char, StrokeOrder>> {
+    let mut out = BTreeMap::new();
+    let mut mismatches = Vec::new();
+
+    for c in chars {
+        let Some(diagram) = kanjivg.get(c)? else {
+            continue;
+        };
+
+        if let Some(mismatch) = diagram.check_strokes(kanji.get(&c).and_then(|k| k.strokes)) {
+            mismatches.push(mismatch);
+        }
+        out.insert(c, diagram);
+    }
+
+    if !mismatches.is_empty() {
+        println!(
+            "{} kanji have a different stroke count in KanjiVG and KANJIDIC:",
+            mismatches.len()
+        );
+        for mismatch in mismatches {
+            println!("  {}", mismatch);
+        }
+    }
+
+    Ok(out)
+}
+
+/// Returns the change of a field the note type has, `None` if it is missing or unchanged.
+fn field_diff(note: &AnkiNote, field: &str, new: String) -> Option<FieldDiff> {
+    let old = note.fields.get(field)?;
+
+    (*old != new).then(|| FieldDiff {
+        field: field.to_owned(),
+        old: old.clone(),
+        new,
+    })
+}
+
+/// Plans the changes filling the stroke order fields: word notes show the diagram of each
+/// of their kanji and kanji notes the numbered stroke order strip. Kanji KanjiVG has no
+/// diagram of are left out.
+///
+/// # Returns
+/// * The changeset and the SVG files of the changed notes by media filename.
+fn plan_strokes(
+    words: &[AnkiNote],
+    kanji_notes: &[AnkiNote],
+    diagrams: &BTreeMap<char, StrokeOrder>,
+    config: &Config,
+) -> (Changeset, BTreeMap<String, String>) {
+    let mut changeset = Changeset::default();
+    let mut media = BTreeMap::new();
+
+    if let Some(field) = &config.fields.stroke_order {
+        for note in words {
+            let Some(word) = note.fields.get(&config.fields.word) else {
+                continue;
+            };
+
+            let known: String = word.chars().filter(|c| diagrams.contains_key(c)).collect();
+            let Some(diff) = field_diff(note, field, kanjivg::stroke_order_html(&known)) else {
+                continue;
+            };
+
+            for c in known.chars() {
+                media.insert(kanjivg::media_name(c), diagrams[&c].svg.clone());
+            }
+
+            let mut change = NoteChange::new(word, note.noteId.unwrap());
+            change.fields.push(diff);
+            changeset.update(change);
+        }
+    }
+
+    if let Some(field) = &config.kanji_fields.stroke_order {
+        for note in kanji_notes {
+            let Some(label) = note.fields.get(&config.kanji_fields.kanji) else {
+                continue;
+            };
+
+            let mut chars = label.chars();
+            let (Some(c), None) = (chars.next(), chars.next()) else {
+                continue;
+            };
+            let Some(diagram) = diagrams.get(&c) else {
+                continue;
+            };
+            let Some(diff) = field_diff(note, field, kanjivg::strip_html(c)) else {
+                continue;
+            };
+
+            media.insert(kanjivg::strip_media_name(c), diagram.strip_svg());
+
+            let mut change = NoteChange::new(label, note.noteId.unwrap());
+            change.fields.push(diff);
+            changeset.update(change);
+        }
+    }
+
+    (changeset, media)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use anki_utill::anki::anki_connect_mock::MockAnkiConnect;
+
+    use super::*;
+
+    /// A KanjiVG file of 十 with its two strokes.
+    const TEN: &str = r#"<svg xmlns="http://www.w3.org/2000/svg" width="109" height="109">
+<path id="kvg:05341-s1" d="M13.5,55.5c3,0.5,6,0.5,9,0.3c14-0.8,49-3.3,69-3.3"/>
+<path id="kvg:05341-s2" d="M53.8,16.5c1.1,1.1,1.6,2.6,1.6,4.5c0,12,0,65,0,75"/>
+</svg>"#;
+
+    /// Tests that the diagrams are stored and referenced by the word and kanji notes, leaving
+    /// out kanji KanjiVG has no diagram of.
+    #[test]
+    fn sync_strokes() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        fs::write(dir.join("05341.svg"), TEN).unwrap();
+
+        let mock = MockAnkiConnect::start().unwrap();
+        let mut config = Config::default();
+        config.deck.name = "Japanese".to_owned();
+        config.fields.stroke_order = Some("7 Stroke Order".to_owned());
+        config.kanji_fields.stroke_order = Some("8 Stroke Order".to_owned());
+
+        let (word, kanji_note) = mock.with_collection(|collection| {
+            collection.add_deck("Japanese");
+            collection.add_model("JP Card V4", &["1 Word", "7 Stroke Order"]);
+            collection.add_model("JP Kanji V4", &["1 Kanji", "8 Stroke Order"]);
+
+            let word = collection
+                .add_note(
+                    "Japanese",
+                    "JP Card V4",
+                    &[("1 Word", "十月[じゅうがつ]")],
+                    &[],
+                )
+                .unwrap();
+            let kanji_note = collection
+                .add_note("Japanese", "JP Kanji V4", &[("1 Kanji", "十")], &[])
+                .unwrap();
+
+            (word, kanji_note)
+        });
+
+        let kanji = HashMap::from([(
+            '十',
+            Kanji::new(
+                '十',
+                HashSet::new(),
+                HashSet::new(),
+                Vec::new(),
+                Some(2),
+                HashSet::new(),
+            ),
+        )]);
+
+        let anki = mock.connect().unwrap();
+        let mut kanjivg = KanjiVg::open(dir).unwrap();
+        assert!(
+            write_strokes(&anki, &config, &Options::default(), &mut kanjivg, &kanji)
+                .unwrap()
+                .is_empty()
+        );
+
+        let collection = mock.collection();
+        assert_eq!(
+            collection.field(word, "7 Stroke Order"),
+            Some("<img src=\"kanjivg_05341.svg\">")
+        );
+        assert_eq!(
+            collection.field(kanji_note, "8 Stroke Order"),
+            Some("<img src=\"kanjivg_05341_strip.svg\">")
+        );
+        assert_eq!(collection.media["kanjivg_05341.svg"], TEN.as_bytes());
+        assert!(collection.media.contains_key("kanjivg_05341_strip.svg"));
+        assert!(!collection.media.contains_key("kanjivg_06708.svg"));
+    }
+}
@@ -0,0 +1,362 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use super::{
+    anki_connect::{AnkiConnect, Response},
+    anki_connect_batch::BatchAction,
+    anki_connect_error::AnkiConnectError,
+    anki_note::{AnkiNote, ID},
+};
+
+/// A planned set of changes to the notes and cards of a collection, which can be reviewed before
+/// it is applied.
+///
+/// The changeset prints as a readable diff and serializes to JSON.
+///
+/// # Example
+/// ```no_run
+/// use anki_utill::anki::{anki_changeset::Changeset, anki_connect::AnkiConnect};
+///
+/// let anki = AnkiConnect::default();
+/// let mut changeset = Changeset::default();
+/// changeset.suspend("猫[ねこ]", vec![1]);
+///
+/// println!("{}", changeset);
+/// for (label, err) in changeset.apply(&anki).unwrap() {
+///     println!("{}: {}", label, err);
+/// }
+/// ```
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+pub struct Changeset {
+    /// Notes to create.
+    pub add: Vec<NoteAddition>,
+    /// Changes to existing notes.
+    pub update: Vec<NoteChange>,
+    /// Cards to suspend.
+    pub suspend: Vec<CardChange>,
+    /// Cards to unsuspend.
+    pub unsuspend: Vec<CardChange>,
+}
+
+/// A note to create.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct NoteAddition {
+    /// A human readable name of the note, e.g. the word.
+    pub label: String,
+    /// The note to create.
+    pub note: AnkiNote,
+}
+
+/// The changes to a single existing note.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct NoteChange {
+    /// A human readable name of the note, e.g. the word.
+    pub label: String,
+    /// The id of the note.
+    pub note: ID,
+    /// The fields that change.
+    pub fields: Vec<FieldDiff>,
+    /// The tags to add.
+    pub add_tags: Vec<String>,
+    /// The tags to remove.
+    pub remove_tags: Vec<String>,
+}
+
+impl NoteChange {
+    /// Creates a change without any changes.
+    pub fn new(label: &str, note: ID) -> Self {
+        Self {
+            label: label.to_owned(),
+            note,
+            fields: Vec::new(),
+            add_tags: Vec::new(),
+            remove_tags: Vec::new(),
+        }
+    }
+
+    /// Returns whether the change does nothing.
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty() && self.add_tags.is_empty() && self.remove_tags.is_empty()
+    }
+}
+
+/// The old and new content of a field.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct FieldDiff {
+    /// The name of the field.
+    pub field: String,
+    /// The current content.
+    pub old: String,
+    /// The new content.
+    pub new: String,
+}
+
+/// Cards of a note to suspend or unsuspend.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct CardChange {
+    /// A human readable name of the note, e.g. the word.
+    pub label: String,
+    /// The id of the cards.
+    pub cards: Vec<ID>,
+}
+
+impl Changeset {
+    /// Plans the creation of a note.
+    pub fn add(&mut self, label: &str, note: AnkiNote) {
+        self.add.push(NoteAddition {
+            label: label.to_owned(),
+            note,
+        });
+    }
+
+    /// Plans changes to an existing note, ignoring changes that do nothing.
+    pub fn update(&mut self, change: NoteChange) {
+        if !change.is_empty() {
+            self.update.push(change);
+        }
+    }
+
+    /// Plans suspending cards, ignoring an empty list.
+    pub fn suspend(&mut self, label: &str, cards: Vec<ID>) {
+        if !cards.is_empty() {
+            self.suspend.push(CardChange {
+                label: label.to_owned(),
+                cards,
+            });
+        }
+    }
+
+    /// Plans unsuspending cards, ignoring an empty list.
+    pub fn unsuspend(&mut self, label: &str, cards: Vec<ID>) {
+        if !cards.is_empty() {
+            self.unsuspend.push(CardChange {
+                label: label.to_owned(),
+                cards,
+            });
+        }
+    }
+
+    /// Returns whether nothing would change.
+    pub fn is_empty(&self) -> bool {
+        self.add.is_empty()
+            && self.update.is_empty()
+            && self.suspend.is_empty()
+            && self.unsuspend.is_empty()
+    }
+
+    /// Returns a one line summary of the number of changes.
+    pub fn summary(&self) -> String {
+        format!(
+            "{} to add, {} to update, {} to suspend, {} to unsuspend",
+            self.add.len(),
+            self.update.len(),
+            self.suspend.len(),
+            self.unsuspend.len()
+        )
+    }
+
+    /// Returns the actions applying the changeset, each with the label of its note.
+    pub fn actions(&self) -> Vec<(&str, BatchAction)> {
+        let mut out = Vec::new();
+
+        for change in &self.update {
+            let label = change.label.as_str();
+
+            if !change.fields.is_empty() {
+                out.push((
+                    label,
+                    BatchAction::UpdateNoteFields {
+                        note: change.note,
+                        fields: change
+                            .fields
+                            .iter()
+                            .map(|diff| (diff.field.clone(), diff.new.clone()))
+                            .collect(),
+                    },
+                ));
+            }
+
+            if !change.remove_tags.is_empty() {
+                out.push((
+                    label,
+                    BatchAction::RemoveTags {
+                        notes: vec![change.note],
+                        tags: change.remove_tags.join(" "),
+                    },
+                ));
+            }
+
+            if !change.add_tags.is_empty() {
+                out.push((
+                    label,
+                    BatchAction::AddTags {
+                        notes: vec![change.note],
+                        tags: change.add_tags.join(" "),
+                    },
+                ));
+            }
+        }
+
+        for change in &self.suspend {
+            out.push((
+                change.label.as_str(),
+                BatchAction::Suspend(change.cards.clone()),
+            ));
+        }
+
+        for change in &self.unsuspend {
+            out.push((
+                change.label.as_str(),
+                BatchAction::Unsuspend(change.cards.clone()),
+            ));
+        }
+
+        for addition in &self.add {
+            out.push((
+                addition.label.as_str(),
+                BatchAction::AddNote(addition.note.clone()),
+            ));
+        }
+
+        out
+    }
+
+    /// Applies the changeset using batched requests. Existing notes are changed before new notes
+    /// are added.
+    ///
+    /// # Returns
+    /// * The label of every change that failed together with the error, or an error if a
+    ///   request as a whole failed.
+    pub fn apply(&self, anki: &AnkiConnect) -> Response<Vec<(String, AnkiConnectError)>> {
+        let mut batch = anki.batch();
+        let mut labels = Vec::new();
+
+        for (label, action) in self.actions() {
+            batch.push(action);
+            labels.push(label.to_owned());
+        }
+
+        Ok(labels
+            .into_iter()
+            .zip(batch.send()?)
+            .filter_map(|(label, result)| result.err().map(|err| (label, err)))
+            .collect())
+    }
+}
+
+impl fmt::Display for Changeset {
+    /// Formats the changeset as a diff: `+` for additions, `-` for removals and `~` for changed
+    /// notes.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for change in &self.update {
+            writeln!(f, "~ {} ({})", change.label, change.note)?;
+
+            for diff in &change.fields {
+                writeln!(f, "    - {}: {}", diff.field, diff.old)?;
+                writeln!(f, "    + {}: {}", diff.field, diff.new)?;
+            }
+
+            for tag in &change.remove_tags {
+                writeln!(f, "    - tag {}", tag)?;
+            }
+
+            for tag in &change.add_tags {
+                writeln!(f, "    + tag {}", tag)?;
+            }
+        }
+
+        for change in &self.suspend {
+            writeln!(f, "- suspend {} {:?}", change.label, change.cards)?;
+        }
+
+        for change in &self.unsuspend {
+            writeln!(f, "+ unsuspend {} {:?}", change.label, change.cards)?;
+        }
+
+        for addition in &self.add {
+            writeln!(f, "+ {}", addition.label)?;
+
+            let mut fields: Vec<(&String, &String)> = addition.note.fields.iter().collect();
+            fields.sort_unstable();
+            for (field, value) in fields {
+                writeln!(f, "    + {}: {}", field, value)?;
+            }
+
+            if !addition.note.tags.is_empty() {
+                writeln!(f, "    + tags {}", addition.note.tags.join(" "))?;
+            }
+        }
+
+        write!(f, "{}", self.summary())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Creates a changeset touching every kind of change.
+    fn changeset() -> Changeset {
+        let mut changeset = Changeset::default();
+
+        let mut change = NoteChange::new("猫[ねこ]", 1);
+        change.fields.push(FieldDiff {
+            field: "Meaning".to_owned(),
+            old: "old".to_owned(),
+            new: "cat".to_owned(),
+        });
+        change.add_tags.push("JLPT-N5".to_owned());
+        changeset.update(change);
+        changeset.update(NoteChange::new("犬[いぬ]", 2));
+
+        changeset.suspend("鳥[とり]", vec![30]);
+        changeset.unsuspend("魚[さかな]", Vec::new());
+
+        changeset.add(
+            "月[つき]",
+            AnkiNote {
+                modelName: "Word".to_owned(),
+                tags: vec!["n".to_owned()],
+                fields: [("Expression".to_owned(), "月[つき]".to_owned())].into(),
+                ..AnkiNote::default()
+            },
+        );
+
+        changeset
+    }
+
+    /// Tests that empty changes are dropped and the diff lists every change.
+    #[test]
+    fn format_diff() {
+        let changeset = changeset();
+
+        assert_eq!(changeset.update.len(), 1);
+        assert!(changeset.unsuspend.is_empty());
+        assert_eq!(
+            changeset.to_string(),
+            "~ 猫[ねこ] (1)\n    - Meaning: old\n    + Meaning: cat\n    + tag JLPT-N5\n\
+             - suspend 鳥[とり] [30]\n\
+             + 月[つき]\n    + Expression: 月[つき]\n    + tags n\n\
+             1 to add, 1 to update, 1 to suspend, 0 to unsuspend"
+        );
+
+        let json = serde_json::to_string(&changeset).unwrap();
+        assert_eq!(serde_json::from_str::<Changeset>(&json).unwrap(), changeset);
+    }
+
+    /// Tests that existing notes are changed before notes are added.
+    #[test]
+    fn actions_in_order() {
+        let actions: Vec<&str> = changeset()
+            .actions()
+            .iter()
+            .map(|(_, action)| action.action())
+            .collect();
+
+        assert_eq!(
+            actions,
+            vec!["updateNoteFields", "addTags", "suspend", "addNote"]
+        );
+    }
+}
@@ -1,14 +1,22 @@
-use std::collections::HashMap;
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
 
+use base64::prelude::*;
 use reqwest::Method;
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 
 use crate::anki::anki_note::ID;
 
-use super::anki_note::AnkiNote;
+use super::{
+    anki_connect_batch::AnkiBatch,
+    anki_connect_error::{AnkiConnectError, ApiError},
+    anki_note::AnkiNote,
+};
 
 /// Represents a response containing either a successful result or an error.
-type Response<T> = Result<T, Box<dyn std::error::Error>>;
+pub type Response<T> = Result<T, AnkiConnectError>;
 
 /// Represents the payload data sent in an API request. It includes the action, a fixed version,
 /// and any additional parameters required for the action.
@@ -46,13 +54,28 @@ impl<T> PayloadData<T> {
 
 /// Represents the response structure returned by the server for an API request.
 #[derive(Deserialize)]
-struct ResponseData<T> {
-    /// The deserialized result of a successful API call.
-    result: Option<T>,
+pub(crate) struct ResponseData {
+    /// The result of a successful API call. Actions without a result return `null`.
+    #[serde(default)]
+    result: serde_json::Value,
     /// An error message if the request fails.
     error: Option<String>,
 }
 
+impl ResponseData {
+    /// Converts the response into the deserialized result or the reported API error.
+    pub(crate) fn into_result<T>(self) -> Response<T>
+    where
+        T: DeserializeOwned,
+    {
+        if let Some(err) = self.error {
+            return Err(ApiError::from(err.as_str()).into());
+        }
+
+        Ok(serde_json::from_value(self.result)?)
+    }
+}
+
 pub struct AnkiConnect {
     url: String,
     api_key: Option<String>,
@@ -75,10 +98,10 @@ impl AnkiConnect {
         let version = link.version()?;
 
         if version != VERSION {
-            Err(Box::from(format!(
-                "Expected AnkiConnect version '{}' but got '{}' instead! ",
-                VERSION, version
-            )))
+            Err(AnkiConnectError::VersionMismatch {
+                expected: VERSION,
+                found: version,
+            })
         } else {
             Ok(link)
         }
@@ -96,6 +119,14 @@ impl AnkiConnect {
         }
     }
 
+    /// Starts a batch of actions that are sent together using the `multi` action.
+    ///
+    /// # Returns
+    /// * An empty batch bound to this connection.
+    pub fn batch(&self) -> AnkiBatch<'_> {
+        AnkiBatch::new(self)
+    }
+
     /// Every request consists of a JSON-encoded object containing an action, a version, and a set of
     /// contextual params.
     ///
@@ -105,7 +136,7 @@ impl AnkiConnect {
     ///
     /// # Returns
     /// * A `Response<T>` where `T` is the deserialized result type if successful, or an error.
-    fn invoke<T, U>(&self, action: &str, data: Option<T>) -> Response<U>
+    pub(crate) fn invoke<T, U>(&self, action: &str, data: Option<T>) -> Response<U>
     where
         T: Serialize,
         U: DeserializeOwned,
@@ -121,19 +152,20 @@ impl AnkiConnect {
             .body(payload)
             .send()?;
 
+        let status = response.status();
         let response = response.text()?;
         /* println!("Response: {}", response); */
-        let response: ResponseData<U> = serde_json::from_str(response.as_str())?;
 
-        if let Some(err) = response.error {
-            return Err(Box::from(err));
+        if !status.is_success() {
+            return Err(AnkiConnectError::Http {
+                status: status.as_u16(),
+                body: response,
+            });
         }
 
-        if let Some(response) = response.result {
-            return Ok(response);
-        }
+        let response: ResponseData = serde_json::from_str(response.as_str())?;
 
-        Err(Box::from("Somehow did not get a response or error"))
+        response.into_result()
     }
 
     /// Gets the version of the API exposed by this plugin. Currently versions `1` through `5` are defined.
@@ -189,7 +221,10 @@ impl AnkiConnect {
     /// # Returns
     /// * A HashMap of deck names and the given cards which belong to it.
     pub fn get_decks(&self, cards: &[ID]) -> Response<HashMap<String, Vec<ID>>> {
-        self.invoke("getDecks", Some(cards))
+        let mut data: HashMap<String, &[ID]> = HashMap::new();
+        data.insert("cards".into(), cards);
+
+        self.invoke("getDecks", Some(data))
     }
 
     /// Moves cards with the given IDs to a different deck, creating the deck if it doesn't exist yet.
@@ -200,12 +235,12 @@ impl AnkiConnect {
     ///
     /// # Returns
     /// * A error if there was one.
-    pub fn change_deck(&self, deck: &str, cards: &[ID]) {
+    pub fn change_deck(&self, deck: &str, cards: &[ID]) -> Response<()> {
         let mut data: HashMap<String, serde_json::Value> = HashMap::new();
         data.insert("deck".into(), deck.into());
         data.insert("cards".into(), cards.into());
 
-        let _: Response<()> = self.invoke("changeDeck", Some(data));
+        self.invoke("changeDeck", Some(data))
     }
 
     /// Deletes decks with the given names. If `cardsToo` is `true` (defaults to `false if` unspecified),
@@ -218,12 +253,12 @@ impl AnkiConnect {
     ///
     /// # Returns
     /// * A error if there was one.
-    pub fn delete_decks(&self, decks: &[&str], cards_too: bool) {
+    pub fn delete_decks(&self, decks: &[&str], cards_too: bool) -> Response<()> {
         let mut data: HashMap<String, serde_json::Value> = HashMap::new();
         data.insert("decks".into(), decks.into());
-        data.insert("cards_too".into(), cards_too.into());
+        data.insert("cardsToo".into(), cards_too.into());
 
-        let _: Response<()> = self.invoke("deleteDecks", Some(data));
+        self.invoke("deleteDecks", Some(data))
     }
 
     /// Gets the complete list of model names for the current user.
@@ -357,7 +392,7 @@ impl AnkiConnect {
     /// # Arguments
     /// * `id` - The id of the note.
     /// * `fields` - The fields and the new data.
-    pub fn update_note_fields(&self, id: ID, fields: &HashMap<String, String>) {
+    pub fn update_note_fields(&self, id: ID, fields: &HashMap<String, String>) -> Response<()> {
         let mut fields_json = serde_json::Map::new();
 
         for (field, data) in fields {
@@ -371,7 +406,7 @@ impl AnkiConnect {
         let mut data: HashMap<String, HashMap<String, serde_json::Value>> = HashMap::new();
         data.insert("note".into(), note);
 
-        let _: Response<()> = self.invoke("updateNoteFields", Some(data));
+        self.invoke("updateNoteFields", Some(data))
     }
 
     /// Modify the fields of an existing note.
@@ -393,7 +428,7 @@ impl AnkiConnect {
         filename: &str,
         fields: &[&str],
         skip_hash: Option<&str>,
-    ) {
+    ) -> Response<()> {
         let mut audio_json = serde_json::Map::new();
         audio_json.insert("url".into(), url.into());
         audio_json.insert("fields".into(), fields.into());
@@ -410,7 +445,7 @@ impl AnkiConnect {
         let mut data: HashMap<String, HashMap<String, serde_json::Value>> = HashMap::new();
         data.insert("note".into(), note);
 
-        let _: Response<()> = self.invoke("updateNoteFields", Some(data));
+        self.invoke("updateNoteFields", Some(data))
     }
 
     /// Adds tags to notes by note ID.
@@ -421,12 +456,12 @@ impl AnkiConnect {
     ///
     /// # Returns
     /// * A error if there was one.
-    pub fn add_tags(&self, notes: &[ID], tags: &str) {
+    pub fn add_tags(&self, notes: &[ID], tags: &str) -> Response<()> {
         let mut data: HashMap<String, serde_json::Value> = HashMap::new();
         data.insert("notes".into(), notes.into());
         data.insert("tags".into(), tags.into());
 
-        let _: Response<()> = self.invoke("addTags", Some(data));
+        self.invoke("addTags", Some(data))
     }
 
     /// Remove tags from notes by note ID.
@@ -437,12 +472,12 @@ impl AnkiConnect {
     ///
     /// # Returns
     /// * A error if there was one.
-    pub fn remove_tags(&self, notes: &[ID], tags: &str) {
+    pub fn remove_tags(&self, notes: &[ID], tags: &str) -> Response<()> {
         let mut data: HashMap<String, serde_json::Value> = HashMap::new();
         data.insert("notes".into(), notes.into());
         data.insert("tags".into(), tags.into());
 
-        let _: Response<()> = self.invoke("removeTags", Some(data));
+        self.invoke("removeTags", Some(data))
     }
 
     /// Gets the complete list of tags for the current user.
@@ -680,6 +715,120 @@ impl AnkiConnect {
         self.invoke("setDueDate", Some(data))
     }
 
+    /// Stores a file with the specified base64-encoded contents inside the media folder.
+    /// To prevent Anki from removing files not used by any cards (e.g. for configuration files),
+    /// prefix the filename with an underscore. If a file with the same name exists, it is
+    /// overwritten.
+    ///
+    /// # Arguments
+    /// * `filename` - The name of the media file.
+    /// * `data` - The contents of the file.
+    ///
+    /// # Returns
+    /// * The name the file was stored as.
+    pub fn store_media_file(&self, filename: &str, data: &[u8]) -> Response<String> {
+        let mut params: HashMap<String, serde_json::Value> = HashMap::new();
+        params.insert("filename".into(), filename.into());
+        params.insert("data".into(), BASE64_STANDARD.encode(data).into());
+
+        self.invoke("storeMediaFile", Some(params))
+    }
+
+    /// Stores a file from the machine Anki runs on inside the media folder, see
+    /// [`store_media_file`](Self::store_media_file).
+    ///
+    /// # Arguments
+    /// * `filename` - The name of the media file.
+    /// * `path` - The absolute path of the file to copy.
+    ///
+    /// # Returns
+    /// * The name the file was stored as.
+    pub fn store_media_file_from_path(&self, filename: &str, path: &Path) -> Response<String> {
+        let mut params: HashMap<String, serde_json::Value> = HashMap::new();
+        params.insert("filename".into(), filename.into());
+        params.insert("path".into(), path.to_string_lossy().into());
+
+        self.invoke("storeMediaFile", Some(params))
+    }
+
+    /// Downloads a file into the media folder, see [`store_media_file`](Self::store_media_file).
+    ///
+    /// # Arguments
+    /// * `filename` - The name of the media file.
+    /// * `url` - The url to download the file from.
+    ///
+    /// # Returns
+    /// * The name the file was stored as.
+    pub fn store_media_file_from_url(&self, filename: &str, url: &str) -> Response<String> {
+        let mut params: HashMap<String, serde_json::Value> = HashMap::new();
+        params.insert("filename".into(), filename.into());
+        params.insert("url".into(), url.into());
+
+        self.invoke("storeMediaFile", Some(params))
+    }
+
+    /// Retrieves the contents of the specified file.
+    ///
+    /// # Arguments
+    /// * `filename` - The name of the media file.
+    ///
+    /// # Returns
+    /// * The contents of the file, or `None` if it does not exist.
+    pub fn retrieve_media_file(&self, filename: &str) -> Response<Option<Vec<u8>>> {
+        let mut params: HashMap<String, serde_json::Value> = HashMap::new();
+        params.insert("filename".into(), filename.into());
+
+        // AnkiConnect answers `false` for missing files
+        let res: serde_json::Value = self.invoke("retrieveMediaFile", Some(params))?;
+        let Some(data) = res.as_str() else {
+            return Ok(None);
+        };
+
+        let data = BASE64_STANDARD
+            .decode(data)
+            .map_err(serde::de::Error::custom::<base64::DecodeError>)
+            .map_err(AnkiConnectError::Json)?;
+
+        Ok(Some(data))
+    }
+
+    /// Gets the names of the media files matching a pattern, `*` matching any text.
+    ///
+    /// # Arguments
+    /// * `pattern` - The pattern the names have to match, e.g. `*.mp3` or `*` for all files.
+    ///
+    /// # Returns
+    /// * A vector of the matching file names.
+    pub fn get_media_files_names(&self, pattern: &str) -> Response<Vec<String>> {
+        let mut params: HashMap<String, serde_json::Value> = HashMap::new();
+        params.insert("pattern".into(), pattern.into());
+
+        self.invoke("getMediaFilesNames", Some(params))
+    }
+
+    /// Gets the full path to the `collection.media` folder of the currently opened profile.
+    ///
+    /// # Returns
+    /// * The path of the media folder.
+    pub fn get_media_dir_path(&self) -> Response<PathBuf> {
+        let data: Option<()> = None;
+        self.invoke("getMediaDirPath", data)
+    }
+
+    /// Deletes the specified file inside the media folder.
+    ///
+    /// # Arguments
+    /// * `filename` - The name of the media file.
+    ///
+    /// # Returns
+    /// * A error if there was one.
+    pub fn delete_media_file(&self, filename: &str) -> Response<()> {
+        let mut params: HashMap<String, serde_json::Value> = HashMap::new();
+        params.insert("filename".into(), filename.into());
+
+        self.invoke("deleteMediaFile", Some(params))
+    }
+
     /// Invokes the _Card Browser_ dialog and searches for a given query.
     /// Returns an array of identifiers of the cards that were found.
     ///
@@ -699,9 +848,9 @@ impl AnkiConnect {
     ///
     /// # Returns
     /// * A vec of card id's.
-    pub fn gui_add_cards(&self) {
+    pub fn gui_add_cards(&self) -> Response<()> {
         let data: Option<()> = None;
-        let _: Response<()> = self.invoke("guiAddCards", Some(data));
+        self.invoke("guiAddCards", Some(data))
     }
 
     /// Opens the _Deck Overview_ dialog for the deck with the given name;
@@ -720,9 +869,9 @@ impl AnkiConnect {
     }
 
     /// Opens the _Deck Browser_ dialog.
-    pub fn gui_deck_browser(&self) {
+    pub fn gui_deck_browser(&self) -> Response<()> {
         let data: Option<()> = None;
-        let _: Response<()> = self.invoke("guiDeckBrowser", Some(data));
+        self.invoke("guiDeckBrowser", Some(data))
     }
 
     /// Starts review for the deck with the given name;
@@ -742,8 +891,8 @@ impl AnkiConnect {
 
     /// Schedules a request to gracefully close Anki. This operation is asynchronous,
     /// so it will return immediately and won't wait until the Anki process actually terminates.
-    pub fn gui_exit_anki(&self) {
+    pub fn gui_exit_anki(&self) -> Response<()> {
         let data: Option<()> = None;
-        let _: Response<()> = self.invoke("guiExitAnki", Some(data));
+        self.invoke("guiExitAnki", Some(data))
     }
 }
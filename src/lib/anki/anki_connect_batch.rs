@@ -0,0 +1,323 @@
+use std::collections::HashMap;
+
+use serde_json::json;
+
+use super::{
+    anki_connect::{AnkiConnect, Response, ResponseData},
+    anki_connect_error::ApiError,
+    anki_note::{AnkiNote, ID},
+};
+
+/// The default number of actions sent in a single `multi` request.
+pub const DEFAULT_BATCH_SIZE: usize = 500;
+
+/// An action that can be queued in an [`AnkiBatch`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum BatchAction {
+    /// Creates a note, see [`AnkiConnect::add_note`]. Results in [`BatchResult::Id`].
+    AddNote(AnkiNote),
+    /// Modifies the fields of a note, see [`AnkiConnect::update_note_fields`].
+    /// Results in [`BatchResult::Unit`].
+    UpdateNoteFields {
+        /// The id of the note.
+        note: ID,
+        /// The fields and the new data.
+        fields: HashMap<String, String>,
+    },
+    /// Adds tags to notes, see [`AnkiConnect::add_tags`]. Results in [`BatchResult::Unit`].
+    AddTags {
+        /// The id of the notes.
+        notes: Vec<ID>,
+        /// The space separated tags to add.
+        tags: String,
+    },
+    /// Removes tags from notes, see [`AnkiConnect::remove_tags`].
+    /// Results in [`BatchResult::Unit`].
+    RemoveTags {
+        /// The id of the notes.
+        notes: Vec<ID>,
+        /// The space separated tags to remove.
+        tags: String,
+    },
+    /// Moves cards to a deck, see [`AnkiConnect::change_deck`]. Results in [`BatchResult::Unit`].
+    ChangeDeck {
+        /// The deck to move to.
+        deck: String,
+        /// The id of the cards.
+        cards: Vec<ID>,
+    },
+    /// Suspends cards, see [`AnkiConnect::suspend`]. Results in [`BatchResult::Bool`].
+    Suspend(Vec<ID>),
+    /// Unsuspends cards, see [`AnkiConnect::unsuspend`]. Results in [`BatchResult::Bool`].
+    Unsuspend(Vec<ID>),
+    /// Sets values of a card, see [`AnkiConnect::set_specific_value_of_card`].
+    /// Results in [`BatchResult::Bools`].
+    SetSpecificValueOfCard {
+        /// The id of the card.
+        card: ID,
+        /// The keys and new values to set.
+        properties: Vec<(String, serde_json::Value)>,
+    },
+    /// Sets the due date of cards, see [`AnkiConnect::set_due_date`].
+    /// Results in [`BatchResult::Bool`].
+    SetDueDate {
+        /// The id of the cards.
+        cards: Vec<ID>,
+        /// The new due date, e.g. `0`, `1!` or `3-7`.
+        days: String,
+    },
+}
+
+impl BatchAction {
+    /// Returns the AnkiConnect name of the action.
+    pub fn action(&self) -> &'static str {
+        match self {
+            BatchAction::AddNote(_) => "addNote",
+            BatchAction::UpdateNoteFields { .. } => "updateNoteFields",
+            BatchAction::AddTags { .. } => "addTags",
+            BatchAction::RemoveTags { .. } => "removeTags",
+            BatchAction::ChangeDeck { .. } => "changeDeck",
+            BatchAction::Suspend(_) => "suspend",
+            BatchAction::Unsuspend(_) => "unsuspend",
+            BatchAction::SetSpecificValueOfCard { .. } => "setSpecificValueOfCard",
+            BatchAction::SetDueDate { .. } => "setDueDate",
+        }
+    }
+
+    /// Returns the parameters of the action.
+    fn params(&self) -> serde_json::Value {
+        match self {
+            BatchAction::AddNote(note) => json!({ "note": note }),
+            BatchAction::UpdateNoteFields { note, fields } => {
+                json!({ "note": { "id": note, "fields": fields } })
+            }
+            BatchAction::AddTags { notes, tags } | BatchAction::RemoveTags { notes, tags } => {
+                json!({ "notes": notes, "tags": tags })
+            }
+            BatchAction::ChangeDeck { deck, cards } => json!({ "deck": deck, "cards": cards }),
+            BatchAction::Suspend(cards) | BatchAction::Unsuspend(cards) => {
+                json!({ "cards": cards })
+            }
+            BatchAction::SetSpecificValueOfCard { card, properties } => json!({
+                "card": card,
+                "keys": properties.iter().map(|(key, _)| key).collect::<Vec<_>>(),
+                "newValues": properties.iter().map(|(_, value)| value).collect::<Vec<_>>(),
+            }),
+            BatchAction::SetDueDate { cards, days } => json!({ "cards": cards, "days": days }),
+        }
+    }
+
+    /// Serializes the action as an entry of a `multi` request. Entries are sent with version 6
+    /// so that every entry gets its own result and error.
+    fn to_json(&self) -> serde_json::Value {
+        json!({
+            "action": self.action(),
+            "version": 6,
+            "params": self.params(),
+        })
+    }
+
+    /// Decodes the response of this action into its typed result.
+    fn decode(&self, response: ResponseData) -> Response<BatchResult> {
+        Ok(match self {
+            BatchAction::AddNote(_) => BatchResult::Id(response.into_result()?),
+            BatchAction::UpdateNoteFields { .. }
+            | BatchAction::AddTags { .. }
+            | BatchAction::RemoveTags { .. }
+            | BatchAction::ChangeDeck { .. } => {
+                response.into_result::<()>()?;
+                BatchResult::Unit
+            }
+            BatchAction::Suspend(_)
+            | BatchAction::Unsuspend(_)
+            | BatchAction::SetDueDate { .. } => BatchResult::Bool(response.into_result()?),
+            BatchAction::SetSpecificValueOfCard { .. } => {
+                BatchResult::Bools(response.into_result()?)
+            }
+        })
+    }
+}
+
+/// The typed result of a single [`BatchAction`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BatchResult {
+    /// The action has no result.
+    Unit,
+    /// The action returned a boolean.
+    Bool(bool),
+    /// The action returned a boolean per value.
+    Bools(Vec<bool>),
+    /// The action returned the id of a created object.
+    Id(ID),
+}
+
+/// Queues actions and sends them as `multi` requests, splitting them into chunks of at most
+/// `batch_size` actions.
+///
+/// # Example
+/// ```no_run
+/// use anki_utill::anki::{anki_connect::AnkiConnect, anki_connect_batch::BatchAction};
+///
+/// let anki = AnkiConnect::default();
+/// let mut batch = anki.batch();
+/// batch.push(BatchAction::Suspend(vec![1]));
+/// batch.push(BatchAction::Unsuspend(vec![2]));
+///
+/// for result in batch.send().unwrap() {
+///     println!("{:?}", result);
+/// }
+/// ```
+pub struct AnkiBatch<'a> {
+    anki: &'a AnkiConnect,
+    actions: Vec<BatchAction>,
+    batch_size: usize,
+}
+
+impl<'a> AnkiBatch<'a> {
+    /// Creates an empty batch using the default batch size.
+    pub fn new(anki: &'a AnkiConnect) -> Self {
+        Self {
+            anki,
+            actions: Vec::new(),
+            batch_size: DEFAULT_BATCH_SIZE,
+        }
+    }
+
+    /// Sets the maximum number of actions sent in a single request.
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// Queues an action and returns its index in the results.
+    pub fn push(&mut self, action: BatchAction) -> usize {
+        self.actions.push(action);
+        self.actions.len() - 1
+    }
+
+    /// Returns the number of queued actions.
+    pub fn len(&self) -> usize {
+        self.actions.len()
+    }
+
+    /// Returns whether no actions are queued.
+    pub fn is_empty(&self) -> bool {
+        self.actions.is_empty()
+    }
+
+    /// Returns the queued actions.
+    pub fn actions(&self) -> &[BatchAction] {
+        &self.actions
+    }
+
+    /// Sends all queued actions.
+    ///
+    /// # Returns
+    /// * A result per queued action, in the order they were pushed, or an error if a request
+    ///   as a whole failed.
+    pub fn send(self) -> Response<Vec<Response<BatchResult>>> {
+        let mut out = Vec::with_capacity(self.actions.len());
+
+        for chunk in self.actions.chunks(self.batch_size) {
+            let actions: Vec<serde_json::Value> =
+                chunk.iter().map(|action| action.to_json()).collect();
+
+            let mut data: HashMap<String, serde_json::Value> = HashMap::new();
+            data.insert("actions".into(), actions.into());
+
+            let responses: Vec<ResponseData> = self.anki.invoke("multi", Some(data))?;
+
+            out.extend(decode_responses(chunk, responses));
+        }
+
+        Ok(out)
+    }
+}
+
+/// Decodes the responses of a chunk, reporting actions without a response as missing.
+fn decode_responses(
+    chunk: &[BatchAction],
+    responses: Vec<ResponseData>,
+) -> Vec<Response<BatchResult>> {
+    let mut responses = responses.into_iter();
+
+    chunk
+        .iter()
+        .map(|action| match responses.next() {
+            Some(response) => action.decode(response),
+            None => {
+                Err(ApiError::Other(format!("No response for action '{}'", action.action())).into())
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tests the serialization of queued actions.
+    #[test]
+    fn serialize_actions() {
+        let action = BatchAction::SetSpecificValueOfCard {
+            card: 5,
+            properties: vec![("due".to_owned(), 10.into())],
+        };
+
+        assert_eq!(
+            action.to_json(),
+            json!({
+                "action": "setSpecificValueOfCard",
+                "version": 6,
+                "params": { "card": 5, "keys": ["due"], "newValues": [10] },
+            })
+        );
+
+        let action = BatchAction::AddTags {
+            notes: vec![1, 2],
+            tags: "JLPT-N5".to_owned(),
+        };
+
+        assert_eq!(
+            action.to_json(),
+            json!({
+                "action": "addTags",
+                "version": 6,
+                "params": { "notes": [1, 2], "tags": "JLPT-N5" },
+            })
+        );
+    }
+
+    /// Tests decoding of per action results, including errors and missing responses.
+    #[test]
+    fn decode_results() {
+        let chunk = vec![
+            BatchAction::Suspend(vec![1]),
+            BatchAction::UpdateNoteFields {
+                note: 1,
+                fields: HashMap::new(),
+            },
+            BatchAction::AddNote(AnkiNote::default()),
+            BatchAction::Unsuspend(vec![1]),
+        ];
+
+        let responses: Vec<ResponseData> = serde_json::from_str(
+            r#"[
+                {"result": true, "error": null},
+                {"result": null, "error": null},
+                {"result": null, "error": "cannot create note because it is a duplicate"}
+            ]"#,
+        )
+        .unwrap();
+
+        let results = decode_responses(&chunk, responses);
+
+        assert_eq!(results[0].as_ref().unwrap(), &BatchResult::Bool(true));
+        assert_eq!(results[1].as_ref().unwrap(), &BatchResult::Unit);
+        assert!(results[2].as_ref().unwrap_err().is_duplicate());
+        assert!(matches!(
+            results[3].as_ref().unwrap_err().api_error(),
+            Some(ApiError::Other(_))
+        ));
+    }
+}
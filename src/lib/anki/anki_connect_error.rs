@@ -0,0 +1,195 @@
+use std::fmt;
+
+/// Represents a failed AnkiConnect request.
+#[derive(Debug)]
+pub enum AnkiConnectError {
+    /// The request could not be sent or the response could not be read,
+    /// e.g. because Anki is not running.
+    Transport(reqwest::Error),
+    /// The server answered with a non-success HTTP status.
+    Http {
+        /// The HTTP status code.
+        status: u16,
+        /// The body of the response.
+        body: String,
+    },
+    /// The request could not be serialized or the response could not be decoded.
+    Json(serde_json::Error),
+    /// AnkiConnect executed the request but reported an error.
+    Api(ApiError),
+    /// The AnkiConnect plugin reports a different API version than expected.
+    VersionMismatch {
+        /// The version this client was written against.
+        expected: u8,
+        /// The version reported by the plugin.
+        found: u8,
+    },
+}
+
+impl AnkiConnectError {
+    /// Returns the API error if AnkiConnect reported one.
+    pub fn api_error(&self) -> Option<&ApiError> {
+        match self {
+            AnkiConnectError::Api(err) => Some(err),
+            _ => None,
+        }
+    }
+
+    /// Returns whether the request failed because the note is a duplicate.
+    pub fn is_duplicate(&self) -> bool {
+        matches!(self, AnkiConnectError::Api(ApiError::DuplicateNote))
+    }
+}
+
+impl fmt::Display for AnkiConnectError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AnkiConnectError::Transport(err) => write!(f, "Failed to reach AnkiConnect: {err}"),
+            AnkiConnectError::Http { status, body } => {
+                write!(f, "AnkiConnect answered with HTTP {status}: {body}")
+            }
+            AnkiConnectError::Json(err) => write!(f, "Invalid AnkiConnect JSON: {err}"),
+            AnkiConnectError::Api(err) => write!(f, "AnkiConnect error: {err}"),
+            AnkiConnectError::VersionMismatch { expected, found } => write!(
+                f,
+                "Expected AnkiConnect version '{expected}' but got '{found}' instead!"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for AnkiConnectError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            AnkiConnectError::Transport(err) => Some(err),
+            AnkiConnectError::Json(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<reqwest::Error> for AnkiConnectError {
+    fn from(err: reqwest::Error) -> Self {
+        AnkiConnectError::Transport(err)
+    }
+}
+
+impl From<serde_json::Error> for AnkiConnectError {
+    fn from(err: serde_json::Error) -> Self {
+        AnkiConnectError::Json(err)
+    }
+}
+
+impl From<ApiError> for AnkiConnectError {
+    fn from(err: ApiError) -> Self {
+        AnkiConnectError::Api(err)
+    }
+}
+
+/// An error string returned by AnkiConnect, with the known cases parsed out.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ApiError {
+    /// The note could not be created because an identical note already exists.
+    DuplicateNote,
+    /// The note could not be created because its first field is empty.
+    EmptyNote,
+    /// The given note type does not exist.
+    ModelNotFound(String),
+    /// The given deck does not exist.
+    DeckNotFound(String),
+    /// The given note id does not exist.
+    NoteNotFound(String),
+    /// The collection is not loaded, e.g. because a profile is being switched.
+    CollectionUnavailable,
+    /// The requested action is not supported by this version of AnkiConnect.
+    UnsupportedAction,
+    /// The request was missing a valid api key.
+    InvalidApiKey,
+    /// An error that is not recognised.
+    Other(String),
+}
+
+impl From<&str> for ApiError {
+    /// Parses an AnkiConnect error string.
+    fn from(message: &str) -> Self {
+        let lower = message.to_lowercase();
+
+        // Extracts the text after the first `:`, e.g. the name in "model was not found: Basic".
+        let detail = || {
+            message
+                .split_once(':')
+                .map(|(_, name)| name.trim().to_owned())
+                .unwrap_or_default()
+        };
+
+        if lower.contains("duplicate") {
+            ApiError::DuplicateNote
+        } else if lower.contains("note is empty") || lower.contains("because it is empty") {
+            ApiError::EmptyNote
+        } else if lower.starts_with("model was not found") {
+            ApiError::ModelNotFound(detail())
+        } else if lower.starts_with("deck was not found") {
+            ApiError::DeckNotFound(detail())
+        } else if lower.starts_with("note was not found") {
+            ApiError::NoteNotFound(detail())
+        } else if lower.contains("collection is not available") {
+            ApiError::CollectionUnavailable
+        } else if lower.contains("unsupported action") {
+            ApiError::UnsupportedAction
+        } else if lower.contains("api key") {
+            ApiError::InvalidApiKey
+        } else {
+            ApiError::Other(message.to_owned())
+        }
+    }
+}
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ApiError::DuplicateNote => write!(f, "the note is a duplicate"),
+            ApiError::EmptyNote => write!(f, "the note is empty"),
+            ApiError::ModelNotFound(name) => write!(f, "model '{name}' was not found"),
+            ApiError::DeckNotFound(name) => write!(f, "deck '{name}' was not found"),
+            ApiError::NoteNotFound(id) => write!(f, "note '{id}' was not found"),
+            ApiError::CollectionUnavailable => write!(f, "the collection is not available"),
+            ApiError::UnsupportedAction => write!(f, "unsupported action"),
+            ApiError::InvalidApiKey => write!(f, "a valid api key must be provided"),
+            ApiError::Other(message) => write!(f, "{message}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tests parsing of the error strings AnkiConnect returns.
+    #[test]
+    fn parse_api_error() {
+        assert_eq!(
+            ApiError::from("cannot create note because it is a duplicate"),
+            ApiError::DuplicateNote
+        );
+        assert_eq!(
+            ApiError::from("cannot create note because it is empty"),
+            ApiError::EmptyNote
+        );
+        assert_eq!(
+            ApiError::from("model was not found: JP Card V4"),
+            ApiError::ModelNotFound("JP Card V4".to_owned())
+        );
+        assert_eq!(
+            ApiError::from("deck was not found: My Deck 4.0"),
+            ApiError::DeckNotFound("My Deck 4.0".to_owned())
+        );
+        assert_eq!(
+            ApiError::from("Note was not found: 1502098034045"),
+            ApiError::NoteNotFound("1502098034045".to_owned())
+        );
+        assert_eq!(
+            ApiError::from("something else"),
+            ApiError::Other("something else".to_owned())
+        );
+    }
+}
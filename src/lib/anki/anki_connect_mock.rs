@@ -0,0 +1,1205 @@
+//! An in-process fake of the AnkiConnect plugin, used to test code talking to Anki on a machine
+//! without Anki installed.
+//!
+//! The server answers the JSON-RPC protocol on a random local port from an in-memory
+//! [`MockCollection`] and records every request it receives.
+//!
+//! # Example
+//! ```
+//! use anki_utill::anki::anki_connect_mock::MockAnkiConnect;
+//!
+//! let mock = MockAnkiConnect::start().unwrap();
+//! mock.with_collection(|collection| {
+//!     collection.add_deck("My Deck 4.0");
+//!     collection.add_model("JP Card V4", &["1 Word", "2 Meaning"]);
+//! });
+//!
+//! let anki = mock.connect().unwrap();
+//! assert_eq!(anki.deck_names().unwrap(), vec!["Default", "My Deck 4.0"]);
+//! assert_eq!(mock.actions(), vec!["version", "deckNames"]);
+//! ```
+
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    fs,
+    io::{self, BufRead, BufReader, Read, Write},
+    net::{SocketAddr, TcpListener, TcpStream},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex, MutexGuard,
+    },
+    thread::{self, JoinHandle},
+};
+
+use base64::prelude::*;
+use serde_json::{json, Value};
+
+use super::{
+    anki_connect::{AnkiConnect, Response},
+    anki_note::ID,
+};
+
+/// The first id handed out by a [`MockCollection`], a millisecond timestamp like the ids Anki uses.
+const FIRST_ID: ID = 1_700_000_000_000;
+
+/// The media folder reported by `getMediaDirPath`.
+pub const MEDIA_DIR: &str = "/mock/collection.media";
+
+/// A note type of the mock collection.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MockModel {
+    /// The id of the model.
+    pub id: ID,
+    /// The names of the fields, in order.
+    pub fields: Vec<String>,
+}
+
+/// A note of the mock collection.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MockNote {
+    /// The id of the note.
+    pub id: ID,
+    /// The name of the model of the note.
+    pub model: String,
+    /// The content of every field of the model.
+    pub fields: HashMap<String, String>,
+    /// The tags of the note.
+    pub tags: Vec<String>,
+    /// The cards of the note.
+    pub cards: Vec<ID>,
+    /// The modification counter of the note, increased on every change.
+    pub modified: i64,
+}
+
+/// A card of the mock collection.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MockCard {
+    /// The id of the card.
+    pub id: ID,
+    /// The id of the note of the card.
+    pub note: ID,
+    /// The name of the deck of the card.
+    pub deck: String,
+    /// Whether the card has not been studied yet.
+    pub new: bool,
+    /// Whether the card is suspended.
+    pub suspended: bool,
+    /// Whether the card is buried.
+    pub buried: bool,
+    /// The due position for new cards or the due day for review cards.
+    pub due: i64,
+    /// Values set through `setSpecificValueOfCard` other than `due`.
+    pub values: HashMap<String, Value>,
+}
+
+/// An in-memory Anki collection answering AnkiConnect actions.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MockCollection {
+    /// The decks by name.
+    pub decks: BTreeMap<String, ID>,
+    /// The models by name.
+    pub models: BTreeMap<String, MockModel>,
+    /// The notes by id.
+    pub notes: BTreeMap<ID, MockNote>,
+    /// The cards by id.
+    pub cards: BTreeMap<ID, MockCard>,
+    /// The contents of the media files by filename.
+    pub media: BTreeMap<String, Vec<u8>>,
+    next_id: ID,
+}
+
+impl Default for MockCollection {
+    fn default() -> Self {
+        let mut collection = Self {
+            decks: BTreeMap::new(),
+            models: BTreeMap::new(),
+            notes: BTreeMap::new(),
+            cards: BTreeMap::new(),
+            media: BTreeMap::new(),
+            next_id: FIRST_ID,
+        };
+
+        collection.add_deck("Default");
+        collection
+    }
+}
+
+impl MockCollection {
+    /// Returns a new unique id.
+    fn next_id(&mut self) -> ID {
+        self.next_id += 1;
+        self.next_id
+    }
+
+    /// Creates a deck if it does not exist yet.
+    ///
+    /// # Returns
+    /// * The id of the deck.
+    pub fn add_deck(&mut self, name: &str) -> ID {
+        if let Some(id) = self.decks.get(name) {
+            return *id;
+        }
+
+        let id = self.next_id();
+        self.decks.insert(name.to_owned(), id);
+        id
+    }
+
+    /// Creates a model with the given fields, replacing any model with the same name.
+    ///
+    /// # Returns
+    /// * The id of the model.
+    pub fn add_model(&mut self, name: &str, fields: &[&str]) -> ID {
+        let id = self.next_id();
+        self.models.insert(
+            name.to_owned(),
+            MockModel {
+                id,
+                fields: fields.iter().map(|field| field.to_string()).collect(),
+            },
+        );
+        id
+    }
+
+    /// Creates a note with a single new card, applying the same checks as `addNote`.
+    ///
+    /// # Arguments
+    /// * `deck` - The deck of the card.
+    /// * `model` - The model of the note.
+    /// * `fields` - The content of the fields, missing fields are left empty.
+    /// * `tags` - The tags of the note.
+    ///
+    /// # Returns
+    /// * The id of the note, or the AnkiConnect error message.
+    pub fn add_note(
+        &mut self,
+        deck: &str,
+        model: &str,
+        fields: &[(&str, &str)],
+        tags: &[&str],
+    ) -> Result<ID, String> {
+        let fields: HashMap<String, String> = fields
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect();
+        let tags: Vec<String> = tags.iter().map(|tag| tag.to_string()).collect();
+
+        self.create_note(deck, model, &fields, &tags)
+    }
+
+    /// Returns a note by id.
+    pub fn note(&self, id: ID) -> Option<&MockNote> {
+        self.notes.get(&id)
+    }
+
+    /// Returns a card by id.
+    pub fn card(&self, id: ID) -> Option<&MockCard> {
+        self.cards.get(&id)
+    }
+
+    /// Returns the content of a field of a note.
+    pub fn field(&self, note: ID, field: &str) -> Option<&str> {
+        self.notes
+            .get(&note)?
+            .fields
+            .get(field)
+            .map(|value| value.as_str())
+    }
+
+    /// Returns the ids of the cards matching a search query.
+    ///
+    /// Supports the subset of the Anki search syntax used by this crate: `deck:`, `note:`, `tag:`,
+    /// `nid:`, `cid:`, `is:new`, `is:suspended`, `is:buried`, `is:due`, `field:value` and plain
+    /// text, each optionally quoted and negated with `-`. `*` matches any text. Parentheses are
+    /// ignored, so every term has to match.
+    pub fn find_cards(&self, query: &str) -> Vec<ID> {
+        let terms = tokenize(query);
+
+        self.cards
+            .values()
+            .filter(|card| {
+                terms
+                    .iter()
+                    .all(|(negate, term)| self.matches(card, term) != *negate)
+            })
+            .map(|card| card.id)
+            .collect()
+    }
+
+    /// Returns the ids of the notes with a card matching a search query, see
+    /// [`MockCollection::find_cards`].
+    pub fn find_notes(&self, query: &str) -> Vec<ID> {
+        let notes: BTreeSet<ID> = self
+            .find_cards(query)
+            .into_iter()
+            .map(|card| self.cards[&card].note)
+            .collect();
+
+        notes.into_iter().collect()
+    }
+
+    /// Returns whether a card matches a single search term.
+    fn matches(&self, card: &MockCard, term: &str) -> bool {
+        let note = &self.notes[&card.note];
+
+        let Some((key, value)) = term.split_once(':') else {
+            return note
+                .fields
+                .values()
+                .any(|field| field.to_lowercase().contains(&term.to_lowercase()));
+        };
+
+        match key.to_lowercase().as_str() {
+            "deck" => {
+                wildcard_match(value, &card.deck) || card.deck.starts_with(&format!("{}::", value))
+            }
+            "note" => wildcard_match(value, &note.model),
+            "tag" => note.tags.iter().any(|tag| wildcard_match(value, tag)),
+            "nid" => id_list(value).contains(&note.id),
+            "cid" => id_list(value).contains(&card.id),
+            "is" => match value {
+                "new" => card.new,
+                "suspended" => card.suspended,
+                "buried" => card.buried,
+                "due" => !card.new && !card.suspended && card.due <= 0,
+                _ => false,
+            },
+            _ => note
+                .fields
+                .iter()
+                .find(|(name, _)| name.eq_ignore_ascii_case(key))
+                .is_some_and(|(_, field)| match value {
+                    "" => field.is_empty(),
+                    "_*" => !field.is_empty(),
+                    _ => wildcard_match(value, field),
+                }),
+        }
+    }
+
+    /// Validates and creates a note with a single new card.
+    fn create_note(
+        &mut self,
+        deck: &str,
+        model: &str,
+        fields: &HashMap<String, String>,
+        tags: &[String],
+    ) -> Result<ID, String> {
+        self.check_note(deck, model, fields)?;
+
+        let model_fields = self.models[model].fields.clone();
+        let id = self.next_id();
+        let card = self.next_id();
+
+        let due = self.cards.values().filter(|card| card.new).count() as i64 + 1;
+
+        self.cards.insert(
+            card,
+            MockCard {
+                id: card,
+                note: id,
+                deck: deck.to_owned(),
+                new: true,
+                suspended: false,
+                buried: false,
+                due,
+                values: HashMap::new(),
+            },
+        );
+
+        self.notes.insert(
+            id,
+            MockNote {
+                id,
+                model: model.to_owned(),
+                fields: model_fields
+                    .into_iter()
+                    .map(|name| {
+                        let value = fields.get(&name).cloned().unwrap_or_default();
+                        (name, value)
+                    })
+                    .collect(),
+                tags: tags.to_vec(),
+                cards: vec![card],
+                modified: 0,
+            },
+        );
+
+        Ok(id)
+    }
+
+    /// Checks whether a note could be created, returning the AnkiConnect error if not.
+    fn check_note(
+        &self,
+        deck: &str,
+        model: &str,
+        fields: &HashMap<String, String>,
+    ) -> Result<(), String> {
+        let Some(model_data) = self.models.get(model) else {
+            return Err(format!("model was not found: {}", model));
+        };
+
+        if !self.decks.contains_key(deck) {
+            return Err(format!("deck was not found: {}", deck));
+        }
+
+        let first_field = model_data.fields.first().and_then(|name| fields.get(name));
+        let Some(first) = first_field.filter(|value| !value.is_empty()) else {
+            return Err("cannot create note because it is empty".to_owned());
+        };
+
+        let duplicate = self.notes.values().any(|note| {
+            note.model == model
+                && note.fields.get(&model_data.fields[0]) == Some(first)
+                && note.cards.iter().any(|card| self.cards[card].deck == deck)
+        });
+
+        if duplicate {
+            return Err("cannot create note because it is a duplicate".to_owned());
+        }
+
+        Ok(())
+    }
+
+    /// Returns the note with the given id or the AnkiConnect error.
+    fn note_mut(&mut self, id: ID) -> Result<&mut MockNote, String> {
+        self.notes
+            .get_mut(&id)
+            .ok_or_else(|| format!("Note was not found: {}", id))
+    }
+
+    /// Returns the card with the given id or the AnkiConnect error.
+    fn card_mut(&mut self, id: ID) -> Result<&mut MockCard, String> {
+        self.cards
+            .get_mut(&id)
+            .ok_or_else(|| format!("Card was not found: {}", id))
+    }
+
+    /// Executes a single action.
+    ///
+    /// # Returns
+    /// * The result of the action, or the AnkiConnect error message.
+    fn execute(&mut self, action: &str, params: &Value) -> Result<Value, String> {
+        match action {
+            "deckNames" => Ok(json!(self.decks.keys().collect::<Vec<_>>())),
+            "deckNamesAndIds" => Ok(json!(self.decks)),
+            "createDeck" => Ok(json!(self.add_deck(&param::<String>(params, "deck")?))),
+            "getDecks" => {
+                let mut decks: BTreeMap<String, Vec<ID>> = BTreeMap::new();
+                for id in param::<Vec<ID>>(params, "cards")? {
+                    if let Some(card) = self.cards.get(&id) {
+                        decks.entry(card.deck.clone()).or_default().push(id);
+                    }
+                }
+                Ok(json!(decks))
+            }
+            "changeDeck" => {
+                let deck: String = param(params, "deck")?;
+                self.add_deck(&deck);
+                for id in param::<Vec<ID>>(params, "cards")? {
+                    self.card_mut(id)?.deck = deck.clone();
+                }
+                Ok(Value::Null)
+            }
+            "deleteDecks" => {
+                let decks: Vec<String> = param(params, "decks")?;
+                let cards_too = params["cardsToo"].as_bool().unwrap_or(false);
+                self.delete_decks(&decks, cards_too);
+                Ok(Value::Null)
+            }
+            "modelNames" => Ok(json!(self.models.keys().collect::<Vec<_>>())),
+            "modelNamesAndIds" => Ok(json!(self
+                .models
+                .iter()
+                .map(|(name, model)| (name, model.id))
+                .collect::<BTreeMap<_, _>>())),
+            "modelFieldNames" => {
+                let name: String = param(params, "modelName")?;
+                match self.models.get(&name) {
+                    Some(model) => Ok(json!(model.fields)),
+                    None => Err(format!("model was not found: {}", name)),
+                }
+            }
+            "addNote" => self.add_note_json(&params["note"]).map(|id| json!(id)),
+            "addNotes" => Ok(json!(param::<Vec<Value>>(params, "notes")?
+                .iter()
+                .map(|note| self.add_note_json(note).ok())
+                .collect::<Vec<_>>())),
+            "canAddNotes" => Ok(json!(param::<Vec<Value>>(params, "notes")?
+                .iter()
+                .map(|note| {
+                    let (deck, model, fields, _) = note_params(note)?;
+                    self.check_note(&deck, &model, &fields)
+                })
+                .map(|result| result.is_ok())
+                .collect::<Vec<_>>())),
+            "updateNoteFields" => {
+                self.update_note_fields(&params["note"])?;
+                Ok(Value::Null)
+            }
+            "addTags" | "removeTags" => {
+                let tags: String = param(params, "tags")?;
+                for id in param::<Vec<ID>>(params, "notes")? {
+                    let note = self.note_mut(id)?;
+                    for tag in tags.split_whitespace() {
+                        let has_tag = note.tags.iter().any(|t| t.eq_ignore_ascii_case(tag));
+                        if action == "addTags" && !has_tag {
+                            note.tags.push(tag.to_owned());
+                        } else if action == "removeTags" {
+                            note.tags.retain(|t| !t.eq_ignore_ascii_case(tag));
+                        }
+                    }
+                    note.modified += 1;
+                }
+                Ok(Value::Null)
+            }
+            "getTags" => {
+                let tags: BTreeSet<&String> =
+                    self.notes.values().flat_map(|note| &note.tags).collect();
+                Ok(json!(tags))
+            }
+            "findNotes" => Ok(json!(self.find_notes(&param::<String>(params, "query")?))),
+            "findCards" => Ok(json!(self.find_cards(&param::<String>(params, "query")?))),
+            "notesInfo" => Ok(json!(param::<Vec<ID>>(params, "notes")?
+                .into_iter()
+                .map(|id| self.note_info(id))
+                .collect::<Vec<_>>())),
+            "cardsToNotes" => {
+                let notes: BTreeSet<ID> = param::<Vec<ID>>(params, "cards")?
+                    .into_iter()
+                    .filter_map(|id| self.cards.get(&id).map(|card| card.note))
+                    .collect();
+                Ok(json!(notes))
+            }
+            "suspend" | "unsuspend" => {
+                let suspend = action == "suspend";
+                let mut changed = false;
+                for id in param::<Vec<ID>>(params, "cards")? {
+                    let card = self.card_mut(id)?;
+                    changed |= card.suspended != suspend;
+                    card.suspended = suspend;
+                }
+                Ok(json!(changed))
+            }
+            "areSuspended" => Ok(json!(param::<Vec<ID>>(params, "cards")?
+                .into_iter()
+                .map(|id| self.cards.get(&id).map(|card| card.suspended))
+                .collect::<Vec<_>>())),
+            "areDue" => Ok(json!(param::<Vec<ID>>(params, "cards")?
+                .into_iter()
+                .map(|id| self
+                    .cards
+                    .get(&id)
+                    .is_some_and(|card| !card.new && card.due <= 0))
+                .collect::<Vec<_>>())),
+            "getIntervals" => Ok(json!(param::<Vec<ID>>(params, "cards")?
+                .into_iter()
+                .map(|_| 0)
+                .collect::<Vec<_>>())),
+            "setSpecificValueOfCard" => {
+                let keys: Vec<String> = param(params, "keys")?;
+                let values: Vec<Value> = param(params, "newValues")?;
+                let card = self.card_mut(param(params, "card")?)?;
+
+                Ok(json!(keys
+                    .into_iter()
+                    .zip(values)
+                    .map(|(key, value)| match key.as_str() {
+                        "due" => match value.as_i64() {
+                            Some(due) => {
+                                card.due = due;
+                                true
+                            }
+                            None => false,
+                        },
+                        _ => {
+                            card.values.insert(key, value);
+                            true
+                        }
+                    })
+                    .collect::<Vec<_>>()))
+            }
+            "setDueDate" => {
+                let days: String = param(params, "days")?;
+                let due: i64 = days
+                    .trim_end_matches('!')
+                    .split('-')
+                    .next()
+                    .and_then(|days| days.parse().ok())
+                    .ok_or_else(|| format!("invalid due date: {}", days))?;
+
+                for id in param::<Vec<ID>>(params, "cards")? {
+                    let card = self.card_mut(id)?;
+                    card.new = false;
+                    card.due = due;
+                }
+                Ok(json!(true))
+            }
+            "storeMediaFile" => {
+                let filename: String = param(params, "filename")?;
+                let data = if let Some(path) = params["path"].as_str() {
+                    fs::read(path).map_err(|err| format!("{}: {}", path, err))?
+                } else if params["url"].is_string() {
+                    // Downloads are not made, like the audio of `updateNoteFields`
+                    Vec::new()
+                } else {
+                    BASE64_STANDARD
+                        .decode(param::<String>(params, "data")?)
+                        .map_err(|err| format!("invalid parameter 'data': {}", err))?
+                };
+
+                self.media.insert(filename.clone(), data);
+                Ok(json!(filename))
+            }
+            "retrieveMediaFile" => Ok(self
+                .media
+                .get(&param::<String>(params, "filename")?)
+                .map_or(json!(false), |data| json!(BASE64_STANDARD.encode(data)))),
+            "getMediaFilesNames" => {
+                let pattern: String = param(params, "pattern")?;
+                Ok(json!(self
+                    .media
+                    .keys()
+                    .filter(|name| wildcard_match(&pattern, name))
+                    .collect::<Vec<_>>()))
+            }
+            "getMediaDirPath" => Ok(json!(MEDIA_DIR)),
+            "deleteMediaFile" => {
+                self.media.remove(&param::<String>(params, "filename")?);
+                Ok(Value::Null)
+            }
+            "guiBrowse" => Ok(json!(self.find_cards(&param::<String>(params, "query")?))),
+            "guiDeckOverview" | "guiDeckReview" => {
+                Ok(json!(self
+                    .decks
+                    .contains_key(&param::<String>(params, "name")?)))
+            }
+            "guiAddCards" | "guiDeckBrowser" | "guiExitAnki" => Ok(Value::Null),
+            _ => Err("unsupported action".to_owned()),
+        }
+    }
+
+    /// Deletes decks, moving their cards to the default deck unless `cards_too` is set.
+    fn delete_decks(&mut self, decks: &[String], cards_too: bool) {
+        for deck in decks {
+            self.decks.remove(deck);
+        }
+
+        let (deleted, moved): (Vec<ID>, Vec<ID>) = self
+            .cards
+            .values()
+            .filter(|card| decks.contains(&card.deck))
+            .map(|card| card.id)
+            .partition(|_| cards_too);
+
+        for id in moved {
+            self.add_deck("Default");
+            self.cards.get_mut(&id).unwrap().deck = "Default".to_owned();
+        }
+
+        for id in deleted {
+            let card = self.cards.remove(&id).unwrap();
+            let note = self.notes.get_mut(&card.note).unwrap();
+            note.cards.retain(|c| *c != id);
+
+            if note.cards.is_empty() {
+                self.notes.remove(&card.note);
+            }
+        }
+    }
+
+    /// Creates a note from the JSON parameters of `addNote`.
+    fn add_note_json(&mut self, note: &Value) -> Result<ID, String> {
+        let (deck, model, fields, tags) = note_params(note)?;
+        self.create_note(&deck, &model, &fields, &tags)
+    }
+
+    /// Applies the JSON parameters of `updateNoteFields`. Audio is added as a `[sound:]` tag to
+    /// the listed fields and as an empty media file, as downloads are not made.
+    fn update_note_fields(&mut self, params: &Value) -> Result<(), String> {
+        let id: ID = param(params, "id")?;
+        let fields: HashMap<String, String> =
+            serde_json::from_value(params["fields"].clone()).unwrap_or_default();
+        let audio: Vec<Value> = match &params["audio"] {
+            Value::Array(audio) => audio.clone(),
+            Value::Object(_) => vec![params["audio"].clone()],
+            _ => Vec::new(),
+        };
+
+        let note = self.note_mut(id)?;
+        let mut filenames = Vec::new();
+
+        for (name, value) in fields {
+            match note.fields.get_mut(&name) {
+                Some(field) => *field = value,
+                None => return Err(format!("field was not found: {}", name)),
+            }
+        }
+
+        for audio in audio {
+            let filename: String = param(&audio, "filename")?;
+            let targets: Vec<String> = match &audio["fields"] {
+                Value::String(field) => vec![field.clone()],
+                fields => serde_json::from_value(fields.clone()).unwrap_or_default(),
+            };
+
+            for name in targets {
+                if let Some(field) = note.fields.get_mut(&name) {
+                    *field += &format!("[sound:{}]", filename);
+                }
+            }
+            filenames.push(filename);
+        }
+
+        note.modified += 1;
+
+        for filename in filenames {
+            self.media.entry(filename).or_default();
+        }
+        Ok(())
+    }
+
+    /// Returns the `notesInfo` entry of a note, or an empty object if it does not exist.
+    fn note_info(&self, id: ID) -> Value {
+        let Some(note) = self.notes.get(&id) else {
+            return json!({});
+        };
+
+        let order = &self.models[&note.model].fields;
+        let fields: serde_json::Map<String, Value> = order
+            .iter()
+            .enumerate()
+            .map(|(i, name)| {
+                (
+                    name.clone(),
+                    json!({ "value": note.fields[name], "order": i }),
+                )
+            })
+            .collect();
+
+        json!({
+            "noteId": note.id,
+            "modelName": note.model,
+            "tags": note.tags,
+            "fields": fields,
+            "cards": note.cards,
+            "mod": note.modified,
+        })
+    }
+}
+
+/// Reads a required parameter.
+fn param<T>(params: &Value, name: &str) -> Result<T, String>
+where
+    T: serde::de::DeserializeOwned,
+{
+    serde_json::from_value(params[name].clone())
+        .map_err(|err| format!("invalid parameter '{}': {}", name, err))
+}
+
+/// Reads the deck, model, fields and tags of a note passed to `addNote`.
+#[allow(clippy::type_complexity)]
+fn note_params(
+    note: &Value,
+) -> Result<(String, String, HashMap<String, String>, Vec<String>), String> {
+    Ok((
+        param(note, "deckName")?,
+        param(note, "modelName")?,
+        param(note, "fields")?,
+        serde_json::from_value(note["tags"].clone()).unwrap_or_default(),
+    ))
+}
+
+/// Splits a search query into terms, keeping quoted text together.
+///
+/// # Returns
+/// * The terms together with whether they are negated.
+fn tokenize(query: &str) -> Vec<(bool, String)> {
+    let mut terms = Vec::new();
+    let mut term = String::new();
+    let mut negate = false;
+    let mut quoted = false;
+
+    for c in query.chars() {
+        match c {
+            '"' => quoted = !quoted,
+            '-' if term.is_empty() && !quoted => negate = true,
+            c if (c.is_whitespace() || c == '(' || c == ')') && !quoted => {
+                if !term.is_empty() {
+                    terms.push((negate, std::mem::take(&mut term)));
+                }
+                negate = false;
+            }
+            c => term.push(c),
+        }
+    }
+
+    if !term.is_empty() {
+        terms.push((negate, term));
+    }
+
+    terms
+}
+
+/// Matches text case insensitively against a pattern where `*` matches any text.
+fn wildcard_match(pattern: &str, text: &str) -> bool {
+    let pattern = regex::escape(&pattern.to_lowercase()).replace(r"\*", ".*");
+    regex::Regex::new(&format!("^{}$", pattern))
+        .map(|re| re.is_match(&text.to_lowercase()))
+        .unwrap_or(false)
+}
+
+/// Parses a comma separated list of ids.
+fn id_list(value: &str) -> Vec<ID> {
+    value
+        .split(',')
+        .filter_map(|id| id.trim().parse().ok())
+        .collect()
+}
+
+/// A request received by the mock server.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecordedRequest {
+    /// The requested action.
+    pub action: String,
+    /// The version sent with the request.
+    pub version: u8,
+    /// The parameters of the request, `null` if none were sent.
+    pub params: Value,
+}
+
+/// The state shared between the mock server thread and the test.
+struct MockState {
+    collection: MockCollection,
+    requests: Vec<RecordedRequest>,
+    version: u8,
+    api_key: Option<String>,
+    failures: HashMap<String, String>,
+}
+
+impl MockState {
+    /// Answers a decoded request body.
+    fn respond(&mut self, request: &Value) -> Value {
+        let action = request["action"].as_str().unwrap_or_default().to_owned();
+        let version = request["version"].as_u64().unwrap_or(4) as u8;
+        let params = request.get("params").cloned().unwrap_or(Value::Null);
+
+        self.requests.push(RecordedRequest {
+            action: action.clone(),
+            version,
+            params: params.clone(),
+        });
+
+        let result = match &self.api_key {
+            Some(key) if request["apiKey"].as_str() != Some(key.as_str()) => {
+                Err("valid api key must be provided".to_owned())
+            }
+            _ => self.dispatch(&action, &params),
+        };
+
+        match (version, result) {
+            (5.., Ok(result)) => json!({ "result": result, "error": null }),
+            (5.., Err(error)) => json!({ "result": null, "error": error }),
+            (_, Ok(result)) => result,
+            (_, Err(error)) => json!({ "result": null, "error": error }),
+        }
+    }
+
+    /// Executes an action, handling the actions that are not about the collection.
+    fn dispatch(&mut self, action: &str, params: &Value) -> Result<Value, String> {
+        if let Some(error) = self.failures.get(action) {
+            return Err(error.clone());
+        }
+
+        match action {
+            "version" => Ok(json!(self.version)),
+            "upgrade" => Ok(json!(false)),
+            "multi" => Ok(json!(param::<Vec<Value>>(params, "actions")?
+                .iter()
+                .map(|request| self.respond(request))
+                .collect::<Vec<_>>())),
+            _ => self.collection.execute(action, params),
+        }
+    }
+}
+
+/// A fake AnkiConnect server running on a background thread until dropped.
+pub struct MockAnkiConnect {
+    address: SocketAddr,
+    state: Arc<Mutex<MockState>>,
+    running: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl MockAnkiConnect {
+    /// Starts a server with an empty collection on a free local port.
+    pub fn start() -> io::Result<Self> {
+        Self::with(MockCollection::default())
+    }
+
+    /// Starts a server serving the given collection on a free local port.
+    pub fn with(collection: MockCollection) -> io::Result<Self> {
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let address = listener.local_addr()?;
+
+        let state = Arc::new(Mutex::new(MockState {
+            collection,
+            requests: Vec::new(),
+            version: 6,
+            api_key: None,
+            failures: HashMap::new(),
+        }));
+        let running = Arc::new(AtomicBool::new(true));
+
+        let handle = {
+            let state = state.clone();
+            let running = running.clone();
+
+            thread::spawn(move || {
+                for stream in listener.incoming() {
+                    if !running.load(Ordering::SeqCst) {
+                        break;
+                    }
+
+                    if let Ok(stream) = stream {
+                        // A broken connection only affects the request that was sent on it
+                        let _ = serve(stream, &state);
+                    }
+                }
+            })
+        };
+
+        Ok(Self {
+            address,
+            state,
+            running,
+            handle: Some(handle),
+        })
+    }
+
+    /// Returns the url of the server.
+    pub fn url(&self) -> String {
+        format!("http://{}", self.address)
+    }
+
+    /// Connects a client to the server, sending the api key if one is required.
+    pub fn connect(&self) -> Response<AnkiConnect> {
+        let api_key = self.lock().api_key.clone();
+        AnkiConnect::new(self.url(), api_key)
+    }
+
+    /// Gives access to the collection, e.g. to set up or inspect notes.
+    pub fn with_collection<R>(&self, f: impl FnOnce(&mut MockCollection) -> R) -> R {
+        f(&mut self.lock().collection)
+    }
+
+    /// Returns a copy of the collection.
+    pub fn collection(&self) -> MockCollection {
+        self.lock().collection.clone()
+    }
+
+    /// Returns every request received so far. The actions inside a `multi` request are recorded
+    /// after the `multi` request itself.
+    pub fn requests(&self) -> Vec<RecordedRequest> {
+        self.lock().requests.clone()
+    }
+
+    /// Returns the actions received so far, with the actions inside `multi` requests listed
+    /// in place of the `multi` request itself.
+    pub fn actions(&self) -> Vec<String> {
+        self.lock()
+            .requests
+            .iter()
+            .filter(|request| request.action != "multi")
+            .map(|request| request.action.clone())
+            .collect()
+    }
+
+    /// Forgets the requests received so far.
+    pub fn clear_requests(&self) {
+        self.lock().requests.clear();
+    }
+
+    /// Sets the version reported by the `version` action.
+    pub fn set_version(&self, version: u8) {
+        self.lock().version = version;
+    }
+
+    /// Requires every request to send the given api key.
+    pub fn set_api_key(&self, api_key: Option<&str>) {
+        self.lock().api_key = api_key.map(|key| key.to_owned());
+    }
+
+    /// Makes every following request of an action fail with the given error message.
+    pub fn fail_action(&self, action: &str, error: &str) {
+        self.lock()
+            .failures
+            .insert(action.to_owned(), error.to_owned());
+    }
+
+    /// Locks the shared state, ignoring panics of other test threads.
+    fn lock(&self) -> MutexGuard<'_, MockState> {
+        self.state.lock().unwrap_or_else(|err| err.into_inner())
+    }
+}
+
+impl Drop for MockAnkiConnect {
+    fn drop(&mut self) {
+        self.running.store(false, Ordering::SeqCst);
+
+        // Wake up the listener so it notices the server was stopped
+        let _ = TcpStream::connect(self.address);
+
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+/// Answers a single HTTP request.
+fn serve(stream: TcpStream, state: &Mutex<MockState>) -> io::Result<()> {
+    let mut reader = BufReader::new(stream.try_clone()?);
+
+    // Read the headers, only the content length is needed
+    let mut length = 0;
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line)? == 0 || line.trim().is_empty() {
+            break;
+        }
+
+        if let Some((name, value)) = line.split_once(':') {
+            if name.eq_ignore_ascii_case("content-length") {
+                length = value.trim().parse().unwrap_or(0);
+            }
+        }
+    }
+
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body)?;
+
+    let response = match serde_json::from_slice::<Value>(&body) {
+        Ok(request) => state
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .respond(&request),
+        Err(err) => json!({ "result": null, "error": err.to_string() }),
+    };
+    let response = response.to_string();
+
+    let mut stream = stream;
+    write!(
+        stream,
+        "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        response.len(),
+        response
+    )?;
+    stream.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+    use crate::anki::{
+        anki_connect_batch::{BatchAction, BatchResult},
+        anki_connect_error::{AnkiConnectError, ApiError},
+        anki_note::AnkiNote,
+    };
+
+    /// Starts a server with a deck and a word model.
+    fn setup() -> MockAnkiConnect {
+        let mock = MockAnkiConnect::start().unwrap();
+        mock.with_collection(|collection| {
+            collection.add_deck("My Deck 4.0");
+            collection.add_model(
+                "JP Card V4",
+                &["1 Word", "2 Meaning", "3 Audio", "4 Sentences"],
+            );
+        });
+        mock
+    }
+
+    /// Creates a word note.
+    fn word_note(word: &str) -> AnkiNote {
+        AnkiNote {
+            deckName: Some("My Deck 4.0".to_owned()),
+            modelName: "JP Card V4".to_owned(),
+            tags: vec!["JLPT-N5".to_owned()],
+            fields: HashMap::from([("1 Word".to_owned(), word.to_owned())]),
+            ..AnkiNote::default()
+        }
+    }
+
+    /// Tests the version check and api key handling.
+    #[test]
+    fn connect() {
+        let mock = setup();
+        assert!(mock.connect().is_ok());
+
+        mock.set_version(5);
+        assert!(matches!(
+            mock.connect(),
+            Err(AnkiConnectError::VersionMismatch {
+                expected: 6,
+                found: 5
+            })
+        ));
+
+        mock.set_version(6);
+        mock.set_api_key(Some("secret"));
+        assert!(mock.connect().is_ok());
+        assert!(matches!(
+            AnkiConnect::new(mock.url(), None),
+            Err(AnkiConnectError::Api(ApiError::InvalidApiKey))
+        ));
+    }
+
+    /// Tests adding, finding, reading and updating notes.
+    #[test]
+    fn notes() {
+        let mock = setup();
+        let anki = mock.connect().unwrap();
+
+        let mut note = word_note("猫[ねこ]");
+        let id = anki.add_note(&mut note).unwrap();
+        assert_eq!(note.noteId, Some(id));
+
+        assert!(anki
+            .add_note(&mut word_note("猫[ねこ]"))
+            .unwrap_err()
+            .is_duplicate());
+        assert!(matches!(
+            anki.add_note(&mut word_note("")).unwrap_err().api_error(),
+            Some(ApiError::EmptyNote)
+        ));
+
+        let found = anki
+            .find_notes("\"deck:My Deck 4.0\" \"note:JP Card V4\"")
+            .unwrap();
+        assert_eq!(found, vec![id]);
+        assert_eq!(
+            anki.find_notes("\"deck:My Deck 4.0\" \"3 Audio:\"")
+                .unwrap(),
+            vec![id]
+        );
+        assert!(anki.find_notes("-tag:JLPT-N5").unwrap().is_empty());
+
+        let fields = HashMap::from([("2 Meaning".to_owned(), "cat".to_owned())]);
+        anki.update_note_fields(id, &fields).unwrap();
+        anki.add_note_audio(id, "http://example.com", "neko.mp3", &["3 Audio"], None)
+            .unwrap();
+        anki.add_tags(&[id], "common").unwrap();
+        anki.remove_tags(&[id], "JLPT-N5").unwrap();
+
+        let info = &anki.notes_info(&[id]).unwrap()[0];
+        assert_eq!(info.fields["2 Meaning"], "cat");
+        assert_eq!(info.fields["3 Audio"], "[sound:neko.mp3]");
+        assert_eq!(info.tags, vec!["common"]);
+        assert_eq!(anki.get_tags().unwrap(), vec!["common"]);
+
+        assert!(matches!(
+            anki.update_note_fields(1, &fields).unwrap_err().api_error(),
+            Some(ApiError::NoteNotFound(_))
+        ));
+    }
+
+    /// Tests card states, due positions and moving cards between decks.
+    #[test]
+    fn cards() {
+        let mock = setup();
+        let anki = mock.connect().unwrap();
+
+        let note = anki.add_note(&mut word_note("犬[いぬ]")).unwrap();
+        let card = anki.find_cards("is:new -is:suspended").unwrap()[0];
+        assert_eq!(anki.cards_to_notes(&[card]).unwrap(), vec![note]);
+
+        assert!(anki.suspend(&[card]).unwrap());
+        assert_eq!(anki.are_suspended(&[card]).unwrap(), vec![true]);
+        assert!(anki.find_cards("is:new -is:suspended").unwrap().is_empty());
+        assert!(anki.unsuspend(&[card]).unwrap());
+
+        assert_eq!(
+            anki.set_specific_value_of_card(card, vec![("due", &42.into())])
+                .unwrap(),
+            vec![json!(true)]
+        );
+        assert_eq!(mock.collection().card(card).unwrap().due, 42);
+
+        anki.change_deck("Other", &[card]).unwrap();
+        assert_eq!(
+            anki.get_decks(&[card]).unwrap(),
+            HashMap::from([("Other".to_owned(), vec![card])])
+        );
+
+        anki.delete_decks(&["Other"], true).unwrap();
+        assert!(mock.collection().notes.is_empty());
+    }
+
+    /// Tests that batches are answered per action and recorded.
+    #[test]
+    fn batch() {
+        let mock = setup();
+        let anki = mock.connect().unwrap();
+        let id = anki.add_note(&mut word_note("猫[ねこ]")).unwrap();
+        mock.clear_requests();
+
+        let mut batch = anki.batch().with_batch_size(2);
+        batch.push(BatchAction::AddTags {
+            notes: vec![id],
+            tags: "a b".to_owned(),
+        });
+        batch.push(BatchAction::AddNote(word_note("猫[ねこ]")));
+        batch.push(BatchAction::AddNote(word_note("犬[いぬ]")));
+
+        let results = batch.send().unwrap();
+        assert_eq!(results[0].as_ref().unwrap(), &BatchResult::Unit);
+        assert!(results[1].as_ref().unwrap_err().is_duplicate());
+        assert!(matches!(results[2], Ok(BatchResult::Id(_))));
+
+        assert_eq!(mock.requests().len(), 2 + 3);
+        assert_eq!(mock.actions(), vec!["addTags", "addNote", "addNote"]);
+        assert_eq!(
+            mock.collection().note(id).unwrap().tags,
+            vec!["JLPT-N5", "a", "b"]
+        );
+    }
+
+    /// Tests injected failures.
+    #[test]
+    fn fail_action() {
+        let mock = setup();
+        let anki = mock.connect().unwrap();
+
+        mock.fail_action("deckNames", "collection is not available");
+        assert!(matches!(
+            anki.deck_names().unwrap_err().api_error(),
+            Some(ApiError::CollectionUnavailable)
+        ));
+        assert!(anki.model_names().is_ok());
+    }
+
+    /// Tests the search syntax.
+    #[test]
+    fn search() {
+        let mut collection = MockCollection::default();
+        collection.add_deck("Deck::Sub");
+        collection.add_model("Basic", &["Front", "Back"]);
+        let a = collection
+            .add_note("Deck::Sub", "Basic", &[("Front", "a")], &["x"])
+            .unwrap();
+        let b = collection
+            .add_note("Default", "Basic", &[("Front", "b"), ("Back", "bb")], &[])
+            .unwrap();
+
+        assert_eq!(collection.find_notes("deck:Deck"), vec![a]);
+        assert_eq!(collection.find_notes("Back:_*"), vec![b]);
+        assert_eq!(collection.find_notes("-tag:x note:basic"), vec![b]);
+        assert_eq!(
+            collection.find_notes(&format!("nid:{},{}", a, b)),
+            vec![a, b]
+        );
+        assert_eq!(collection.find_notes("\"front:*\" bb"), vec![b]);
+        assert_eq!(collection.find_notes("note:Basic (-tag:x)"), vec![b]);
+    }
+}
@@ -0,0 +1,156 @@
+use std::collections::BTreeSet;
+
+use regex::Regex;
+
+use super::anki_connect::{AnkiConnect, Response};
+
+/// Returns the media files referenced by a field: the `[sound:]` tags and the `src` attributes
+/// of `<img>`, `<audio>` and other elements.
+///
+/// # Example
+/// ```
+/// use anki_utill::anki::anki_media::media_references;
+///
+/// assert_eq!(
+///     media_references("[sound:neko.mp3]<img src=\"kanjivg_0732b.svg\">"),
+///     vec!["neko.mp3", "kanjivg_0732b.svg"]
+/// );
+/// ```
+pub fn media_references(field: &str) -> Vec<String> {
+    let regex =
+        Regex::new(r#"\[sound:([^\]]+)\]|<[^>]*\ssrc\s*=\s*(?:"([^"]*)"|'([^']*)'|([^\s>]+))"#)
+            .unwrap();
+
+    regex
+        .captures_iter(field)
+        .filter_map(|captures| {
+            let sound = captures.get(1).map(|name| name.as_str().to_owned());
+            let src = (2..=4)
+                .find_map(|i| captures.get(i))
+                .map(|src| unescape_src(src.as_str()));
+
+            sound.or(src)
+        })
+        .collect()
+}
+
+/// Decodes the HTML entities and percent-encoding Anki uses for file names in `src` attributes.
+fn unescape_src(src: &str) -> String {
+    let src = src
+        .replace("&amp;", "&")
+        .replace("&quot;", "\"")
+        .replace("&#x27;", "'")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">");
+
+    let mut bytes = Vec::with_capacity(src.len());
+    let mut rest = src.as_bytes();
+    while let Some((&byte, tail)) = rest.split_first() {
+        let hex = tail
+            .get(..2)
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+
+        match (byte, hex) {
+            (b'%', Some(decoded)) => {
+                bytes.push(decoded);
+                rest = &tail[2..];
+            }
+            _ => {
+                bytes.push(byte);
+                rest = tail;
+            }
+        }
+    }
+
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+/// The search query matching every note of the collection.
+pub const ALL_NOTES: &str = "deck:*";
+
+/// Finds the media files no note references. The notes are fetched once for all patterns.
+///
+/// # Arguments
+/// * `anki` - The AnkiConnect connection.
+/// * `query` - The search query of the notes whose references are kept, usually [`ALL_NOTES`]
+///   as notes of any deck may share a file.
+/// * `patterns` - The patterns of the media files to check, e.g. `*.mp3` or `*` for all files.
+///   Files starting with `_` are always kept, like Anki does.
+///
+/// # Returns
+/// * The unreferenced files, sorted by name.
+pub fn unused_media(anki: &AnkiConnect, query: &str, patterns: &[&str]) -> Response<Vec<String>> {
+    let notes = anki.notes_info(&anki.find_notes(query)?)?;
+
+    let referenced: BTreeSet<String> = notes
+        .iter()
+        .flat_map(|note| note.fields.values())
+        .flat_map(|field| media_references(field))
+        .collect();
+
+    let mut unused: BTreeSet<String> = BTreeSet::new();
+    for pattern in patterns {
+        unused.extend(
+            anki.get_media_files_names(pattern)?
+                .into_iter()
+                .filter(|name| !name.starts_with('_') && !referenced.contains(name)),
+        );
+    }
+
+    Ok(unused.into_iter().collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::anki::anki_connect_mock::{MockAnkiConnect, MEDIA_DIR};
+
+    use super::*;
+
+    /// Tests the media calls and finding the files no note of a deck references.
+    #[test]
+    fn unused() {
+        let mock = MockAnkiConnect::start().unwrap();
+        mock.with_collection(|collection| {
+            collection.add_deck("Japanese");
+            collection.add_model("JP Card V4", &["1 Word", "3 Audio"]);
+            collection
+                .add_note(
+                    "Japanese",
+                    "JP Card V4",
+                    &[
+                        ("1 Word", "<img src='neko%20cat.svg'>"),
+                        ("3 Audio", "[sound:neko.mp3]"),
+                    ],
+                    &[],
+                )
+                .unwrap();
+        });
+
+        let anki = mock.connect().unwrap();
+        for name in ["neko.mp3", "neko cat.svg", "inu.mp3", "_style.css"] {
+            anki.store_media_file(name, name.as_bytes()).unwrap();
+        }
+
+        assert_eq!(
+            anki.retrieve_media_file("inu.mp3").unwrap().as_deref(),
+            Some("inu.mp3".as_bytes())
+        );
+        assert_eq!(anki.retrieve_media_file("tori.mp3").unwrap(), None);
+        assert_eq!(
+            anki.get_media_files_names("*.mp3").unwrap(),
+            vec!["inu.mp3", "neko.mp3"]
+        );
+        assert_eq!(anki.get_media_dir_path().unwrap().to_str(), Some(MEDIA_DIR));
+
+        assert_eq!(
+            unused_media(&anki, "\"deck:Japanese\"", &["*", "*.mp3"]).unwrap(),
+            vec!["inu.mp3"]
+        );
+
+        anki.delete_media_file("inu.mp3").unwrap();
+        assert!(unused_media(&anki, "\"deck:Japanese\"", &["*"])
+            .unwrap()
+            .is_empty());
+    }
+}
@@ -0,0 +1,866 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fs::File,
+    io::Write,
+    path::Path,
+    sync::OnceLock,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use regex::Regex;
+use rusqlite::{params, Connection};
+use serde_json::json;
+use sha1::{Digest, Sha1};
+use zip::{write::SimpleFileOptions, ZipWriter};
+
+use crate::{
+    anki::anki_note::{AnkiNote, ID},
+    entry::{Kanji, Word},
+    kanjivg,
+};
+
+/// Represents a result of writing an `.apkg` package.
+type Response<T> = Result<T, Box<dyn std::error::Error>>;
+
+/// Schema of an Anki 2.1 legacy collection (`collection.anki2`, schema version 11).
+const COLLECTION_SCHEMA: &str = r#"
+CREATE TABLE col (
+    id      integer primary key,
+    crt     integer not null,
+    mod     integer not null,
+    scm     integer not null,
+    ver     integer not null,
+    dty     integer not null,
+    usn     integer not null,
+    ls      integer not null,
+    conf    text not null,
+    models  text not null,
+    decks   text not null,
+    dconf   text not null,
+    tags    text not null
+);
+CREATE TABLE notes (
+    id      integer primary key,
+    guid    text not null,
+    mid     integer not null,
+    mod     integer not null,
+    usn     integer not null,
+    tags    text not null,
+    flds    text not null,
+    sfld    integer not null,
+    csum    integer not null,
+    flags   integer not null,
+    data    text not null
+);
+CREATE TABLE cards (
+    id      integer primary key,
+    nid     integer not null,
+    did     integer not null,
+    ord     integer not null,
+    mod     integer not null,
+    usn     integer not null,
+    type    integer not null,
+    queue   integer not null,
+    due     integer not null,
+    ivl     integer not null,
+    factor  integer not null,
+    reps    integer not null,
+    lapses  integer not null,
+    left    integer not null,
+    odue    integer not null,
+    odid    integer not null,
+    flags   integer not null,
+    data    text not null
+);
+CREATE TABLE revlog (
+    id      integer primary key,
+    cid     integer not null,
+    usn     integer not null,
+    ease    integer not null,
+    ivl     integer not null,
+    lastIvl integer not null,
+    factor  integer not null,
+    time    integer not null,
+    type    integer not null
+);
+CREATE TABLE graves (
+    usn     integer not null,
+    oid     integer not null,
+    type    integer not null
+);
+CREATE INDEX ix_notes_usn ON notes (usn);
+CREATE INDEX ix_cards_usn ON cards (usn);
+CREATE INDEX ix_revlog_usn ON revlog (usn);
+CREATE INDEX ix_cards_nid ON cards (nid);
+CREATE INDEX ix_cards_sched ON cards (did, queue, due);
+CREATE INDEX ix_revlog_cid ON revlog (cid);
+CREATE INDEX ix_notes_csum ON notes (csum);
+"#;
+
+/// The logical content a field of an exported note type is filled with when adding
+/// `Word` or `Kanji` values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NoteField {
+    /// The furigana representation of a word.
+    Word,
+    /// The formatted glossary of a word.
+    Meaning,
+    /// The audio of a word, left empty until audio is attached.
+    Audio,
+    /// The formatted example sentences of a word.
+    Sentences,
+    /// The reading of a word in romaji.
+    Romaji,
+    /// The pitch accent graphs of a word.
+    Pitch,
+    /// The conjugation table of a word.
+    Conjugations,
+    /// The kanji character itself.
+    Kanji,
+    /// The onyomi readings of a kanji.
+    Onyomi,
+    /// The kunyomi readings of a kanji.
+    Kunyomi,
+    /// The meanings of a kanji.
+    KanjiMeaning,
+    /// The stroke count of a kanji.
+    Strokes,
+    /// The breakdown of a kanji into its components.
+    Components,
+    /// The stroke order diagrams of the kanji of a word, or the numbered stroke order strip
+    /// of a kanji. The SVG files are added separately, see [`kanjivg`](crate::kanjivg).
+    StrokeOrder,
+    /// A field that is always left empty.
+    Empty,
+}
+
+impl NoteField {
+    /// Returns the content of this field for the given word.
+    pub fn word_value(&self, word: &Word) -> String {
+        match self {
+            NoteField::Word => word.furigana.clone(),
+            NoteField::Meaning => word.meaning_html(),
+            NoteField::Sentences => word.examples_html(),
+            NoteField::Romaji => word.romaji(),
+            NoteField::Pitch => word.pitch_html(),
+            NoteField::Conjugations => word.conjugations_html(),
+            NoteField::StrokeOrder => kanjivg::stroke_order_html(&word.furigana),
+            _ => String::new(),
+        }
+    }
+
+    /// Returns the content of this field for the given kanji.
+    pub fn kanji_value(&self, kanji: &Kanji) -> String {
+        match self {
+            NoteField::Kanji => kanji.kanji.to_string(),
+            NoteField::Onyomi => sorted_join(&kanji.onyomi, "、"),
+            NoteField::Kunyomi => sorted_join(&kanji.kunyomi, "、"),
+            NoteField::KanjiMeaning => kanji.meaning.join(", "),
+            NoteField::Strokes => kanji.strokes.map(|s| s.to_string()).unwrap_or_default(),
+            NoteField::Components => kanji.breakdown(),
+            NoteField::StrokeOrder => kanjivg::strip_html(kanji.kanji),
+            _ => String::new(),
+        }
+    }
+}
+
+/// Joins a set of strings in a stable order.
+fn sorted_join(set: &HashSet<String>, separator: &str) -> String {
+    let mut list: Vec<&str> = set.iter().map(|s| s.as_str()).collect();
+    list.sort_unstable();
+    list.join(separator)
+}
+
+/// A field of an exported note type.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApkgField {
+    /// The name of the field as shown in Anki.
+    pub name: String,
+    /// The content the field is filled with for `Word`/`Kanji` values.
+    pub content: NoteField,
+}
+
+impl ApkgField {
+    /// Creates a new field.
+    pub fn new(name: &str, content: NoteField) -> Self {
+        Self {
+            name: name.to_owned(),
+            content,
+        }
+    }
+}
+
+/// A card template of an exported note type.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApkgTemplate {
+    /// The name of the card template.
+    pub name: String,
+    /// The question side of the template.
+    pub front: String,
+    /// The answer side of the template.
+    pub back: String,
+}
+
+impl ApkgTemplate {
+    /// Creates a new card template.
+    pub fn new(name: &str, front: &str, back: &str) -> Self {
+        Self {
+            name: name.to_owned(),
+            front: front.to_owned(),
+            back: back.to_owned(),
+        }
+    }
+
+    /// Returns the names of all fields referenced on the question side.
+    fn front_fields(&self) -> HashSet<String> {
+        static FIELD: OnceLock<Regex> = OnceLock::new();
+        let regex = FIELD.get_or_init(|| Regex::new(r"\{\{([^{}#/^]+?)\}\}").unwrap());
+
+        regex
+            .captures_iter(&self.front)
+            .map(|caps| {
+                // Strip filters such as `furigana:` or `text:`
+                let name = &caps[1];
+                name.rsplit(':').next().unwrap_or(name).trim().to_owned()
+            })
+            .collect()
+    }
+}
+
+/// A note type (model) definition used for an `.apkg` export.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApkgModel {
+    /// The id of the note type. Must stay the same between exports so that re-imports
+    /// update the existing notes instead of creating a new note type.
+    pub id: ID,
+    /// The name of the note type.
+    pub name: String,
+    /// The fields of the note type, in order.
+    pub fields: Vec<ApkgField>,
+    /// The card templates of the note type.
+    pub templates: Vec<ApkgTemplate>,
+    /// The styling shared by all card templates.
+    pub css: String,
+    /// The index of the field used for sorting and duplicate checking.
+    pub sort_field: usize,
+}
+
+impl ApkgModel {
+    /// Creates a new note type sorted by its first field.
+    pub fn new(id: ID, name: &str, fields: Vec<ApkgField>, templates: Vec<ApkgTemplate>) -> Self {
+        Self {
+            id,
+            name: name.to_owned(),
+            fields,
+            templates,
+            css: ".card {\n font-family: arial;\n font-size: 20px;\n text-align: center;\n color: black;\n background-color: white;\n}\n".to_owned(),
+            sort_field: 0,
+        }
+    }
+
+    /// Returns the field values of a note in the order of this note type.
+    fn field_values(&self, fields: &HashMap<String, String>) -> Vec<String> {
+        self.fields
+            .iter()
+            .map(|field| fields.get(&field.name).cloned().unwrap_or_default())
+            .collect()
+    }
+
+    /// Serialises the note type into the JSON layout used in the `col.models` column.
+    fn to_json(&self, deck_id: ID, modified: i64) -> serde_json::Value {
+        let flds: Vec<serde_json::Value> = self
+            .fields
+            .iter()
+            .enumerate()
+            .map(|(ord, field)| {
+                json!({
+                    "name": field.name,
+                    "ord": ord,
+                    "font": "Arial",
+                    "media": [],
+                    "rtl": false,
+                    "size": 20,
+                    "sticky": false,
+                })
+            })
+            .collect();
+
+        let tmpls: Vec<serde_json::Value> = self
+            .templates
+            .iter()
+            .enumerate()
+            .map(|(ord, template)| {
+                json!({
+                    "name": template.name,
+                    "ord": ord,
+                    "qfmt": template.front,
+                    "afmt": template.back,
+                    "bqfmt": "",
+                    "bafmt": "",
+                    "did": null,
+                })
+            })
+            .collect();
+
+        // Which fields must be non-empty for each card template to generate a card.
+        let req: Vec<serde_json::Value> = self
+            .templates
+            .iter()
+            .enumerate()
+            .map(|(ord, template)| {
+                let names = template.front_fields();
+                let required: Vec<usize> = self
+                    .fields
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, field)| names.contains(&field.name))
+                    .map(|(i, _)| i)
+                    .collect();
+
+                json!([ord, "any", required])
+            })
+            .collect();
+
+        json!({
+            "id": self.id,
+            "name": self.name,
+            "type": 0,
+            "mod": modified,
+            "usn": -1,
+            "sortf": self.sort_field,
+            "did": deck_id,
+            "tmpls": tmpls,
+            "flds": flds,
+            "css": self.css,
+            "latexPre": "\\documentclass[12pt]{article}\n\\special{papersize=3in,5in}\n\\usepackage[utf8]{inputenc}\n\\usepackage{amssymb,amsmath}\n\\pagestyle{empty}\n\\setlength{\\parindent}{0in}\n\\begin{document}\n",
+            "latexPost": "\\end{document}",
+            "tags": [],
+            "vers": [],
+            "req": req,
+        })
+    }
+
+    /// Returns the ordinals of the card templates that generate a card for the given fields.
+    fn card_templates(&self, values: &[String]) -> Vec<usize> {
+        self.templates
+            .iter()
+            .enumerate()
+            .filter(|(_, template)| {
+                let names = template.front_fields();
+
+                self.fields
+                    .iter()
+                    .zip(values)
+                    .any(|(field, value)| names.contains(&field.name) && !value.is_empty())
+            })
+            .map(|(ord, _)| ord)
+            .collect()
+    }
+}
+
+/// A note queued for export.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ApkgNote {
+    model_id: ID,
+    guid: String,
+    fields: Vec<String>,
+    tags: Vec<String>,
+}
+
+/// Builds an offline Anki package (`.apkg`) containing a single deck, its note types,
+/// notes, cards and media files, without requiring a running Anki instance.
+///
+/// # Example
+/// ```no_run
+/// use anki_utill::anki::apkg::{ApkgDeck, ApkgField, ApkgModel, ApkgTemplate, NoteField};
+///
+/// let model = ApkgModel::new(
+///     1_700_000_000_000,
+///     "JP Card",
+///     vec![
+///         ApkgField::new("Word", NoteField::Word),
+///         ApkgField::new("Meaning", NoteField::Meaning),
+///     ],
+///     vec![ApkgTemplate::new("Card 1", "{{Word}}", "{{FrontSide}}<hr id=answer>{{Meaning}}")],
+/// );
+///
+/// let mut deck = ApkgDeck::new(1_700_000_000_001, "My Deck");
+/// deck.add_model(model);
+/// deck.write(std::path::Path::new("./result/deck.apkg")).unwrap();
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApkgDeck {
+    id: ID,
+    name: String,
+    description: String,
+    models: Vec<ApkgModel>,
+    notes: Vec<ApkgNote>,
+    media: Vec<(String, Vec<u8>)>,
+}
+
+impl ApkgDeck {
+    /// Creates a new empty deck.
+    ///
+    /// # Arguments
+    /// * `id` - The id of the deck. Must stay the same between exports.
+    /// * `name` - The name of the deck, `::` separates sub decks.
+    pub fn new(id: ID, name: &str) -> Self {
+        Self {
+            id,
+            name: name.to_owned(),
+            description: String::new(),
+            models: Vec::new(),
+            notes: Vec::new(),
+            media: Vec::new(),
+        }
+    }
+
+    /// Sets the description shown on the deck overview screen.
+    pub fn set_description(&mut self, description: &str) {
+        self.description = description.to_owned();
+    }
+
+    /// Registers a note type. Notes can only be added to registered note types.
+    pub fn add_model(&mut self, model: ApkgModel) {
+        self.models.retain(|m| m.id != model.id);
+        self.models.push(model);
+    }
+
+    /// Returns the number of queued notes.
+    pub fn note_count(&self) -> usize {
+        self.notes.len()
+    }
+
+    /// Looks up a registered note type by name.
+    fn model_by_name(&self, name: &str) -> Response<&ApkgModel> {
+        self.models
+            .iter()
+            .find(|model| model.name == name)
+            .ok_or_else(|| Box::from(format!("Note type '{}' is not registered", name)))
+    }
+
+    /// Looks up a registered note type by id.
+    fn model_by_id(&self, id: ID) -> Response<&ApkgModel> {
+        self.models
+            .iter()
+            .find(|model| model.id == id)
+            .ok_or_else(|| Box::from(format!("Note type '{}' is not registered", id)))
+    }
+
+    /// Queues a note. The note type is looked up using `modelName` and fields are matched by name.
+    /// Fields missing from the note are left empty.
+    ///
+    /// # Arguments
+    /// * `note` - The note to add.
+    pub fn add_note(&mut self, note: &AnkiNote) -> Response<()> {
+        let model = self.model_by_name(&note.modelName)?;
+        let fields = model.field_values(&note.fields);
+        let model_id = model.id;
+
+        self.push_note(model_id, fields, note.tags.clone());
+
+        Ok(())
+    }
+
+    /// Queues a note for a word using the given note type.
+    ///
+    /// # Arguments
+    /// * `model_id` - The id of a registered note type.
+    /// * `word` - The word to add.
+    pub fn add_word(&mut self, model_id: ID, word: &Word) -> Response<()> {
+        let model = self.model_by_id(model_id)?;
+        let fields = model
+            .fields
+            .iter()
+            .map(|field| field.content.word_value(word))
+            .collect();
+
+        let mut tags: Vec<String> = word.get_all_tags().iter().map(|t| t.to_string()).collect();
+        tags.sort_unstable();
+
+        self.push_note(model_id, fields, tags);
+
+        Ok(())
+    }
+
+    /// Queues a note for a kanji using the given note type.
+    ///
+    /// # Arguments
+    /// * `model_id` - The id of a registered note type.
+    /// * `kanji` - The kanji to add.
+    pub fn add_kanji(&mut self, model_id: ID, kanji: &Kanji) -> Response<()> {
+        let model = self.model_by_id(model_id)?;
+        let fields = model
+            .fields
+            .iter()
+            .map(|field| field.content.kanji_value(kanji))
+            .collect();
+
+        let mut tags: Vec<String> = kanji.tags.iter().cloned().collect();
+        tags.sort_unstable();
+
+        self.push_note(model_id, fields, tags);
+
+        Ok(())
+    }
+
+    /// Adds a media file to the package. Reference it from a field using its filename,
+    /// e.g. `[sound:file.mp3]` or `<img src="file.svg">`.
+    pub fn add_media(&mut self, filename: &str, data: Vec<u8>) {
+        self.media.retain(|(name, _)| name != filename);
+        self.media.push((filename.to_owned(), data));
+    }
+
+    /// Adds a note with a guid derived from the note type and its sort field, so that
+    /// re-importing an updated package updates the notes instead of duplicating them.
+    fn push_note(&mut self, model_id: ID, fields: Vec<String>, tags: Vec<String>) {
+        let sort_field = self
+            .models
+            .iter()
+            .find(|model| model.id == model_id)
+            .map_or(0, |model| model.sort_field);
+        let key = fields.get(sort_field).cloned().unwrap_or_default();
+
+        let tags = tags.into_iter().map(|tag| tag.replace(' ', "_")).collect();
+
+        self.notes.push(ApkgNote {
+            model_id,
+            guid: guid_for(&format!("{}\u{1f}{}", model_id, key)),
+            fields,
+            tags,
+        });
+    }
+
+    /// Writes the package to the given path.
+    ///
+    /// The package is a ZIP archive containing the `collection.anki2` SQLite database,
+    /// a `media` file mapping archive entries to filenames, and the media files themselves.
+    pub fn write(&self, path: &Path) -> Response<()> {
+        let collection = self.write_collection()?;
+
+        let mut zip = ZipWriter::new(File::create(path)?);
+        let options = SimpleFileOptions::default();
+
+        zip.start_file("collection.anki2", options)?;
+        zip.write_all(&collection)?;
+
+        let mut media_map = serde_json::Map::new();
+        for (index, (filename, data)) in self.media.iter().enumerate() {
+            media_map.insert(index.to_string(), filename.clone().into());
+
+            zip.start_file(index.to_string(), options)?;
+            zip.write_all(data)?;
+        }
+
+        zip.start_file("media", options)?;
+        zip.write_all(serde_json::to_string(&media_map)?.as_bytes())?;
+
+        zip.finish()?;
+
+        Ok(())
+    }
+
+    /// Builds the collection database and returns its bytes.
+    fn write_collection(&self) -> Response<Vec<u8>> {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH)?;
+        let now_secs = now.as_secs() as i64;
+        let now_millis = now.as_millis() as i64;
+
+        // SQLite can only serialize a file backed database reliably, so build it in a temp file.
+        let db_path = std::env::temp_dir().join(format!(
+            "anki_utill_{}_{}.anki2",
+            std::process::id(),
+            now.as_nanos()
+        ));
+
+        let result = self.fill_collection(&db_path, now_secs, now_millis);
+        let bytes = result.and_then(|_| Ok(std::fs::read(&db_path)?));
+        let _ = std::fs::remove_file(&db_path);
+
+        bytes
+    }
+
+    /// Creates the collection tables and inserts the deck, note types, notes and cards.
+    fn fill_collection(&self, db_path: &Path, now_secs: i64, now_millis: i64) -> Response<()> {
+        let mut conn = Connection::open(db_path)?;
+        conn.execute_batch(COLLECTION_SCHEMA)?;
+
+        let models: serde_json::Map<String, serde_json::Value> = self
+            .models
+            .iter()
+            .map(|model| (model.id.to_string(), model.to_json(self.id, now_secs)))
+            .collect();
+
+        let mut decks = serde_json::Map::new();
+        decks.insert("1".into(), deck_json(1, "Default", "", now_secs));
+        decks.insert(
+            self.id.to_string(),
+            deck_json(self.id, &self.name, &self.description, now_secs),
+        );
+
+        let conf = json!({
+            "activeDecks": [1],
+            "curDeck": 1,
+            "newSpread": 0,
+            "collapseTime": 1200,
+            "timeLim": 0,
+            "estTimes": true,
+            "dueCounts": true,
+            "curModel": null,
+            "nextPos": self.notes.len() + 1,
+            "sortType": "noteFld",
+            "sortBackwards": false,
+            "addToCur": true,
+        });
+
+        let tx = conn.transaction()?;
+
+        tx.execute(
+            "INSERT INTO col VALUES (1, ?1, ?2, ?2, 11, 0, 0, 0, ?3, ?4, ?5, ?6, '{}')",
+            params![
+                now_secs - now_secs % 86400,
+                now_millis,
+                conf.to_string(),
+                serde_json::Value::from(models).to_string(),
+                serde_json::Value::from(decks).to_string(),
+                dconf_json().to_string(),
+            ],
+        )?;
+
+        let mut card_id = now_millis;
+        for (position, note) in self.notes.iter().enumerate() {
+            let model = self.model_by_id(note.model_id)?;
+            let note_id = now_millis + position as i64;
+            let sort_field = strip_html(note.fields.get(model.sort_field).map_or("", |f| f));
+
+            let tags = if note.tags.is_empty() {
+                String::new()
+            } else {
+                format!(" {} ", note.tags.join(" "))
+            };
+
+            tx.execute(
+                "INSERT INTO notes VALUES (?1, ?2, ?3, ?4, -1, ?5, ?6, ?7, ?8, 0, '')",
+                params![
+                    note_id,
+                    note.guid,
+                    note.model_id,
+                    now_secs,
+                    tags,
+                    note.fields.join("\u{1f}"),
+                    sort_field,
+                    field_checksum(&sort_field),
+                ],
+            )?;
+
+            for ord in model.card_templates(&note.fields) {
+                card_id += 1;
+
+                tx.execute(
+                    "INSERT INTO cards VALUES (?1, ?2, ?3, ?4, ?5, -1, 0, 0, ?6, 0, 0, 0, 0, 0, 0, 0, 0, '')",
+                    params![card_id, note_id, self.id, ord, now_secs, position as i64 + 1],
+                )?;
+            }
+        }
+
+        tx.commit()?;
+        conn.close().map_err(|(_, err)| err)?;
+
+        Ok(())
+    }
+}
+
+/// Serialises a deck into the JSON layout used in the `col.decks` column.
+fn deck_json(id: ID, name: &str, description: &str, modified: i64) -> serde_json::Value {
+    json!({
+        "id": id,
+        "name": name,
+        "desc": description,
+        "mod": modified,
+        "usn": -1,
+        "collapsed": false,
+        "browserCollapsed": false,
+        "conf": 1,
+        "dyn": 0,
+        "extendNew": 10,
+        "extendRev": 50,
+        "newToday": [0, 0],
+        "revToday": [0, 0],
+        "lrnToday": [0, 0],
+        "timeToday": [0, 0],
+    })
+}
+
+/// The default deck options group stored in the `col.dconf` column.
+fn dconf_json() -> serde_json::Value {
+    json!({
+        "1": {
+            "id": 1,
+            "name": "Default",
+            "mod": 0,
+            "usn": 0,
+            "maxTaken": 60,
+            "autoplay": true,
+            "timer": 0,
+            "replayq": true,
+            "dyn": false,
+            "new": {
+                "bury": true,
+                "delays": [1, 10],
+                "initialFactor": 2500,
+                "ints": [1, 4, 7],
+                "order": 1,
+                "perDay": 20,
+                "separate": true,
+            },
+            "lapse": {
+                "delays": [10],
+                "leechAction": 0,
+                "leechFails": 8,
+                "minInt": 1,
+                "mult": 0,
+            },
+            "rev": {
+                "bury": true,
+                "ease4": 1.3,
+                "fuzz": 0.05,
+                "ivlFct": 1,
+                "maxIvl": 36500,
+                "minSpace": 1,
+                "perDay": 100,
+            },
+        }
+    })
+}
+
+/// Removes any HTML tags from a field, as Anki does for the sort field.
+fn strip_html(field: &str) -> String {
+    static TAG: OnceLock<Regex> = OnceLock::new();
+    let regex = TAG.get_or_init(|| Regex::new(r"<[^<>]*>").unwrap());
+    regex.replace_all(field, "").to_string()
+}
+
+/// Computes the checksum Anki uses for duplicate detection: the first 8 hex digits
+/// of the SHA1 hash of the stripped sort field.
+fn field_checksum(field: &str) -> i64 {
+    let hash = Sha1::digest(field.as_bytes());
+    u32::from_be_bytes([hash[0], hash[1], hash[2], hash[3]]) as i64
+}
+
+/// Generates a stable note guid from a key, encoded in the base91 alphabet Anki uses.
+fn guid_for(key: &str) -> String {
+    const TABLE: &[u8] =
+        b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789!#$%&()*+,-./:;<=>?@[]^_`{|}~";
+
+    let hash = Sha1::digest(key.as_bytes());
+    let mut value = u64::from_be_bytes(hash[..8].try_into().unwrap());
+
+    let mut out = Vec::new();
+    while value > 0 {
+        out.push(TABLE[(value % TABLE.len() as u64) as usize]);
+        value /= TABLE.len() as u64;
+    }
+    out.reverse();
+
+    String::from_utf8(out).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Read;
+
+    use zip::ZipArchive;
+
+    use super::*;
+    use crate::entry::Glossary;
+
+    /// Writes a small package and verifies the collection, media map and media files.
+    #[test]
+    fn write_package() {
+        let model = ApkgModel::new(
+            1000,
+            "JP Card",
+            vec![
+                ApkgField::new("Word", NoteField::Word),
+                ApkgField::new("Meaning", NoteField::Meaning),
+                ApkgField::new("Audio", NoteField::Audio),
+            ],
+            vec![ApkgTemplate::new(
+                "Recognition",
+                "{{furigana:Word}}",
+                "{{FrontSide}}<hr id=answer>{{Meaning}}",
+            )],
+        );
+
+        let mut deck = ApkgDeck::new(2000, "My Deck");
+        deck.add_model(model);
+
+        let word = Word::new(
+            1,
+            "食[た]べる".to_owned(),
+            vec![Glossary::new(
+                0,
+                ["v1".to_owned()].into(),
+                vec!["to eat".to_owned()],
+            )],
+            ["JLPT-N5".to_owned()].into(),
+            HashSet::new(),
+        );
+        deck.add_word(1000, &word).unwrap();
+        deck.add_media("eat.mp3", vec![1, 2, 3]);
+
+        assert!(deck.add_word(1, &word).is_err());
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("deck.apkg");
+        deck.write(&path).unwrap();
+
+        let mut zip = ZipArchive::new(File::open(&path).unwrap()).unwrap();
+
+        let mut media = String::new();
+        zip.by_name("media")
+            .unwrap()
+            .read_to_string(&mut media)
+            .unwrap();
+        assert_eq!(media, r#"{"0":"eat.mp3"}"#);
+
+        let mut data = Vec::new();
+        zip.by_name("0").unwrap().read_to_end(&mut data).unwrap();
+        assert_eq!(data, vec![1, 2, 3]);
+
+        let db_path = dir.path().join("collection.anki2");
+        let mut collection = Vec::new();
+        zip.by_name("collection.anki2")
+            .unwrap()
+            .read_to_end(&mut collection)
+            .unwrap();
+        std::fs::write(&db_path, collection).unwrap();
+
+        let conn = Connection::open(&db_path).unwrap();
+        let (flds, tags, sfld): (String, String, String) = conn
+            .query_row("SELECT flds, tags, sfld FROM notes", [], |row| {
+                Ok((row.get(0)?, row.get(1)?, row.get(2)?))
+            })
+            .unwrap();
+        assert_eq!(flds, "食[た]べる\u{1f}[ v1 ] to eat\u{1f}");
+        assert_eq!(tags, " JLPT-N5 v1 ");
+        assert_eq!(sfld, "食[た]べる");
+
+        let (did, ord): (i64, i64) = conn
+            .query_row("SELECT did, ord FROM cards", [], |row| {
+                Ok((row.get(0)?, row.get(1)?))
+            })
+            .unwrap();
+        assert_eq!((did, ord), (2000, 0));
+    }
+
+    /// Tests that guids are stable and that the checksum matches Anki's definition.
+    #[test]
+    fn note_identity() {
+        assert_eq!(guid_for("1000\u{1f}猫"), guid_for("1000\u{1f}猫"));
+        assert_ne!(guid_for("1000\u{1f}猫"), guid_for("1000\u{1f}犬"));
+
+        // sha1("abc") = a9993e36...
+        assert_eq!(field_checksum("abc"), 0xa9993e36);
+    }
+}
@@ -1,2 +1,9 @@
+pub mod anki_changeset;
 pub mod anki_connect;
+pub mod anki_connect_batch;
+pub mod anki_connect_error;
+#[cfg(any(test, feature = "mock"))]
+pub mod anki_connect_mock;
+pub mod anki_media;
 pub mod anki_note;
+pub mod apkg;
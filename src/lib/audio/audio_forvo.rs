@@ -0,0 +1,169 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use serde::{Deserialize, Serialize};
+
+use super::audio_source::{download, media_filename, AudioClip, AudioSource, Response};
+
+/// A pronunciation of a Forvo-style index, named like the items of the Forvo API.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+#[serde(default)]
+pub struct ForvoItem {
+    /// The word as written.
+    pub word: String,
+    /// The reading of the word, if the index records one.
+    pub reading: Option<String>,
+    /// The language code, `ja` for Japanese.
+    pub code: Option<String>,
+    /// The user who recorded the pronunciation.
+    pub username: String,
+    /// The url or the path relative to the index of the MP3 file.
+    pub pathmp3: Option<String>,
+    /// The url or the path relative to the index of the OGG file.
+    pub pathogg: Option<String>,
+    /// The rating of the pronunciation.
+    pub rate: i32,
+    /// The number of votes the rating is based on.
+    pub num_votes: u32,
+}
+
+/// The contents of a Forvo-style index file.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+#[serde(default)]
+pub struct ForvoIndexFile {
+    /// The pronunciations.
+    pub items: Vec<ForvoItem>,
+}
+
+/// A JSON index of pronunciations in the format of the Forvo API, e.g. saved API responses
+/// merged into one `items` list. The best rated Japanese pronunciation of a word is used.
+#[derive(Debug, Clone, Default)]
+pub struct ForvoIndex {
+    /// The directory relative paths are read from.
+    base: PathBuf,
+    /// The pronunciations.
+    items: Vec<ForvoItem>,
+}
+
+impl ForvoIndex {
+    /// Reads an index file.
+    pub fn open(path: &Path) -> Response<Self> {
+        let index: ForvoIndexFile = serde_json::from_str(&fs::read_to_string(path)?)?;
+
+        Ok(Self::new(
+            path.parent().unwrap_or(Path::new(".")),
+            index.items,
+        ))
+    }
+
+    /// Creates a new ForvoIndex reading relative paths from `base`.
+    pub fn new(base: &Path, items: Vec<ForvoItem>) -> Self {
+        Self {
+            base: base.to_owned(),
+            items,
+        }
+    }
+
+    /// Returns the best rated pronunciation of a word with an audio file.
+    pub fn best(&self, kanji: &str, reading: &str) -> Option<(&ForvoItem, &str)> {
+        self.items
+            .iter()
+            .filter(|item| item.word == kanji)
+            .filter(|item| item.reading.as_deref().is_none_or(|r| r == reading))
+            .filter(|item| item.code.as_deref().is_none_or(|code| code == "ja"))
+            .filter_map(|item| Some((item, item.pathmp3.as_deref().or(item.pathogg.as_deref())?)))
+            .max_by_key(|(item, _)| (item.rate, item.num_votes))
+    }
+
+    /// Returns whether any audio file has to be downloaded.
+    fn is_remote(&self) -> bool {
+        self.items.iter().any(|item| {
+            [&item.pathmp3, &item.pathogg]
+                .into_iter()
+                .flatten()
+                .any(|path| is_url(path))
+        })
+    }
+}
+
+/// Returns whether a path of the index is a url.
+fn is_url(path: &str) -> bool {
+    path.starts_with("http://") || path.starts_with("https://")
+}
+
+impl AudioSource for ForvoIndex {
+    fn name(&self) -> &str {
+        "Forvo"
+    }
+
+    /// One second between lookups if files are downloaded, none otherwise.
+    fn rate_limit(&self) -> Duration {
+        if self.is_remote() {
+            Duration::from_secs(1)
+        } else {
+            Duration::ZERO
+        }
+    }
+
+    fn find(&self, kanji: &str, reading: &str) -> Response<Option<AudioClip>> {
+        let Some((_, path)) = self.best(kanji, reading) else {
+            return Ok(None);
+        };
+
+        let data = if is_url(path) {
+            download(path)?
+        } else {
+            fs::read(self.base.join(path))?
+        };
+
+        let extension = path
+            .rsplit_once('.')
+            .map(|(_, ext)| ext)
+            .filter(|ext| ext.len() <= 4 && !ext.contains('/'))
+            .unwrap_or("mp3");
+
+        Ok(Some(AudioClip {
+            source: self.name().to_owned(),
+            filename: media_filename(self.name(), kanji, reading, extension),
+            data,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tests choosing the best rated pronunciation and reading local files.
+    #[test]
+    fn forvo_index() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        fs::write(dir.join("neko_1.mp3"), "neko 1").unwrap();
+        fs::write(dir.join("neko_2.ogg"), "neko 2").unwrap();
+        fs::write(
+            dir.join("forvo.json"),
+            r#"{"items": [
+                {"word": "猫", "username": "a", "pathmp3": "neko_1.mp3", "rate": 1},
+                {"word": "猫", "username": "b", "pathogg": "neko_2.ogg", "rate": 3},
+                {"word": "猫", "username": "c", "code": "zh", "pathmp3": "mao.mp3", "rate": 9},
+                {"word": "猫", "reading": "びょう", "username": "d", "pathmp3": "byou.mp3", "rate": 9},
+                {"word": "犬", "username": "e", "rate": 9}
+            ]}"#,
+        )
+        .unwrap();
+
+        let index = ForvoIndex::open(&dir.join("forvo.json")).unwrap();
+
+        assert_eq!(index.rate_limit(), Duration::ZERO);
+        assert_eq!(index.best("猫", "ねこ").unwrap().0.username, "b");
+
+        let clip = index.find("猫", "ねこ").unwrap().unwrap();
+        assert_eq!(clip.filename, "Forvo_猫「ねこ」.ogg");
+        assert_eq!(clip.data, b"neko 2");
+        assert_eq!(index.find("犬", "いぬ").unwrap(), None);
+    }
+}
@@ -0,0 +1,53 @@
+use std::time::Duration;
+
+use super::audio_source::{download, media_filename, AudioClip, AudioSource, Response};
+
+/// The MD5 hash of the "audio not available" recording JapanesePod101 answers with for words
+/// it has no audio of.
+pub const PLACEHOLDER_HASH: &str = "7e2c2f954ef6051373ba916f000168dc";
+
+/// The dictionary audio of JapanesePod101, looked up by kanji and reading.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct JapanesePod101;
+
+impl JapanesePod101 {
+    /// Returns the url of the audio of a word.
+    ///
+    /// # Example
+    /// ```
+    /// use anki_utill::audio::audio_jpod101::JapanesePod101;
+    ///
+    /// assert_eq!(
+    ///     JapanesePod101::url("猫", "ねこ"),
+    ///     "https://assets.languagepod101.com/dictionary/japanese/audiomp3.php?kanji=猫&kana=ねこ"
+    /// );
+    /// ```
+    pub fn url(kanji: &str, reading: &str) -> String {
+        format!(
+            "https://assets.languagepod101.com/dictionary/japanese/audiomp3.php?kanji={}&kana={}",
+            kanji, reading
+        )
+    }
+}
+
+impl AudioSource for JapanesePod101 {
+    fn name(&self) -> &str {
+        "JapanesePod101"
+    }
+
+    fn rate_limit(&self) -> Duration {
+        Duration::from_secs(2)
+    }
+
+    fn placeholder_hashes(&self) -> &[&str] {
+        &[PLACEHOLDER_HASH]
+    }
+
+    fn find(&self, kanji: &str, reading: &str) -> Response<Option<AudioClip>> {
+        Ok(Some(AudioClip {
+            source: self.name().to_owned(),
+            filename: media_filename(self.name(), kanji, reading, "mp3"),
+            data: download(&Self::url(kanji, reading))?,
+        }))
+    }
+}
@@ -0,0 +1,373 @@
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use rusqlite::{Connection, OpenFlags};
+use serde::Deserialize;
+
+use crate::japanese::JapaneseStr;
+
+use super::{
+    audio_local::word_of_file,
+    audio_source::{media_filename, AudioClip, AudioSource, Response},
+};
+
+/// A recording of a local audio library.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LibraryEntry {
+    /// The word as written.
+    pub kanji: String,
+    /// The reading of the word, `None` if the library does not record it.
+    pub reading: Option<String>,
+    /// The source the recording comes from, e.g. `nhk16`.
+    pub source: String,
+    /// The speaker, if known.
+    pub speaker: Option<String>,
+    /// The audio file.
+    pub path: PathBuf,
+}
+
+/// The `index.json` of a source folder: the files of every headword and their readings.
+#[derive(Deserialize, Debug, Default)]
+#[serde(default)]
+struct SourceIndex {
+    /// The files of every headword.
+    headwords: HashMap<String, Vec<String>>,
+    /// Information about every file.
+    files: HashMap<String, SourceFile>,
+}
+
+/// Information about a file of a source folder `index.json`.
+#[derive(Deserialize, Debug, Default)]
+#[serde(default)]
+struct SourceFile {
+    /// The reading of the word.
+    kana_reading: Option<String>,
+    /// The speaker.
+    speaker: Option<String>,
+}
+
+/// A local word audio library such as a Yomitan local audio server dump, looked up by kanji
+/// and reading.
+///
+/// The library is read from `entries.db`, an SQLite table `entries` of the `expression`,
+/// `reading`, `source`, `speaker` and `file` of every recording, the files being in the folder
+/// `{source}_files`. Without it, the `*_files` folders are scanned: a folder's `index.json`
+/// lists the `headwords` and the `kana_reading` of their `files`, otherwise the files are
+/// named after their word like those of [`LocalAudio`](super::audio_local::LocalAudio) and
+/// files in subfolders are recorded by the speaker named by the folder.
+#[derive(Debug, Clone, Default)]
+pub struct AudioLibrary {
+    /// The recordings by the word as written.
+    entries: HashMap<String, Vec<LibraryEntry>>,
+    /// The sources in order of preference.
+    sources: Vec<String>,
+    /// The speakers in order of preference.
+    speakers: Vec<String>,
+}
+
+impl AudioLibrary {
+    /// Indexes the library in a directory.
+    pub fn open(path: &Path) -> Response<Self> {
+        let database = path.join("entries.db");
+
+        let entries = if database.is_file() {
+            read_database(&database)?
+        } else {
+            scan_folders(path)?
+        };
+
+        Ok(Self::new(entries))
+    }
+
+    /// Creates a new AudioLibrary from its recordings.
+    pub fn new(entries: Vec<LibraryEntry>) -> Self {
+        let mut out: HashMap<String, Vec<LibraryEntry>> = HashMap::new();
+        for entry in entries {
+            out.entry(entry.kanji.clone()).or_default().push(entry);
+        }
+
+        Self {
+            entries: out,
+            sources: Vec::new(),
+            speakers: Vec::new(),
+        }
+    }
+
+    /// Sets the sources and speakers in order of preference. Unlisted ones come last.
+    pub fn with_ranking(mut self, sources: &[String], speakers: &[String]) -> Self {
+        self.sources = sources.to_vec();
+        self.speakers = speakers.to_vec();
+        self
+    }
+
+    /// Returns the number of recordings.
+    pub fn len(&self) -> usize {
+        self.entries.values().map(Vec::len).sum()
+    }
+
+    /// Returns whether there are no recordings.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the recordings of a word, the preferred source and speaker first. Recordings
+    /// without a reading match any reading.
+    pub fn lookup(&self, kanji: &str, reading: &str) -> Vec<&LibraryEntry> {
+        let reading = reading.to_hiragana();
+
+        let mut out: Vec<&LibraryEntry> = self
+            .entries
+            .get(kanji)
+            .into_iter()
+            .flatten()
+            .filter(|entry| {
+                entry
+                    .reading
+                    .as_deref()
+                    .is_none_or(|r| r.to_hiragana() == reading)
+            })
+            .collect();
+
+        out.sort_by_key(|entry| {
+            (
+                rank(&self.sources, Some(&entry.source)),
+                rank(&self.speakers, entry.speaker.as_ref()),
+                entry.reading.is_none(),
+            )
+        });
+
+        out
+    }
+}
+
+/// Returns the position of a name in an order of preference, unlisted names last.
+fn rank(order: &[String], name: Option<&String>) -> usize {
+    name.and_then(|name| order.iter().position(|n| n == name))
+        .unwrap_or(order.len())
+}
+
+/// Reads the recordings of an `entries.db`.
+fn read_database(path: &Path) -> Response<Vec<LibraryEntry>> {
+    let root = path.parent().unwrap_or(Path::new("."));
+    let conn = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)?;
+
+    let mut statement =
+        conn.prepare("SELECT expression, reading, source, speaker, file FROM entries")?;
+    let rows = statement.query_map([], |row| {
+        let source: String = row.get(2)?;
+        let file: String = row.get(4)?;
+
+        Ok(LibraryEntry {
+            kanji: row.get(0)?,
+            reading: row.get(1)?,
+            path: root.join(format!("{}_files", source)).join(file),
+            source,
+            speaker: row.get(3)?,
+        })
+    })?;
+
+    Ok(rows.collect::<Result<_, _>>()?)
+}
+
+/// Scans the `*_files` source folders of a library without an `entries.db`.
+fn scan_folders(path: &Path) -> Response<Vec<LibraryEntry>> {
+    let mut folders: Vec<PathBuf> = fs::read_dir(path)?
+        .filter_map(|entry| Some(entry.ok()?.path()))
+        .filter(|path| path.is_dir())
+        .collect();
+    folders.sort_unstable();
+
+    let mut out = Vec::new();
+    for folder in folders {
+        let Some(source) = folder
+            .file_name()
+            .and_then(|name| name.to_str())
+            .and_then(|name| name.strip_suffix("_files"))
+        else {
+            continue;
+        };
+
+        let index = folder.join("index.json");
+        if index.is_file() {
+            let index: SourceIndex = serde_json::from_str(&fs::read_to_string(index)?)?;
+
+            for (kanji, files) in index.headwords {
+                for file in files {
+                    let info = index.files.get(&file);
+
+                    out.push(LibraryEntry {
+                        kanji: kanji.clone(),
+                        reading: info.and_then(|info| info.kana_reading.clone()),
+                        source: source.to_owned(),
+                        speaker: info.and_then(|info| info.speaker.clone()),
+                        path: folder.join(file),
+                    });
+                }
+            }
+        } else {
+            scan_files(&folder, source, None, &mut out)?;
+        }
+    }
+
+    Ok(out)
+}
+
+/// Adds the recordings named after their word in a folder, files in subfolders being recorded
+/// by the speaker named by the folder.
+fn scan_files(
+    folder: &Path,
+    source: &str,
+    speaker: Option<&str>,
+    out: &mut Vec<LibraryEntry>,
+) -> Response<()> {
+    let mut paths: Vec<PathBuf> = fs::read_dir(folder)?
+        .filter_map(|entry| Some(entry.ok()?.path()))
+        .collect();
+    paths.sort_unstable();
+
+    for path in paths {
+        if path.is_dir() {
+            let name = path.file_name().and_then(|name| name.to_str());
+            scan_files(&path, source, name.or(speaker), out)?;
+            continue;
+        }
+
+        let Some((kanji, reading)) = word_of_file(&path) else {
+            continue;
+        };
+
+        out.push(LibraryEntry {
+            reading: (kanji != reading).then_some(reading),
+            kanji,
+            source: source.to_owned(),
+            speaker: speaker.map(str::to_owned),
+            path,
+        });
+    }
+
+    Ok(())
+}
+
+impl AudioSource for AudioLibrary {
+    fn name(&self) -> &str {
+        "Library"
+    }
+
+    fn rate_limit(&self) -> Duration {
+        Duration::ZERO
+    }
+
+    /// Returns the best ranked recording whose file can be read.
+    fn find(&self, kanji: &str, reading: &str) -> Response<Option<AudioClip>> {
+        for entry in self.lookup(kanji, reading) {
+            let Ok(data) = fs::read(&entry.path) else {
+                continue;
+            };
+
+            let extension = entry
+                .path
+                .extension()
+                .and_then(|ext| ext.to_str())
+                .unwrap_or("mp3");
+
+            return Ok(Some(AudioClip {
+                source: format!("{} ({})", self.name(), entry.source),
+                filename: media_filename(self.name(), kanji, reading, extension),
+                data,
+            }));
+        }
+
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tests reading an `entries.db` and ranking by source and speaker.
+    #[test]
+    fn database() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        fs::create_dir_all(dir.join("nhk16_files")).unwrap();
+        fs::create_dir_all(dir.join("forvo_files").join("akira")).unwrap();
+        fs::write(dir.join("nhk16_files").join("neko.opus"), "nhk").unwrap();
+        fs::write(
+            dir.join("forvo_files").join("akira").join("猫.mp3"),
+            "forvo",
+        )
+        .unwrap();
+
+        let conn = Connection::open(dir.join("entries.db")).unwrap();
+        conn.execute_batch(
+            "CREATE TABLE entries (id integer PRIMARY KEY, expression text NOT NULL,
+                reading text, source text NOT NULL, speaker text, display text,
+                file text NOT NULL);
+            INSERT INTO entries (expression, reading, source, speaker, file) VALUES
+                ('猫', 'ねこ', 'nhk16', NULL, 'neko.opus'),
+                ('猫', NULL, 'forvo', 'akira', 'akira/猫.mp3'),
+                ('猫', 'ビョウ', 'jpod', NULL, 'byou.mp3');",
+        )
+        .unwrap();
+        drop(conn);
+
+        let sources = ["forvo".to_owned(), "nhk16".to_owned()];
+        let library = AudioLibrary::open(dir).unwrap().with_ranking(&sources, &[]);
+
+        assert_eq!(library.len(), 3);
+        let found = library.lookup("猫", "ねこ");
+        assert_eq!(found.len(), 2);
+        assert_eq!(found[0].source, "forvo");
+        assert_eq!(found[0].speaker.as_deref(), Some("akira"));
+        let found = library.lookup("猫", "びょう");
+        assert_eq!(found.len(), 2);
+        assert_eq!(found[1].source, "jpod");
+
+        let clip = library.find("猫", "ねこ").unwrap().unwrap();
+        assert_eq!(clip.filename, "Library_猫「ねこ」.mp3");
+        assert_eq!(clip.data, b"forvo");
+
+        let library = library.with_ranking(&[], &[]);
+        assert_eq!(library.find("猫", "ねこ").unwrap().unwrap().data, b"nhk");
+    }
+
+    /// Tests scanning source folders with and without an `index.json`.
+    #[test]
+    fn folders() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        fs::create_dir_all(dir.join("jpod_files")).unwrap();
+        fs::create_dir_all(dir.join("forvo_files").join("yuki")).unwrap();
+        fs::write(
+            dir.join("jpod_files").join("index.json"),
+            r#"{
+                "headwords": {"猫": ["ねこ - 猫.mp3"]},
+                "files": {"ねこ - 猫.mp3": {"kana_reading": "ねこ"}}
+            }"#,
+        )
+        .unwrap();
+        fs::write(dir.join("jpod_files").join("ねこ - 猫.mp3"), "jpod").unwrap();
+        fs::write(dir.join("forvo_files").join("yuki").join("猫.mp3"), "yuki").unwrap();
+        fs::write(dir.join("forvo_files").join("yuki").join("いぬ.mp3"), "inu").unwrap();
+
+        let speakers = ["yuki".to_owned()];
+        let library = AudioLibrary::open(dir)
+            .unwrap()
+            .with_ranking(&["jpod".to_owned()], &speakers);
+
+        assert_eq!(library.len(), 3);
+        let found = library.lookup("猫", "ねこ");
+        assert_eq!(found[0].source, "jpod");
+        assert_eq!(found[1].speaker.as_deref(), Some("yuki"));
+        assert_eq!(
+            library.lookup("いぬ", "いぬ")[0].path,
+            dir.join("forvo_files/yuki/いぬ.mp3")
+        );
+        assert!(library.lookup("犬", "いぬ").is_empty());
+    }
+}
@@ -0,0 +1,157 @@
+use std::{
+    collections::HashMap,
+    fs, io,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use super::audio_source::{media_filename, AudioClip, AudioSource, Response};
+
+/// The extensions of the audio files read.
+pub const AUDIO_EXTENSIONS: [&str; 6] = ["mp3", "ogg", "opus", "m4a", "wav", "flac"];
+
+/// A directory of recordings named after their word: `猫_ねこ.mp3`, `猫「ねこ」.mp3`,
+/// `猫[ねこ].mp3` or `ねこ.mp3` for words written in kana. Subdirectories are included, the
+/// first file in name order is used if a word has several.
+#[derive(Debug, Clone, Default)]
+pub struct LocalAudio {
+    files: HashMap<(String, String), PathBuf>,
+}
+
+impl LocalAudio {
+    /// Indexes the recordings of a directory.
+    pub fn open(path: &Path) -> io::Result<Self> {
+        let mut paths = Vec::new();
+        collect_files(path, &mut paths)?;
+        paths.sort_unstable();
+
+        let mut files = HashMap::new();
+        for path in paths {
+            let Some(word) = word_of_file(&path) else {
+                continue;
+            };
+
+            files.entry(word).or_insert(path);
+        }
+
+        Ok(Self { files })
+    }
+
+    /// Returns the number of words with a recording.
+    pub fn len(&self) -> usize {
+        self.files.len()
+    }
+
+    /// Returns whether there are no recordings.
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+}
+
+/// Adds the files of a directory and its subdirectories.
+fn collect_files(path: &Path, out: &mut Vec<PathBuf>) -> io::Result<()> {
+    for entry in fs::read_dir(path)? {
+        let path = entry?.path();
+
+        if path.is_dir() {
+            collect_files(&path, out)?;
+        } else {
+            out.push(path);
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns the `(kanji, reading)` of a recording named after its word, `None` for other files.
+///
+/// # Example
+/// ```
+/// use std::path::Path;
+///
+/// use anki_utill::audio::audio_local::word_of_file;
+///
+/// let word = Some(("猫".to_owned(), "ねこ".to_owned()));
+/// assert_eq!(word_of_file(Path::new("猫_ねこ.mp3")), word);
+/// assert_eq!(word_of_file(Path::new("猫「ねこ」.ogg")), word);
+/// assert_eq!(word_of_file(Path::new("猫.txt")), None);
+/// ```
+pub fn word_of_file(path: &Path) -> Option<(String, String)> {
+    let extension = path.extension()?.to_str()?.to_lowercase();
+    if !AUDIO_EXTENSIONS.contains(&extension.as_str()) {
+        return None;
+    }
+
+    let stem = path.file_stem()?.to_str()?;
+    let (kanji, reading) = if let Some((kanji, reading)) = stem.split_once('_') {
+        (kanji, reading)
+    } else if let Some((kanji, reading)) = stem.split_once('「') {
+        (kanji, reading.strip_suffix('」')?)
+    } else if let Some((kanji, reading)) = stem.split_once('[') {
+        (kanji, reading.strip_suffix(']')?)
+    } else {
+        (stem, stem)
+    };
+
+    if kanji.is_empty() || reading.is_empty() {
+        return None;
+    }
+
+    Some((kanji.to_owned(), reading.to_owned()))
+}
+
+impl AudioSource for LocalAudio {
+    fn name(&self) -> &str {
+        "Local"
+    }
+
+    fn rate_limit(&self) -> Duration {
+        Duration::ZERO
+    }
+
+    fn find(&self, kanji: &str, reading: &str) -> Response<Option<AudioClip>> {
+        let Some(path) = self.files.get(&(kanji.to_owned(), reading.to_owned())) else {
+            return Ok(None);
+        };
+
+        let extension = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .unwrap_or("mp3");
+
+        Ok(Some(AudioClip {
+            source: self.name().to_owned(),
+            filename: media_filename(self.name(), kanji, reading, extension),
+            data: fs::read(path)?,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tests indexing a directory and looking up its recordings.
+    #[test]
+    fn local_audio() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        fs::create_dir_all(dir.join("speaker")).unwrap();
+        fs::write(dir.join("猫_ねこ.mp3"), "neko").unwrap();
+        fs::write(dir.join("speaker").join("猫「ねこ」.ogg"), "neko 2").unwrap();
+        fs::write(dir.join("speaker").join("いぬ.ogg"), "inu").unwrap();
+        fs::write(dir.join("notes.txt"), "").unwrap();
+
+        let audio = LocalAudio::open(dir).unwrap();
+
+        assert_eq!(audio.len(), 2);
+        let clip = audio.find("猫", "ねこ").unwrap().unwrap();
+        assert_eq!(clip.filename, "Local_猫「ねこ」.ogg");
+        assert_eq!(clip.data, b"neko 2");
+        assert_eq!(
+            audio.find("いぬ", "いぬ").unwrap().unwrap().filename,
+            "Local_いぬ.ogg"
+        );
+        assert_eq!(audio.find("犬", "いぬ").unwrap(), None);
+    }
+}
@@ -0,0 +1,319 @@
+use std::{
+    error::Error,
+    thread,
+    time::{Duration, Instant},
+};
+
+use md5::{Digest, Md5};
+
+use crate::config::{AudioSourceKind, Config};
+
+use super::{
+    audio_forvo::ForvoIndex,
+    audio_jpod101::JapanesePod101,
+    audio_library::AudioLibrary,
+    audio_local::LocalAudio,
+    audio_validate::{read_blocklist, AudioValidator, Rejection},
+};
+
+/// Represents a result of looking up audio.
+pub type Response<T> = Result<T, Box<dyn Error>>;
+
+/// The audio of a word found by an [`AudioSource`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AudioClip {
+    /// The name of the source it was found in.
+    pub source: String,
+    /// The media filename to store it as.
+    pub filename: String,
+    /// The contents of the audio file.
+    pub data: Vec<u8>,
+}
+
+/// Audio found by an [`AudioSource`] and rejected by the [`AudioValidator`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RejectedAudio {
+    /// The name of the source it was found in.
+    pub source: String,
+    /// The MD5 hash of the file, to add to the blocklist.
+    pub hash: String,
+    /// Why it was rejected.
+    pub reason: Rejection,
+}
+
+/// The result of looking up the audio of a word in an [`AudioChain`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AudioLookup {
+    /// The first valid audio found.
+    pub clip: Option<AudioClip>,
+    /// The audio rejected before it was found.
+    pub rejected: Vec<RejectedAudio>,
+}
+
+/// A source of word pronunciations.
+pub trait AudioSource {
+    /// Returns the name of the source, used in media filenames and reports.
+    fn name(&self) -> &str;
+
+    /// Returns the minimum time between two lookups, zero for local sources.
+    fn rate_limit(&self) -> Duration;
+
+    /// Returns the MD5 hashes of the placeholder files the source answers with when it has no
+    /// audio, e.g. a "not available" recording.
+    fn placeholder_hashes(&self) -> &[&str] {
+        &[]
+    }
+
+    /// Looks up the audio of a word.
+    ///
+    /// # Arguments
+    /// * `kanji` - The word as written, the reading for words written in kana.
+    /// * `reading` - The reading in kana.
+    ///
+    /// # Returns
+    /// * The audio, or `None` if the source has none.
+    fn find(&self, kanji: &str, reading: &str) -> Response<Option<AudioClip>>;
+}
+
+/// Returns the media filename of the audio of a word, e.g. `JapanesePod101_猫「ねこ」.mp3`.
+///
+/// # Example
+/// ```
+/// use anki_utill::audio::audio_source::media_filename;
+///
+/// assert_eq!(media_filename("Forvo", "猫", "ねこ", "ogg"), "Forvo_猫「ねこ」.ogg");
+/// assert_eq!(media_filename("Forvo", "ねこ", "ねこ", "ogg"), "Forvo_ねこ.ogg");
+/// ```
+pub fn media_filename(source: &str, kanji: &str, reading: &str, extension: &str) -> String {
+    if kanji == reading {
+        format!("{}_{}.{}", source, reading, extension)
+    } else {
+        format!("{}_{}「{}」.{}", source, kanji, reading, extension)
+    }
+}
+
+/// Returns the hexadecimal MD5 hash of a file, the hash AnkiConnect's `skipHash` uses.
+pub fn md5_hex(data: &[u8]) -> String {
+    format!("{:x}", Md5::digest(data))
+}
+
+/// Downloads a file, failing on a non-success HTTP status.
+pub(crate) fn download(url: &str) -> Response<Vec<u8>> {
+    let response = reqwest::blocking::get(url)?.error_for_status()?;
+
+    Ok(response.bytes()?.to_vec())
+}
+
+/// Tries audio sources in priority order, waiting out the rate limit of each source.
+pub struct AudioChain {
+    sources: Vec<Box<dyn AudioSource>>,
+    last_lookup: Vec<Option<Instant>>,
+    validator: AudioValidator,
+}
+
+impl AudioChain {
+    /// Creates a new AudioChain trying the sources in the given order.
+    pub fn new(sources: Vec<Box<dyn AudioSource>>) -> Self {
+        let last_lookup = vec![None; sources.len()];
+
+        Self {
+            sources,
+            last_lookup,
+            validator: AudioValidator::default(),
+        }
+    }
+
+    /// Sets the validator the audio found has to pass.
+    pub fn with_validator(mut self, validator: AudioValidator) -> Self {
+        self.validator = validator;
+        self
+    }
+
+    /// Creates the AudioChain of the configured sources, leaving out local sources whose files
+    /// are missing. The audio has to pass the configured validation and blocklist.
+    pub fn from_config(config: &Config) -> Response<Self> {
+        let paths = &config.paths;
+        let mut sources: Vec<Box<dyn AudioSource>> = Vec::new();
+
+        for kind in &config.audio.sources {
+            match kind {
+                AudioSourceKind::Library if paths.audio_library.is_dir() => {
+                    let library = AudioLibrary::open(&paths.audio_library)?.with_ranking(
+                        &config.audio.library_sources,
+                        &config.audio.library_speakers,
+                    );
+                    sources.push(Box::new(library));
+                }
+                AudioSourceKind::Local if paths.audio.is_dir() => {
+                    sources.push(Box::new(LocalAudio::open(&paths.audio)?));
+                }
+                AudioSourceKind::Forvo if paths.forvo_index.is_file() => {
+                    sources.push(Box::new(ForvoIndex::open(&paths.forvo_index)?));
+                }
+                AudioSourceKind::JapanesePod101 => sources.push(Box::new(JapanesePod101)),
+                _ => {}
+            }
+        }
+
+        let mut validator =
+            AudioValidator::default().decode(Duration::from_millis(config.audio.min_duration_ms));
+        if paths.audio_blocklist.is_file() {
+            validator = validator.with_blocklist(read_blocklist(&paths.audio_blocklist)?);
+        }
+
+        Ok(Self::new(sources).with_validator(validator))
+    }
+
+    /// Returns the names of the sources, in order.
+    pub fn names(&self) -> Vec<&str> {
+        self.sources.iter().map(|source| source.name()).collect()
+    }
+
+    /// Returns whether there are no sources.
+    pub fn is_empty(&self) -> bool {
+        self.sources.is_empty()
+    }
+
+    /// Looks up the audio of a word in every source in order until one has valid audio.
+    /// A source that fails is skipped.
+    ///
+    /// # Returns
+    /// * The first valid audio, `None` if no source has any, or the first error if a source
+    ///   failed and none had audio.
+    pub fn find(&mut self, kanji: &str, reading: &str) -> Response<Option<AudioClip>> {
+        Ok(self.lookup(kanji, reading)?.clip)
+    }
+
+    /// Looks up the audio of a word like [`AudioChain::find`], also returning the audio the
+    /// validator rejected.
+    pub fn lookup(&mut self, kanji: &str, reading: &str) -> Response<AudioLookup> {
+        let mut lookup = AudioLookup::default();
+        let mut error = None;
+
+        for (source, last_lookup) in self.sources.iter().zip(&mut self.last_lookup) {
+            if let Some(last_lookup) = last_lookup {
+                thread::sleep(source.rate_limit().saturating_sub(last_lookup.elapsed()));
+            }
+            *last_lookup = Some(Instant::now());
+
+            let clip = match source.find(kanji, reading) {
+                Ok(Some(clip)) => clip,
+                Ok(None) => continue,
+                Err(err) => {
+                    error.get_or_insert(err);
+                    continue;
+                }
+            };
+
+            let hash = md5_hex(&clip.data);
+            if source.placeholder_hashes().contains(&hash.as_str()) {
+                continue;
+            }
+
+            match self.validator.check(&clip.data) {
+                Ok(_) => {
+                    lookup.clip = Some(clip);
+                    return Ok(lookup);
+                }
+                Err(reason) => lookup.rejected.push(RejectedAudio {
+                    source: clip.source,
+                    hash,
+                    reason,
+                }),
+            }
+        }
+
+        match error {
+            Some(err) => Err(err),
+            None => Ok(lookup),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A source answering every lookup with the same data.
+    struct FixedSource {
+        name: &'static str,
+        rate_limit: Duration,
+        data: Option<&'static [u8]>,
+    }
+
+    impl AudioSource for FixedSource {
+        fn name(&self) -> &str {
+            self.name
+        }
+
+        fn rate_limit(&self) -> Duration {
+            self.rate_limit
+        }
+
+        fn placeholder_hashes(&self) -> &[&str] {
+            // The hash of "missing"
+            &["ea21841da70e6405af19fabc4ff8bdd9"]
+        }
+
+        fn find(&self, kanji: &str, reading: &str) -> Response<Option<AudioClip>> {
+            Ok(self.data.map(|data| AudioClip {
+                source: self.name.to_owned(),
+                filename: media_filename(self.name, kanji, reading, "mp3"),
+                data: data.to_vec(),
+            }))
+        }
+    }
+
+    /// Tests that the sources are tried in order, skipping empty, placeholder and rejected audio,
+    /// and that the rate limit of a source is waited out.
+    #[test]
+    fn fallback() {
+        let source = |name, data, millis| -> Box<dyn AudioSource> {
+            Box::new(FixedSource {
+                name,
+                rate_limit: Duration::from_millis(millis),
+                data,
+            })
+        };
+
+        let mut chain = AudioChain::new(vec![
+            source("None", None, 0),
+            source("Empty", Some(b""), 0),
+            source("Placeholder", Some(b"missing"), 0),
+            source("Slow", Some(b"audio"), 100),
+        ]);
+        assert_eq!(chain.names(), vec!["None", "Empty", "Placeholder", "Slow"]);
+
+        let start = Instant::now();
+        let clip = chain.find("猫", "ねこ").unwrap().unwrap();
+        assert_eq!(clip.source, "Slow");
+        assert_eq!(clip.filename, "Slow_猫「ねこ」.mp3");
+
+        chain.find("犬", "いぬ").unwrap().unwrap();
+        assert!(start.elapsed() >= Duration::from_millis(100));
+
+        assert_eq!(
+            AudioChain::new(Vec::new()).find("猫", "ねこ").unwrap(),
+            None
+        );
+        let mut chain = AudioChain::new(vec![
+            source("Placeholder", Some(b"missing"), 0),
+            source("Html", Some(b"<!DOCTYPE html>"), 0),
+            source("Blocked", Some(b"audio"), 0),
+        ])
+        .with_validator(AudioValidator::default().with_blocklist([md5_hex(b"audio")]));
+        let lookup = chain.lookup("猫", "ねこ").unwrap();
+        assert_eq!(lookup.clip, None);
+        assert_eq!(
+            lookup
+                .rejected
+                .iter()
+                .map(|audio| (audio.source.as_str(), audio.reason.clone()))
+                .collect::<Vec<_>>(),
+            vec![
+                ("Html", Rejection::HtmlPage),
+                ("Blocked", Rejection::Blocklisted)
+            ]
+        );
+    }
+}
@@ -0,0 +1,523 @@
+use std::{collections::HashSet, fmt, fs, io, path::Path, time::Duration};
+
+use super::audio_source::md5_hex;
+
+/// The container format of an audio file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AudioFormat {
+    Mp3,
+    Ogg,
+    Wav,
+    Flac,
+    Mp4,
+}
+
+/// What is known about an audio file from its headers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AudioInfo {
+    /// The container format.
+    pub format: AudioFormat,
+    /// The length of the audio, `None` if the headers do not tell.
+    pub duration: Option<Duration>,
+    /// Whether the audio is digital silence. Only detected in MP3 and WAV files.
+    pub silent: bool,
+}
+
+/// Why audio was rejected by an [`AudioValidator`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Rejection {
+    /// The file is empty.
+    Empty,
+    /// The file is an HTML page, e.g. an error page saved as `.mp3`.
+    HtmlPage,
+    /// The MD5 hash of the file is on the blocklist.
+    Blocklisted,
+    /// The file is no audio in a known format.
+    UnknownFormat,
+    /// The audio is shorter than the minimum duration.
+    TooShort(Duration),
+    /// The audio is silent.
+    Silent,
+}
+
+impl fmt::Display for Rejection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Rejection::Empty => write!(f, "empty file"),
+            Rejection::HtmlPage => write!(f, "HTML page"),
+            Rejection::Blocklisted => write!(f, "blocklisted"),
+            Rejection::UnknownFormat => write!(f, "unknown audio format"),
+            Rejection::TooShort(duration) => write!(f, "too short ({} ms)", duration.as_millis()),
+            Rejection::Silent => write!(f, "silent"),
+        }
+    }
+}
+
+/// Checks that audio files are usable.
+///
+/// Empty files, HTML pages and files whose MD5 hash is on the blocklist are always rejected.
+/// Once [`AudioValidator::decode`] is set, files have to be MP3, OGG, WAV, FLAC or MP4 audio
+/// at least the minimum duration long and not silent.
+#[derive(Debug, Clone, Default)]
+pub struct AudioValidator {
+    /// Whether the audio headers are checked.
+    decode: bool,
+    /// The minimum duration of audio whose headers tell its duration.
+    min_duration: Duration,
+    /// The MD5 hashes of rejected files.
+    blocklist: HashSet<String>,
+}
+
+impl AudioValidator {
+    /// Checks the audio headers, rejecting unknown formats, silence and audio shorter than
+    /// `min_duration`.
+    pub fn decode(mut self, min_duration: Duration) -> Self {
+        self.decode = true;
+        self.min_duration = min_duration;
+        self
+    }
+
+    /// Adds MD5 hashes to the blocklist.
+    pub fn with_blocklist<I, S>(mut self, hashes: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.blocklist
+            .extend(hashes.into_iter().map(|hash| hash.as_ref().to_lowercase()));
+        self
+    }
+
+    /// Returns whether a MD5 hash is on the blocklist.
+    pub fn is_blocked(&self, hash: &str) -> bool {
+        self.blocklist.contains(&hash.to_lowercase())
+    }
+
+    /// Checks an audio file.
+    ///
+    /// # Returns
+    /// * What is known about the audio, `None` if the headers were not checked.
+    pub fn check(&self, data: &[u8]) -> Result<Option<AudioInfo>, Rejection> {
+        if data.is_empty() {
+            return Err(Rejection::Empty);
+        }
+        if is_html(data) {
+            return Err(Rejection::HtmlPage);
+        }
+        if self.is_blocked(&md5_hex(data)) {
+            return Err(Rejection::Blocklisted);
+        }
+        if !self.decode {
+            return Ok(None);
+        }
+
+        let info = probe(data).ok_or(Rejection::UnknownFormat)?;
+        if let Some(duration) = info.duration.filter(|d| *d < self.min_duration) {
+            return Err(Rejection::TooShort(duration));
+        }
+        if info.silent {
+            return Err(Rejection::Silent);
+        }
+
+        Ok(Some(info))
+    }
+}
+
+/// Reads a blocklist file: one MD5 hash per line, anything after the hash and lines starting
+/// with `#` are ignored.
+///
+/// # Example
+/// ```text
+/// # JapanesePod101 "audio not available"
+/// 7e2c2f954ef6051373ba916f000168dc
+/// ea21841da70e6405af19fabc4ff8bdd9 Forvo_猫「ねこ」.mp3, silent
+/// ```
+pub fn read_blocklist(path: &Path) -> io::Result<Vec<String>> {
+    Ok(fs::read_to_string(path)?
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.starts_with('#'))
+        .filter_map(|line| line.split_whitespace().next())
+        .map(str::to_owned)
+        .collect())
+}
+
+/// Returns whether a file is an HTML or XML page.
+fn is_html(data: &[u8]) -> bool {
+    let start = &data[..data.len().min(512)];
+    let text = String::from_utf8_lossy(start).to_lowercase();
+    let text = text.trim_start_matches('\u{feff}').trim_start();
+
+    ["<!doctype", "<html", "<?xml", "<head", "<body"]
+        .iter()
+        .any(|tag| text.starts_with(tag))
+        || text.contains("<html")
+}
+
+/// Reads the headers of an audio file.
+///
+/// # Returns
+/// * What is known about the audio, `None` if it is no audio in a known format.
+pub fn probe(data: &[u8]) -> Option<AudioInfo> {
+    if data.starts_with(b"OggS") {
+        probe_ogg(data)
+    } else if data.starts_with(b"RIFF") && data.get(8..12) == Some(b"WAVE") {
+        probe_wav(data)
+    } else if data.starts_with(b"fLaC") {
+        probe_flac(data)
+    } else if data.get(4..8) == Some(b"ftyp") {
+        Some(AudioInfo {
+            format: AudioFormat::Mp4,
+            duration: None,
+            silent: false,
+        })
+    } else {
+        probe_mp3(data)
+    }
+}
+
+/// The bitrates in kbit/s of MPEG audio by version (1 or 2 and 2.5), layer and index.
+const MPEG_BITRATES: [[[u32; 15]; 3]; 2] = [
+    [
+        [
+            0, 32, 64, 96, 128, 160, 192, 224, 256, 288, 320, 352, 384, 416, 448,
+        ],
+        [
+            0, 32, 48, 56, 64, 80, 96, 112, 128, 160, 192, 224, 256, 320, 384,
+        ],
+        [
+            0, 32, 40, 48, 56, 64, 80, 96, 112, 128, 160, 192, 224, 256, 320,
+        ],
+    ],
+    [
+        [
+            0, 32, 48, 56, 64, 80, 96, 112, 128, 144, 160, 176, 192, 224, 256,
+        ],
+        [0, 8, 16, 24, 32, 40, 48, 56, 64, 80, 96, 112, 128, 144, 160],
+        [0, 8, 16, 24, 32, 40, 48, 56, 64, 80, 96, 112, 128, 144, 160],
+    ],
+];
+
+/// A parsed MPEG audio frame header.
+struct MpegFrame {
+    /// The length of the frame in bytes, header included.
+    length: usize,
+    /// The number of samples in the frame.
+    samples: u32,
+    /// The sample rate in Hz.
+    sample_rate: u32,
+    /// Whether the frame carries no Layer III audio data, i.e. is digital silence.
+    silent: bool,
+}
+
+/// Parses the MPEG audio frame at the start of `data`.
+fn mpeg_frame(data: &[u8]) -> Option<MpegFrame> {
+    let header = data.get(..4)?;
+    if header[0] != 0xFF || header[1] & 0xE0 != 0xE0 {
+        return None;
+    }
+
+    // 3 is MPEG 1, 2 is MPEG 2 and 0 is MPEG 2.5
+    let version = (header[1] >> 3) & 0b11;
+    // 3 is Layer I, 2 is Layer II and 1 is Layer III
+    let layer = (header[1] >> 1) & 0b11;
+    let crc = header[1] & 1 == 0;
+    let bitrate_index = (header[2] >> 4) as usize;
+    let sample_rate_index = ((header[2] >> 2) & 0b11) as usize;
+    let padding = ((header[2] >> 1) & 1) as usize;
+    let mono = header[3] >> 6 == 0b11;
+
+    if version == 1 || layer == 0 || bitrate_index == 0 || bitrate_index == 15 {
+        return None;
+    }
+    let sample_rate =
+        [44100, 48000, 32000].get(sample_rate_index)? >> [2, 0, 1, 0][version as usize];
+
+    let mpeg1 = version == 3;
+    let bitrate = MPEG_BITRATES[!mpeg1 as usize][3 - layer as usize][bitrate_index] * 1000;
+    let samples = match layer {
+        3 => 384,
+        1 if !mpeg1 => 576,
+        _ => 1152,
+    };
+    let length = if layer == 3 {
+        (12 * bitrate / sample_rate) as usize * 4 + padding * 4
+    } else {
+        (samples / 8 * bitrate / sample_rate) as usize + padding
+    };
+
+    let silent = layer == 1 && {
+        let side_info = data.get(4 + crc as usize * 2..)?;
+        // The bits before the first granule and the bits of each granule and channel
+        let (offset, stride, granules) = match (mpeg1, mono) {
+            (true, true) => (18, 59, 2),
+            (true, false) => (20, 59, 4),
+            (false, true) => (9, 63, 1),
+            (false, false) => (10, 63, 2),
+        };
+
+        (0..granules).all(|i| read_bits(side_info, offset + i * stride, 12) == Some(0))
+    };
+
+    Some(MpegFrame {
+        length,
+        samples,
+        sample_rate,
+        silent,
+    })
+}
+
+/// Reads `count` big-endian bits starting at the bit `start`.
+fn read_bits(data: &[u8], start: usize, count: usize) -> Option<u32> {
+    (start..start + count).try_fold(0, |value, bit| {
+        let byte = data.get(bit / 8)?;
+        Some(value << 1 | ((byte >> (7 - bit % 8)) & 1) as u32)
+    })
+}
+
+/// Reads the frames of an MP3 file, skipping a leading ID3v2 tag.
+fn probe_mp3(data: &[u8]) -> Option<AudioInfo> {
+    let mut position = 0;
+    if data.starts_with(b"ID3") {
+        let header = data.get(..10)?;
+        let size = header[6..10]
+            .iter()
+            .fold(0, |size, byte| size << 7 | (byte & 0x7F) as usize);
+        let footer = if header[5] & 0x10 != 0 { 10 } else { 0 };
+        position = 10 + size + footer;
+    }
+
+    let mut frames = 0;
+    let mut seconds = 0.0;
+    let mut silent = true;
+    while let Some(frame) = data.get(position..).and_then(mpeg_frame) {
+        frames += 1;
+        seconds += frame.samples as f64 / frame.sample_rate as f64;
+        silent &= frame.silent;
+        position += frame.length;
+    }
+
+    (frames > 0).then(|| AudioInfo {
+        format: AudioFormat::Mp3,
+        duration: Some(Duration::from_secs_f64(seconds)),
+        silent,
+    })
+}
+
+/// Reads the pages of an OGG Vorbis or Opus file for the duration.
+fn probe_ogg(data: &[u8]) -> Option<AudioInfo> {
+    let mut position = 0;
+    let mut sample_rate = None;
+    let mut pre_skip = 0;
+    let mut granule = 0;
+
+    while let Some(page) = data
+        .get(position..)
+        .filter(|page| page.starts_with(b"OggS"))
+    {
+        let segments = *page.get(26)? as usize;
+        let table = page.get(27..27 + segments)?;
+        let body_start = 27 + segments;
+        let body_length: usize = table.iter().map(|&length| length as usize).sum();
+        let body = page.get(body_start..body_start + body_length)?;
+
+        if sample_rate.is_none() {
+            if body.starts_with(b"\x01vorbis") {
+                sample_rate = Some(u32::from_le_bytes(body.get(12..16)?.try_into().ok()?));
+            } else if body.starts_with(b"OpusHead") {
+                // Opus granule positions always count 48 kHz samples
+                sample_rate = Some(48000);
+                pre_skip = u16::from_le_bytes(body.get(10..12)?.try_into().ok()?) as u64;
+            }
+        }
+
+        let position_field = i64::from_le_bytes(page.get(6..14)?.try_into().ok()?);
+        if position_field >= 0 {
+            granule = position_field as u64;
+        }
+        position += body_start + body_length;
+    }
+
+    let sample_rate = sample_rate.filter(|rate| *rate > 0)?;
+
+    Some(AudioInfo {
+        format: AudioFormat::Ogg,
+        duration: Some(Duration::from_secs_f64(
+            granule.saturating_sub(pre_skip) as f64 / sample_rate as f64,
+        )),
+        silent: false,
+    })
+}
+
+/// The largest absolute 16-bit sample of PCM audio counted as silence, about -54 dBFS.
+const SILENCE_THRESHOLD: i16 = 64;
+
+/// Reads the chunks of a WAV file for the duration and checks PCM audio for silence.
+fn probe_wav(data: &[u8]) -> Option<AudioInfo> {
+    let mut position = 12;
+    let mut format = None;
+    let mut samples = None;
+
+    while let Some(header) = data.get(position..position + 8) {
+        let length = u32::from_le_bytes(header[4..8].try_into().ok()?) as usize;
+        let start = position + 8;
+        let chunk = &data[start..(start + length).min(data.len())];
+
+        match &header[..4] {
+            b"fmt " => format = Some(chunk.get(..16)?),
+            b"data" => samples = Some(chunk),
+            _ => {}
+        }
+        position = start + length + length % 2;
+    }
+
+    let format = format?;
+    let samples = samples?;
+    let pcm = u16::from_le_bytes([format[0], format[1]]) == 1;
+    let byte_rate = u32::from_le_bytes(format[8..12].try_into().ok()?);
+    let bits = u16::from_le_bytes([format[14], format[15]]);
+
+    let silent = pcm
+        && match bits {
+            8 => samples.iter().all(|&sample| sample.abs_diff(128) <= 1),
+            16 => samples.chunks_exact(2).all(|sample| {
+                i16::from_le_bytes([sample[0], sample[1]]).unsigned_abs()
+                    <= SILENCE_THRESHOLD as u16
+            }),
+            _ => false,
+        };
+
+    Some(AudioInfo {
+        format: AudioFormat::Wav,
+        duration: (byte_rate > 0)
+            .then(|| Duration::from_secs_f64(samples.len() as f64 / byte_rate as f64)),
+        silent,
+    })
+}
+
+/// Reads the STREAMINFO block of a FLAC file for the duration.
+fn probe_flac(data: &[u8]) -> Option<AudioInfo> {
+    let info = u64::from_be_bytes(data.get(18..26)?.try_into().ok()?);
+    let sample_rate = info >> 44;
+    let total_samples = info & 0xF_FFFF_FFFF;
+
+    Some(AudioInfo {
+        format: AudioFormat::Flac,
+        duration: (sample_rate > 0 && total_samples > 0)
+            .then(|| Duration::from_secs_f64(total_samples as f64 / sample_rate as f64)),
+        silent: false,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Creates an MP3 file of 128 kbit/s 44.1 kHz mono Layer III frames, 26 ms each.
+    fn mp3(frames: usize, silent: bool) -> Vec<u8> {
+        let mut data = b"ID3\x03\x00\x00\x00\x00\x00\x02ab".to_vec();
+        for _ in 0..frames {
+            let mut frame = vec![0; 417];
+            frame[..4].copy_from_slice(&[0xFF, 0xFB, 0x90, 0xC0]);
+            if !silent {
+                frame[6..8].copy_from_slice(&[0xFF, 0xFF]);
+            }
+            data.extend(frame);
+        }
+        data
+    }
+
+    /// Creates a 16-bit 8 kHz mono PCM WAV file.
+    fn wav(samples: &[i16]) -> Vec<u8> {
+        let mut data = b"RIFF\0\0\0\0WAVEfmt \x10\0\0\0\x01\0\x01\0".to_vec();
+        data.extend(8000u32.to_le_bytes());
+        data.extend(16000u32.to_le_bytes());
+        data.extend([2, 0, 16, 0]);
+        data.extend(b"data");
+        data.extend((samples.len() as u32 * 2).to_le_bytes());
+        data.extend(samples.iter().flat_map(|sample| sample.to_le_bytes()));
+        data
+    }
+
+    /// Creates an OGG page.
+    fn ogg_page(granule: i64, body: &[u8]) -> Vec<u8> {
+        let mut page = b"OggS\0\0".to_vec();
+        page.extend(granule.to_le_bytes());
+        page.extend([0; 12]);
+        page.push(1);
+        page.push(body.len() as u8);
+        page.extend(body);
+        page
+    }
+
+    /// Tests reading the duration and silence of audio files.
+    #[test]
+    fn probe_formats() {
+        let info = probe(&mp3(20, false)).unwrap();
+        assert_eq!(info.format, AudioFormat::Mp3);
+        assert_eq!(info.duration.unwrap().as_millis(), 522);
+        assert!(!info.silent);
+        assert!(probe(&mp3(20, true)).unwrap().silent);
+
+        let info = probe(&wav(&[1000, -1000].repeat(4000))).unwrap();
+        assert_eq!(info.format, AudioFormat::Wav);
+        assert_eq!(info.duration, Some(Duration::from_secs(1)));
+        assert!(!info.silent);
+        assert!(probe(&wav(&[3, -3].repeat(4000))).unwrap().silent);
+
+        let mut vorbis = b"\x01vorbis\0\0\0\0\x01".to_vec();
+        vorbis.extend(22050u32.to_le_bytes());
+        let mut ogg = ogg_page(0, &vorbis);
+        ogg.extend(ogg_page(-1, b"comment"));
+        ogg.extend(ogg_page(11025, b"audio"));
+        let info = probe(&ogg).unwrap();
+        assert_eq!(info.format, AudioFormat::Ogg);
+        assert_eq!(info.duration, Some(Duration::from_millis(500)));
+
+        assert_eq!(probe(b"nihon"), None);
+    }
+
+    /// Tests rejecting empty files, HTML pages, blocklisted, short and silent audio.
+    #[test]
+    fn validate() {
+        let validator =
+            AudioValidator::default().with_blocklist(["EA21841DA70E6405AF19FABC4FF8BDD9"]);
+
+        assert_eq!(validator.check(b"audio"), Ok(None));
+        assert_eq!(validator.check(b""), Err(Rejection::Empty));
+        assert_eq!(validator.check(b"missing"), Err(Rejection::Blocklisted));
+        assert_eq!(
+            validator.check(b"\n <!DOCTYPE html><title>404</title>"),
+            Err(Rejection::HtmlPage)
+        );
+
+        let validator = validator.decode(Duration::from_millis(300));
+        assert_eq!(validator.check(b"audio"), Err(Rejection::UnknownFormat));
+        assert!(validator.check(&mp3(20, false)).unwrap().is_some());
+        assert!(matches!(
+            validator.check(&mp3(5, false)),
+            Err(Rejection::TooShort(duration)) if duration.as_millis() == 130
+        ));
+        assert_eq!(validator.check(&mp3(20, true)), Err(Rejection::Silent));
+    }
+
+    /// Tests reading a blocklist file.
+    #[test]
+    fn blocklist() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("blocklist.txt");
+        fs::write(
+            &path,
+            "# Placeholders\n7e2c2f954ef6051373ba916f000168dc\n\n  ea21841da70e6405af19fabc4ff8bdd9 silent\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            read_blocklist(&path).unwrap(),
+            vec![
+                "7e2c2f954ef6051373ba916f000168dc",
+                "ea21841da70e6405af19fabc4ff8bdd9"
+            ]
+        );
+    }
+}
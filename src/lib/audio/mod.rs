@@ -0,0 +1,6 @@
+pub mod audio_forvo;
+pub mod audio_jpod101;
+pub mod audio_library;
+pub mod audio_local;
+pub mod audio_source;
+pub mod audio_validate;
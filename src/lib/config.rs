@@ -0,0 +1,427 @@
+use std::{
+    env, fmt, fs,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::anki::anki_connect::{AnkiConnect, Response};
+
+/// The environment variable that can point to a config file.
+pub const CONFIG_ENV: &str = "ANKI_DECK_CONFIG";
+
+/// The config files looked for in the working directory, in order.
+pub const DEFAULT_CONFIG_PATHS: [&str; 2] = ["./config.toml", "./config.json"];
+
+/// Represents a failure to load a config file.
+#[derive(Debug)]
+pub enum ConfigError {
+    /// The file could not be read.
+    Io(PathBuf, std::io::Error),
+    /// The file is not valid TOML.
+    Toml(PathBuf, toml::de::Error),
+    /// The file is not valid JSON.
+    Json(PathBuf, serde_json::Error),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ConfigError::Io(path, err) => write!(f, "Failed to read {}: {err}", path.display()),
+            ConfigError::Toml(path, err) => write!(f, "Invalid config {}: {err}", path.display()),
+            ConfigError::Json(path, err) => write!(f, "Invalid config {}: {err}", path.display()),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ConfigError::Io(_, err) => Some(err),
+            ConfigError::Toml(_, err) => Some(err),
+            ConfigError::Json(_, err) => Some(err),
+        }
+    }
+}
+
+/// The settings shared by every binary. Missing sections and keys fall back to their defaults.
+///
+/// # Example
+/// ```toml
+/// [anki]
+/// url = "http://127.0.0.1:8765"
+///
+/// [deck]
+/// name = "Japanese"
+/// word_model = "Japanese Word"
+///
+/// [fields]
+/// word = "Expression"
+/// meaning = "Meaning"
+/// ```
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+#[serde(default)]
+pub struct Config {
+    /// The AnkiConnect connection.
+    pub anki: AnkiConfig,
+    /// The deck and note type names.
+    pub deck: DeckConfig,
+    /// The field names of the word note type.
+    pub fields: WordFields,
+    /// The field names of the kanji note type.
+    pub kanji_fields: KanjiFields,
+    /// The audio sources.
+    pub audio: AudioConfig,
+    /// The Tatoeba example searches.
+    pub tatoeba: TatoebaConfig,
+    /// The input and output paths.
+    pub paths: PathConfig,
+}
+
+impl Config {
+    /// Loads a config file, using JSON for `.json` files and TOML otherwise.
+    pub fn load(path: &Path) -> Result<Self, ConfigError> {
+        let data = fs::read_to_string(path).map_err(|err| ConfigError::Io(path.to_owned(), err))?;
+
+        if path.extension().is_some_and(|ext| ext == "json") {
+            serde_json::from_str(&data).map_err(|err| ConfigError::Json(path.to_owned(), err))
+        } else {
+            toml::from_str(&data).map_err(|err| ConfigError::Toml(path.to_owned(), err))
+        }
+    }
+
+    /// Loads the config file named by [`CONFIG_ENV`], or else the first existing file of
+    /// [`DEFAULT_CONFIG_PATHS`]. Returns the default config if there is none.
+    pub fn load_default() -> Result<Self, ConfigError> {
+        if let Some(path) = env::var_os(CONFIG_ENV) {
+            return Self::load(Path::new(&path));
+        }
+
+        match DEFAULT_CONFIG_PATHS
+            .iter()
+            .map(Path::new)
+            .find(|path| path.exists())
+        {
+            Some(path) => Self::load(path),
+            None => Ok(Self::default()),
+        }
+    }
+
+    /// Connects to AnkiConnect using the configured url and api key.
+    pub fn connect(&self) -> Response<AnkiConnect> {
+        AnkiConnect::new(self.anki.url.clone(), self.anki.api_key.clone())
+    }
+
+    /// Returns the search query matching every note in the deck.
+    pub fn deck_query(&self) -> String {
+        format!("\"deck:{}\"", self.deck.name)
+    }
+
+    /// Returns the search query matching every word note in the deck.
+    pub fn word_query(&self) -> String {
+        format!(
+            "\"deck:{}\" \"note:{}\"",
+            self.deck.name, self.deck.word_model
+        )
+    }
+
+    /// Returns the search query matching every kanji note in the deck.
+    pub fn kanji_query(&self) -> String {
+        format!(
+            "\"deck:{}\" \"note:{}\"",
+            self.deck.name, self.deck.kanji_model
+        )
+    }
+}
+
+/// The AnkiConnect connection.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(default)]
+pub struct AnkiConfig {
+    /// The url AnkiConnect listens on.
+    pub url: String,
+    /// The api key, if AnkiConnect requires one.
+    pub api_key: Option<String>,
+}
+
+impl Default for AnkiConfig {
+    fn default() -> Self {
+        Self {
+            url: "http://127.0.0.1:8765".to_owned(),
+            api_key: None,
+        }
+    }
+}
+
+/// The deck and note type names.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(default)]
+pub struct DeckConfig {
+    /// The name of the deck.
+    pub name: String,
+    /// The name of the note type used for words.
+    pub word_model: String,
+    /// The name of the note type used for kanji.
+    pub kanji_model: String,
+}
+
+impl Default for DeckConfig {
+    fn default() -> Self {
+        Self {
+            name: "My Deck 4.0".to_owned(),
+            word_model: "JP Card V4".to_owned(),
+            kanji_model: "JP Kanji V4".to_owned(),
+        }
+    }
+}
+
+/// The Anki field names of the word note type.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(default)]
+pub struct WordFields {
+    /// The word in furigana format.
+    pub word: String,
+    /// The meaning.
+    pub meaning: String,
+    /// The pronunciation audio.
+    pub audio: String,
+    /// The example sentences.
+    pub sentences: String,
+    /// The reading in romaji, if the note type has such a field.
+    pub romaji: Option<String>,
+    /// The pitch accent graphs, if the note type has such a field.
+    pub pitch: Option<String>,
+    /// The stroke order diagrams of the kanji of the word, if the note type has such a field.
+    pub stroke_order: Option<String>,
+    /// The conjugation table of the word, if the note type has such a field.
+    pub conjugations: Option<String>,
+}
+
+impl Default for WordFields {
+    fn default() -> Self {
+        Self {
+            word: "1 Word".to_owned(),
+            meaning: "2 Meaning".to_owned(),
+            audio: "3 Audio".to_owned(),
+            sentences: "4 Sentences".to_owned(),
+            romaji: None,
+            pitch: None,
+            stroke_order: None,
+            conjugations: None,
+        }
+    }
+}
+
+/// The Anki field names of the kanji note type.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(default)]
+pub struct KanjiFields {
+    /// The kanji character.
+    pub kanji: String,
+    /// The meaning.
+    pub meaning: String,
+    /// The onyomi readings.
+    pub onyomi: String,
+    /// The kunyomi readings.
+    pub kunyomi: String,
+    /// The stroke count.
+    pub strokes: String,
+    /// Words of the word deck using the kanji, if the note type has such a field.
+    pub examples: Option<String>,
+    /// The breakdown of the kanji into its components, if the note type has such a field.
+    pub components: Option<String>,
+    /// The numbered stroke order strip, if the note type has such a field.
+    pub stroke_order: Option<String>,
+}
+
+impl Default for KanjiFields {
+    fn default() -> Self {
+        Self {
+            kanji: "1 Kanji".to_owned(),
+            meaning: "2 Meaning".to_owned(),
+            onyomi: "3 Onyomi".to_owned(),
+            kunyomi: "4 Kunyomi".to_owned(),
+            strokes: "5 Strokes".to_owned(),
+            examples: None,
+            components: None,
+            stroke_order: None,
+        }
+    }
+}
+
+/// An audio source, see [`audio`](crate::audio).
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum AudioSourceKind {
+    /// A local audio library at `paths.audio_library`, e.g. a Yomitan local audio dump.
+    Library,
+    /// Recordings in the directory `paths.audio`.
+    Local,
+    /// The Forvo-style JSON index `paths.forvo_index`.
+    Forvo,
+    /// The JapanesePod101 dictionary audio.
+    JapanesePod101,
+}
+
+/// The audio sources.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(default)]
+pub struct AudioConfig {
+    /// The sources tried in order, the first valid audio found is used. Local sources whose
+    /// files are missing are skipped.
+    pub sources: Vec<AudioSourceKind>,
+    /// The sources of the audio library in order of preference, e.g. `nhk16`. Unlisted sources
+    /// come last.
+    pub library_sources: Vec<String>,
+    /// The speakers of the audio library in order of preference. Unlisted speakers come last.
+    pub library_speakers: Vec<String>,
+    /// The minimum length in milliseconds of valid audio.
+    pub min_duration_ms: u64,
+    /// The tag of notes whose audio was rejected and none other found, for review.
+    pub rejected_tag: String,
+}
+
+impl Default for AudioConfig {
+    fn default() -> Self {
+        Self {
+            sources: vec![
+                AudioSourceKind::Library,
+                AudioSourceKind::Local,
+                AudioSourceKind::Forvo,
+                AudioSourceKind::JapanesePod101,
+            ],
+            library_sources: ["nhk16", "shinmeikai8", "jpod", "jpod_alternate", "forvo"]
+                .map(str::to_owned)
+                .to_vec(),
+            library_speakers: Vec::new(),
+            min_duration_ms: 250,
+            rejected_tag: "audio-rejected".to_owned(),
+        }
+    }
+}
+
+/// The Tatoeba example searches.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(default)]
+pub struct TatoebaConfig {
+    /// How many days saved responses of the Tatoeba API are used, `0` to keep them forever.
+    pub cache_days: u64,
+}
+
+impl TatoebaConfig {
+    /// Returns how long saved responses are used, `None` to keep them forever.
+    pub fn cache_ttl(&self) -> Option<Duration> {
+        (self.cache_days > 0).then(|| Duration::from_secs(self.cache_days * 24 * 60 * 60))
+    }
+}
+
+impl Default for TatoebaConfig {
+    fn default() -> Self {
+        Self { cache_days: 30 }
+    }
+}
+
+/// The input and output paths.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(default)]
+pub struct PathConfig {
+    /// The directory of the Yomitan dictionaries and EDRDG XML files the words are taken from.
+    pub dictionaries: PathBuf,
+    /// The directory of the Yomitan dictionaries the examples are taken from.
+    pub examples: PathBuf,
+    /// The directory of the extracted Tatoeba exports the examples are searched in offline.
+    /// May be missing, the Tatoeba API is used then.
+    pub tatoeba_corpus: PathBuf,
+    /// The directory of the KRADFILE, RADKFILE and IDS files the kanji components are taken
+    /// from. May be missing.
+    pub components: PathBuf,
+    /// The KanjiVG release archive or directory the stroke order diagrams are taken from.
+    /// May be missing.
+    pub kanjivg: PathBuf,
+    /// The local audio library, a directory with an `entries.db` or `*_files` source folders.
+    /// May be missing.
+    pub audio_library: PathBuf,
+    /// The directory of local recordings named after their word, e.g. `猫_ねこ.mp3`.
+    /// May be missing.
+    pub audio: PathBuf,
+    /// The Forvo-style JSON index of recordings. May be missing.
+    pub forvo_index: PathBuf,
+    /// The MD5 hashes of rejected audio files, one per line. May be missing.
+    pub audio_blocklist: PathBuf,
+    /// The word list written by `dictionary` and read by the other binaries.
+    pub wordlist: PathBuf,
+    /// The kanji list written by `dictionary` and read by the other binaries.
+    pub kanjilist: PathBuf,
+    /// The tag definitions written by `dictionary`.
+    pub tags: PathBuf,
+    /// The compiled dictionary index `dictionary` reuses while the dictionaries are unchanged.
+    pub index: PathBuf,
+    /// The directory of the saved responses of the Tatoeba API.
+    pub tatoeba_cache: PathBuf,
+    /// The `.apkg` package written by `export`.
+    pub package: PathBuf,
+}
+
+impl Default for PathConfig {
+    fn default() -> Self {
+        Self {
+            dictionaries: "./input/dictionaries".into(),
+            examples: "./input/examples".into(),
+            tatoeba_corpus: "./input/tatoeba".into(),
+            components: "./input/components".into(),
+            kanjivg: "./input/kanjivg".into(),
+            audio_library: "./input/audio_library".into(),
+            audio: "./input/audio".into(),
+            forvo_index: "./input/forvo.json".into(),
+            audio_blocklist: "./input/audio_blocklist.txt".into(),
+            wordlist: "./result/wordlist.json".into(),
+            kanjilist: "./result/kanjilist.json".into(),
+            tags: "./result/tags.json".into(),
+            index: "./result/dictionary.idx".into(),
+            tatoeba_cache: "./result/tatoeba_cache".into(),
+            package: "./result/deck.apkg".into(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tests that partial TOML and JSON configs keep the defaults of missing keys.
+    #[test]
+    fn parse_partial_config() {
+        let config: Config = toml::from_str(
+            r#"
+            [deck]
+            name = "Japanese"
+
+            [fields]
+            word = "Expression"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(config.deck.name, "Japanese");
+        assert_eq!(config.deck.word_model, "JP Card V4");
+        assert_eq!(config.fields.word, "Expression");
+        assert_eq!(config.fields.meaning, "2 Meaning");
+        assert_eq!(config.anki, AnkiConfig::default());
+        assert_eq!(config.word_query(), "\"deck:Japanese\" \"note:JP Card V4\"");
+
+        let config: Config = serde_json::from_str(r#"{"anki": {"api_key": "secret"}}"#).unwrap();
+
+        assert_eq!(config.anki.api_key.as_deref(), Some("secret"));
+        assert_eq!(config.anki.url, "http://127.0.0.1:8765");
+        assert_eq!(config.paths, PathConfig::default());
+    }
+
+    /// Tests that the example config documents the defaults.
+    #[test]
+    fn example_config_is_default() {
+        let config: Config = toml::from_str(include_str!("../../config.example.toml")).unwrap();
+
+        assert_eq!(config, Config::default());
+    }
+}
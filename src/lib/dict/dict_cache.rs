@@ -0,0 +1,333 @@
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    fs::{self, File},
+    io::{self, BufReader, BufWriter, Read, Write},
+    path::Path,
+};
+
+use serde::{Deserialize, Serialize};
+use sha1::{Digest, Sha1};
+
+use crate::{
+    entry::{Example, Kanji, Word},
+    japanese::JapaneseStr,
+};
+
+use super::dict_index::TagBank;
+
+/// The bytes every index file starts with.
+const MAGIC: &[u8; 8] = b"ANKIDICT";
+
+/// The version of the index format, increased whenever the layout of the index or of the types
+/// it holds changes. Files of other versions are rebuilt.
+pub const INDEX_VERSION: u32 = 5;
+
+/// The SHA-1 hash of every input archive by its path.
+pub type InputHashes = BTreeMap<String, String>;
+
+/// The converted dictionaries saved as a binary file, so repeated runs skip unzipping and
+/// parsing the archives while the archives are unchanged.
+///
+/// Besides the word and kanji maps the index holds lookup tables of the words by their kanji
+/// form, kana reading and word id. The tables are not saved but built when the index is created
+/// or read.
+///
+/// # Example
+/// ```no_run
+/// use std::path::Path;
+///
+/// use anki_utill::dict::dict_cache::{hash_inputs, DictionaryCache};
+///
+/// let inputs = hash_inputs(&[Path::new("input/dictionaries")]).unwrap();
+/// if let Some(index) = DictionaryCache::load(Path::new("result/dictionary.idx"), &inputs) {
+///     for word in index.by_kana("ねこ") {
+///         println!("{}", word.furigana);
+///     }
+/// }
+/// ```
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct DictionaryCache {
+    /// The hashes of the archives the index was built from.
+    pub inputs: InputHashes,
+    /// The words by their furigana.
+    pub words: HashMap<String, Word>,
+    /// The kanji by their character.
+    pub kanji: HashMap<char, Kanji>,
+    /// The tag definitions of the dictionaries.
+    pub tags: TagBank,
+    /// The example sentences of the example dictionaries by the furigana of their word.
+    pub examples: HashMap<String, HashSet<Example>>,
+    /// The furigana of the words by their kanji form.
+    #[serde(skip)]
+    by_kanji: HashMap<String, Vec<String>>,
+    /// The furigana of the words by their kana reading.
+    #[serde(skip)]
+    by_kana: HashMap<String, Vec<String>>,
+    /// The furigana of the words by their word id.
+    #[serde(skip)]
+    by_id: HashMap<i32, Vec<String>>,
+}
+
+impl DictionaryCache {
+    /// Creates an index and builds its lookup tables.
+    pub fn new(
+        inputs: InputHashes,
+        words: HashMap<String, Word>,
+        kanji: HashMap<char, Kanji>,
+        tags: TagBank,
+        examples: HashMap<String, HashSet<Example>>,
+    ) -> Self {
+        let mut index = Self {
+            inputs,
+            words,
+            kanji,
+            tags,
+            examples,
+            by_kanji: HashMap::new(),
+            by_kana: HashMap::new(),
+            by_id: HashMap::new(),
+        };
+        index.build_lookups();
+        index
+    }
+
+    /// Builds the lookup tables from the words.
+    fn build_lookups(&mut self) {
+        self.by_kanji.clear();
+        self.by_kana.clear();
+        self.by_id.clear();
+
+        for (furigana, word) in self.words.iter() {
+            self.by_kanji
+                .entry(furigana.to_kanji())
+                .or_default()
+                .push(furigana.clone());
+            self.by_kana
+                .entry(furigana.to_kana())
+                .or_default()
+                .push(furigana.clone());
+            self.by_id
+                .entry(word.word_id)
+                .or_default()
+                .push(furigana.clone());
+        }
+
+        // Keep lookups in a stable order
+        for table in [&mut self.by_kanji, &mut self.by_kana] {
+            table.values_mut().for_each(|list| list.sort_unstable());
+        }
+        self.by_id
+            .values_mut()
+            .for_each(|list| list.sort_unstable());
+    }
+
+    /// Returns the words written with the given kanji form, e.g. `猫`.
+    pub fn by_kanji(&self, kanji: &str) -> Vec<&Word> {
+        self.lookup(self.by_kanji.get(kanji))
+    }
+
+    /// Returns the words read as the given kana, e.g. `ねこ`.
+    pub fn by_kana(&self, kana: &str) -> Vec<&Word> {
+        self.lookup(self.by_kana.get(kana))
+    }
+
+    /// Returns the words with the given word id.
+    pub fn by_id(&self, word_id: i32) -> Vec<&Word> {
+        self.lookup(self.by_id.get(&word_id))
+    }
+
+    /// Returns the word with the given furigana. Falls back to the word written and read the same
+    /// way when the furigana splits the word differently, e.g. `日本語[にほんご]` finds
+    /// `日本[にほん] 語[ご]`.
+    pub fn find(&self, furigana: &str) -> Option<&Word> {
+        if let Some(word) = self.words.get(furigana) {
+            return Some(word);
+        }
+
+        let kana = furigana.to_kana();
+        self.by_kanji(&furigana.to_kanji())
+            .into_iter()
+            .find(|word| word.furigana.to_kana() == kana)
+    }
+
+    /// Returns the words of a lookup table entry.
+    fn lookup(&self, furigana: Option<&Vec<String>>) -> Vec<&Word> {
+        furigana
+            .into_iter()
+            .flatten()
+            .filter_map(|furigana| self.words.get(furigana))
+            .collect()
+    }
+
+    /// Writes the index to a file, creating its directory if needed.
+    pub fn write(&self, path: &Path) -> io::Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+
+        let mut file = BufWriter::new(File::create(path)?);
+        file.write_all(MAGIC)?;
+        file.write_all(&INDEX_VERSION.to_le_bytes())?;
+        bincode::serialize_into(&mut file, self).map_err(invalid_data)?;
+
+        file.flush()
+    }
+
+    /// Reads an index from a file.
+    ///
+    /// # Returns
+    /// * The index, or an error if the file can not be read, is not an index or is of another
+    ///   version.
+    pub fn read(path: &Path) -> io::Result<Self> {
+        let mut file = BufReader::new(File::open(path)?);
+
+        let mut magic = [0; 8];
+        let mut version = [0; 4];
+        file.read_exact(&mut magic)?;
+        file.read_exact(&mut version)?;
+
+        if &magic != MAGIC {
+            return Err(invalid_data("not a dictionary index"));
+        }
+
+        let version = u32::from_le_bytes(version);
+        if version != INDEX_VERSION {
+            return Err(invalid_data(format!(
+                "index version {} is not {}",
+                version, INDEX_VERSION
+            )));
+        }
+
+        let mut index: Self = bincode::deserialize_from(file).map_err(invalid_data)?;
+        index.build_lookups();
+        Ok(index)
+    }
+
+    /// Reads an index from a file if it was built from the given inputs.
+    ///
+    /// # Returns
+    /// * The index, or `None` if it is missing, unreadable or outdated and must be rebuilt.
+    pub fn load(path: &Path, inputs: &InputHashes) -> Option<Self> {
+        Self::read(path)
+            .ok()
+            .filter(|index| &index.inputs == inputs)
+    }
+}
+
+/// Converts an error into an I/O error of invalid data.
+fn invalid_data<E>(err: E) -> io::Error
+where
+    E: Into<Box<dyn std::error::Error + Send + Sync>>,
+{
+    io::Error::new(io::ErrorKind::InvalidData, err)
+}
+
+/// Hashes every file of the given directories, which identifies the inputs of an index.
+pub fn hash_inputs(dirs: &[&Path]) -> io::Result<InputHashes> {
+    let mut out = InputHashes::new();
+
+    for dir in dirs.iter().filter(|dir| dir.is_dir()) {
+        for path in fs::read_dir(dir)? {
+            let path = path?.path();
+            if !path.is_file() {
+                continue;
+            }
+
+            let mut hasher = Sha1::new();
+            io::copy(&mut File::open(&path)?, &mut hasher)?;
+
+            out.insert(
+                path.display().to_string(),
+                format!("{:x}", hasher.finalize()),
+            );
+        }
+    }
+
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Creates an index with a few words.
+    fn index(inputs: InputHashes) -> DictionaryCache {
+        let words: HashMap<String, Word> = [
+            (1, "猫[ねこ]"),
+            (2, "根[ね] 子[こ]"),
+            (3, "犬[いぬ]"),
+            (4, "日本[にほん] 語[ご]"),
+        ]
+        .into_iter()
+        .map(|(id, furigana)| {
+            let word = Word::new(
+                id,
+                furigana.to_owned(),
+                Vec::new(),
+                HashSet::new(),
+                HashSet::new(),
+            );
+            (furigana.to_owned(), word)
+        })
+        .collect();
+
+        DictionaryCache::new(
+            inputs,
+            words,
+            HashMap::new(),
+            TagBank::default(),
+            HashMap::new(),
+        )
+    }
+
+    /// Tests the lookup tables.
+    #[test]
+    fn lookup() {
+        let index = index(InputHashes::new());
+
+        let kana: Vec<&str> = index
+            .by_kana("ねこ")
+            .iter()
+            .map(|word| word.furigana.as_str())
+            .collect();
+        assert_eq!(kana, vec!["根[ね] 子[こ]", "猫[ねこ]"]);
+
+        assert_eq!(index.by_kanji("犬")[0].word_id, 3);
+        assert_eq!(index.by_id(1)[0].furigana, "猫[ねこ]");
+        assert!(index.by_kana("とり").is_empty());
+
+        assert_eq!(index.find("猫[ねこ]").unwrap().word_id, 1);
+        assert_eq!(index.find("日本語[にほんご]").unwrap().word_id, 4);
+        assert!(index.find("日本語[にっぽんご]").is_none());
+    }
+
+    /// Tests writing and reading an index and rebuilding it when the inputs change.
+    #[test]
+    fn write_and_load() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        let input = dir.join("input");
+        fs::create_dir_all(&input).unwrap();
+        fs::write(input.join("jmdict.zip"), "first").unwrap();
+
+        let path = dir.join("dictionary.idx");
+        let inputs = hash_inputs(&[&input]).unwrap();
+        let index = index(inputs.clone());
+        index.write(&path).unwrap();
+
+        let loaded = DictionaryCache::load(&path, &inputs).unwrap();
+        assert_eq!(loaded.by_kanji("犬")[0].word_id, 3);
+        assert_eq!(loaded, index);
+
+        fs::write(input.join("jmdict.zip"), "second").unwrap();
+        let changed = hash_inputs(&[&input]).unwrap();
+        assert_ne!(changed, inputs);
+        assert_eq!(DictionaryCache::load(&path, &changed), None);
+
+        // Files of another version are not read
+        let mut data = fs::read(&path).unwrap();
+        data[MAGIC.len()] += 1;
+        fs::write(&path, data).unwrap();
+        assert!(DictionaryCache::read(&path).is_err());
+    }
+}
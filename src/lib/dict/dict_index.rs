@@ -0,0 +1,197 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::entry::Source;
+
+/// The metadata of a Yomitan dictionary, read from its `index.json`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct DictionaryInfo {
+    /// The title of the dictionary.
+    pub title: String,
+    /// The revision of the dictionary.
+    pub revision: String,
+    /// The format of the dictionary files. Older dictionaries call it `version`.
+    #[serde(alias = "version", default)]
+    pub format: u8,
+    /// The author of the dictionary, if given.
+    #[serde(default)]
+    pub author: Option<String>,
+    /// The homepage of the dictionary, if given.
+    #[serde(default)]
+    pub url: Option<String>,
+    /// A description of the dictionary, if given.
+    #[serde(default)]
+    pub description: Option<String>,
+}
+
+impl DictionaryInfo {
+    /// Returns the title and revision identifying data taken from this dictionary.
+    pub fn source(&self) -> Source {
+        Source::new(self.title.clone(), self.revision.clone())
+    }
+}
+
+/// A tag definition from a Yomitan `tag_bank` file.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Tag {
+    /// The short name used on entries, e.g. `n`.
+    pub name: String,
+    /// The category of the tag, e.g. `partOfSpeech`.
+    pub category: String,
+    /// The sorting order of the tag, lower first.
+    pub order: i32,
+    /// The full description of the tag, e.g. `noun (common)`.
+    pub notes: String,
+    /// The popularity score of the tag.
+    pub score: i32,
+}
+
+impl Tag {
+    /// Creates a new Tag.
+    pub fn new(name: String, category: String, order: i32, notes: String, score: i32) -> Self {
+        Self {
+            name,
+            category,
+            order,
+            notes,
+            score,
+        }
+    }
+}
+
+/// The row of a Yomitan `tag_bank` file: name, category, order, notes and score.
+pub type TagBankEntry = (String, String, i32, String, i32);
+
+/// The tag definitions of one or more dictionaries by their name.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+#[serde(transparent)]
+pub struct TagBank(HashMap<String, Tag>);
+
+impl TagBank {
+    /// Adds a tag, replacing an earlier definition with the same name.
+    pub fn insert(&mut self, tag: Tag) {
+        self.0.insert(tag.name.clone(), tag);
+    }
+
+    /// Adds every tag of another bank.
+    pub fn extend(&mut self, other: TagBank) {
+        self.0.extend(other.0);
+    }
+
+    /// Returns the definition of a tag.
+    pub fn get(&self, name: &str) -> Option<&Tag> {
+        self.0.get(name)
+    }
+
+    /// Returns the number of tags.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns whether the bank has no tags.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Renames every tag, e.g. to the names tags get on words. Tags renamed to `None` are dropped.
+    pub fn renamed(self, rename: impl Fn(&str) -> Option<String>) -> Self {
+        self.0
+            .into_values()
+            .filter_map(|mut tag| {
+                tag.name = rename(&tag.name)?;
+                Some(tag)
+            })
+            .collect()
+    }
+
+    /// Returns the definitions of the given tags sorted by category, order and name. Unknown tags
+    /// are skipped.
+    pub fn resolve<'a>(&self, tags: impl IntoIterator<Item = &'a str>) -> Vec<&Tag> {
+        let mut out: Vec<&Tag> = tags.into_iter().filter_map(|tag| self.get(tag)).collect();
+
+        out.sort_unstable_by(|a, b| {
+            (&a.category, a.order, &a.name).cmp(&(&b.category, b.order, &b.name))
+        });
+        out.dedup_by(|a, b| a.name == b.name);
+
+        out
+    }
+}
+
+impl FromIterator<Tag> for TagBank {
+    fn from_iter<I: IntoIterator<Item = Tag>>(iter: I) -> Self {
+        let mut bank = TagBank::default();
+
+        for tag in iter {
+            bank.insert(tag);
+        }
+
+        bank
+    }
+}
+
+impl FromIterator<TagBankEntry> for TagBank {
+    fn from_iter<I: IntoIterator<Item = TagBankEntry>>(iter: I) -> Self {
+        iter.into_iter()
+            .map(|(name, category, order, notes, score)| {
+                Tag::new(name, category, order, notes, score)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tests parsing `index.json` in both the current and the legacy format.
+    #[test]
+    fn parse_index() {
+        let info: DictionaryInfo = serde_json::from_str(
+            r#"{"title": "JMdict", "revision": "2025-01-01", "format": 3, "author": "EDRDG"}"#,
+        )
+        .unwrap();
+
+        assert_eq!(info.format, 3);
+        assert_eq!(info.author.as_deref(), Some("EDRDG"));
+        assert_eq!(
+            info.source(),
+            Source::new("JMdict".into(), "2025-01-01".into())
+        );
+
+        let info: DictionaryInfo =
+            serde_json::from_str(r#"{"title": "Old", "revision": "1", "version": 1}"#).unwrap();
+
+        assert_eq!(info.format, 1);
+        assert_eq!(info.url, None);
+    }
+
+    /// Tests parsing a tag bank and resolving tags in order.
+    #[test]
+    fn resolve_tags() {
+        let entries: Vec<TagBankEntry> = serde_json::from_str(
+            r#"[
+                ["v5k", "partOfSpeech", 0, "Godan verb with 'ku' ending", 0],
+                ["n", "partOfSpeech", 0, "noun (common) (futsuumeishi)", 0],
+                ["uk", "misc", 0, "word usually written using kana alone", 0]
+            ]"#,
+        )
+        .unwrap();
+        let bank: TagBank = entries.into_iter().collect();
+
+        assert_eq!(bank.len(), 3);
+        assert_eq!(bank.get("n").unwrap().notes, "noun (common) (futsuumeishi)");
+
+        let names: Vec<&str> = bank
+            .resolve(["n", "uk", "JLPT-N5", "n"])
+            .iter()
+            .map(|tag| tag.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["uk", "n"]);
+
+        let bank = bank.renamed(|name| (name != "uk").then(|| name.replace("k", "く")));
+        assert_eq!(bank.len(), 2);
+        assert_eq!(bank.get("v5く").unwrap().category, "partOfSpeech");
+    }
+}
@@ -1,14 +1,23 @@
 use std::{
     collections::{HashMap, HashSet},
     fs::{self, File},
-    io::{self, Read},
+    io::{self, Read, Seek},
+    marker::PhantomData,
     path::Path,
 };
 
 use serde::de::DeserializeOwned;
 use zip::ZipArchive;
 
-use crate::entry::{Kanji, Word};
+use crate::{
+    entry::{Kanji, KanjiForm, Source, Word},
+    japanese::JapaneseStr,
+};
+
+use super::{
+    dict_index::{DictionaryInfo, TagBank, TagBankEntry},
+    progress::Progress,
+};
 
 /// Parses a JSON string into a vector of objects of type T.
 ///
@@ -34,15 +43,258 @@ where
     serde_json::from_str(data).ok()
 }
 
-/// Parses a ZIP file into a vector of objects of type T.
+/// An opened Yomitan dictionary ZIP file whose banks are read one at a time.
 ///
-/// This function reads a ZIP archive from the specified path, iterates over its entries,
-/// and parses each relevant entry into a collection of objects. The function skips entries
-/// that do not start with "term_", "kanji_", or have an unknown filename.
+/// Opening the file reads its `index.json` and `tag_bank` files, which are small. The `term_` and
+/// `kanji_` banks are only read while iterating [`DictionaryReader::banks`], so no more than one
+/// bank is held in memory at a time.
 ///
-/// The parsing process involves reading each entry's content as a JSON string and converting it
-/// into Rust objects using the `serde_json` library. These objects are then collected into a vector
-/// which is returned upon completion.
+/// # Example
+/// ```no_run
+/// use std::path::Path;
+///
+/// use anki_utill::dict::{dict_parser::DictionaryReader, jmnedict::jmnedict_entry::JmnedictEntry};
+///
+/// let mut reader = DictionaryReader::open(Path::new("jmdict.zip")).unwrap();
+/// for bank in reader.banks::<JmnedictEntry>() {
+///     println!("{} entries", bank.unwrap().len());
+/// }
+/// ```
+pub struct DictionaryReader<R> {
+    /// The ZIP archive of the dictionary.
+    archive: ZipArchive<R>,
+    /// The name of the file, used when the dictionary has no title.
+    path: String,
+    /// The indices of the `term_` and `kanji_` banks in the archive.
+    banks: Vec<usize>,
+    /// The metadata from `index.json`, if the file has one.
+    pub info: Option<DictionaryInfo>,
+    /// The tag definitions from the `tag_bank` files.
+    pub tags: TagBank,
+}
+
+impl DictionaryReader<File> {
+    /// Opens a dictionary ZIP file.
+    pub fn open(path: &Path) -> io::Result<Self> {
+        let mut reader = Self::new(File::open(path)?)?;
+        reader.path = path.display().to_string();
+
+        Ok(reader)
+    }
+}
+
+impl<R> DictionaryReader<R>
+where
+    R: Read + Seek,
+{
+    /// Opens a dictionary from a ZIP archive and reads its info and tags.
+    pub fn new(reader: R) -> io::Result<Self> {
+        let mut reader = Self {
+            archive: ZipArchive::new(reader)?,
+            path: "dictionary".to_owned(),
+            banks: Vec::new(),
+            info: None,
+            tags: TagBank::default(),
+        };
+
+        for index in 0..reader.archive.len() {
+            let mut file = reader.archive.by_index(index)?;
+
+            // Retrieve the file name of the entry within the ZIP.
+            let filename = file
+                .enclosed_name()
+                .and_then(|path| Some(path.file_name()?.to_str()?.to_owned()))
+                .unwrap_or("UNKNOWN".to_owned());
+
+            if filename == "index.json" {
+                let mut raw_data = String::new();
+                file.read_to_string(&mut raw_data)?;
+
+                reader.info = Some(serde_json::from_str(&raw_data).map_err(invalid_data)?);
+            } else if filename.starts_with("tag_bank_") {
+                let mut raw_data = String::new();
+                file.read_to_string(&mut raw_data)?;
+
+                let tags: Vec<TagBankEntry> =
+                    serde_json::from_str(&raw_data).map_err(invalid_data)?;
+                reader.tags.extend(tags.into_iter().collect());
+            } else if filename.starts_with("term_")
+                || filename.starts_with("kanji_")
+                || filename == "UNKNOWN"
+            {
+                reader.banks.push(index);
+            }
+        }
+
+        Ok(reader)
+    }
+
+    /// Returns the title of the dictionary, or its file name if it has no `index.json`.
+    pub fn name(&self) -> &str {
+        self.info
+            .as_ref()
+            .map_or(self.path.as_str(), |info| info.title.as_str())
+    }
+
+    /// Returns the title and revision of the dictionary, if it has an `index.json`.
+    pub fn source(&self) -> Option<Source> {
+        self.info.as_ref().map(|info| info.source())
+    }
+
+    /// Returns the number of `term_` and `kanji_` banks.
+    pub fn bank_count(&self) -> usize {
+        self.banks.len()
+    }
+
+    /// Returns an iterator reading the `term_` and `kanji_` banks one at a time.
+    pub fn banks<T>(&mut self) -> Banks<'_, T, R>
+    where
+        T: DeserializeOwned,
+    {
+        Banks {
+            reader: self,
+            next: 0,
+            marker: PhantomData,
+        }
+    }
+
+    /// Reads and parses the bank at an index of the archive.
+    fn read_bank<T>(&mut self, index: usize) -> io::Result<Vec<T>>
+    where
+        T: DeserializeOwned,
+    {
+        let mut file = self.archive.by_index(index)?;
+
+        let mut raw_data = String::new();
+        file.read_to_string(&mut raw_data)?;
+
+        serde_json::from_str(&raw_data).map_err(|err| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("Invalid bank {}: {}", file.name(), err),
+            )
+        })
+    }
+}
+
+/// Converts a JSON error into an I/O error.
+fn invalid_data(err: serde_json::Error) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, err)
+}
+
+/// An iterator over the parsed banks of a [`DictionaryReader`].
+pub struct Banks<'a, T, R> {
+    /// The dictionary being read.
+    reader: &'a mut DictionaryReader<R>,
+    /// The position of the next bank in the bank list.
+    next: usize,
+    marker: PhantomData<T>,
+}
+
+impl<T, R> Iterator for Banks<'_, T, R>
+where
+    T: DeserializeOwned,
+    R: Read + Seek,
+{
+    type Item = io::Result<Vec<T>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let index = *self.reader.banks.get(self.next)?;
+        self.next += 1;
+
+        Some(self.reader.read_bank(index))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let left = self.reader.banks.len() - self.next;
+        (left, Some(left))
+    }
+}
+
+/// A Yomitan dictionary read fully into memory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Dictionary<T> {
+    /// The metadata from `index.json`, if the file has one.
+    pub info: Option<DictionaryInfo>,
+    /// The tag definitions from the `tag_bank` files.
+    pub tags: TagBank,
+    /// The entries of the `term_` and `kanji_` banks.
+    pub entries: Vec<T>,
+}
+
+impl<T> Dictionary<T> {
+    /// Returns the title and revision of the dictionary, if it has an `index.json`.
+    pub fn source(&self) -> Option<Source> {
+        self.info.as_ref().map(|info| info.source())
+    }
+}
+
+/// Parses a ZIP file into a [`Dictionary`] of objects of type T.
+///
+/// This function reads a ZIP archive from the specified path and parses its entries:
+/// `index.json` into the dictionary info, `tag_bank_` files into the tag bank and `term_` and
+/// `kanji_` files into the entries. Other entries are skipped.
+///
+/// Large dictionaries are better read bank by bank with a [`DictionaryReader`].
+///
+/// # Type Parameters
+/// - `T`: The type of objects to parse from the bank entries. This type must implement
+///   `DeserializeOwned` trait, allowing it to be deserialized from JSON data.
+///
+/// # Arguments
+/// - `path`: A reference to a `Path` specifying the location of the ZIP file.
+/// - `progress`: Receives the number of banks read.
+///
+/// # Returns
+/// - `io::Result<Dictionary<T>>`: The parsed dictionary if successful, or an error if any I/O
+///   operation fails or a file is not valid JSON.
+pub fn parse_dictionary<T>(path: &Path, progress: &mut dyn Progress) -> io::Result<Dictionary<T>>
+where
+    T: DeserializeOwned,
+{
+    read_dictionary(&mut DictionaryReader::open(path)?, progress)
+}
+
+/// Parses an opened ZIP archive into a [`Dictionary`] of objects of type T, see
+/// [`parse_dictionary`].
+pub fn parse_archive<T, R>(reader: R, progress: &mut dyn Progress) -> io::Result<Dictionary<T>>
+where
+    T: DeserializeOwned,
+    R: Read + Seek,
+{
+    read_dictionary(&mut DictionaryReader::new(reader)?, progress)
+}
+
+/// Reads every bank of a dictionary into memory.
+fn read_dictionary<T, R>(
+    reader: &mut DictionaryReader<R>,
+    progress: &mut dyn Progress,
+) -> io::Result<Dictionary<T>>
+where
+    T: DeserializeOwned,
+    R: Read + Seek,
+{
+    progress.start(&format!("Reading {}", reader.name()), reader.bank_count());
+
+    let mut entries = Vec::new();
+    for (count, bank) in reader.banks::<T>().enumerate() {
+        entries.append(&mut bank?);
+        progress.advance(count + 1);
+    }
+
+    progress.finish();
+
+    Ok(Dictionary {
+        info: reader.info.clone(),
+        tags: reader.tags.clone(),
+        entries,
+    })
+}
+
+/// Parses a ZIP file into a vector of objects of type T.
+///
+/// Only the `term_` and `kanji_` banks are returned, see [`parse_dictionary`] for the
+/// dictionary info and tags.
 ///
 /// # Type Parameters
 /// - `T`: The type of objects to parse from the ZIP file entries. This type must implement
@@ -50,78 +302,56 @@ where
 ///
 /// # Arguments
 /// - `path`: A reference to a `Path` specifying the location of the ZIP file.
+/// - `progress`: Receives the number of banks read.
 ///
 /// # Returns
 /// - `io::Result<Vec<T>>`: A result containing a vector of parsed objects if successful,
 ///   or an error if any I/O operation fails.
-///
-/// # Notes
-/// - **Entry Filtering**: The function filters ZIP entries based on their filenames. It processes
-///   entries whose filenames start with "term_" or "kanji_", as well as those named "UNKNOWN".
-///   All other entries are ignored.
-/// - **Progress Feedback**: During processing, the function prints progress information to the console,
-///   indicating which files are being processed and whether they were skipped.
-/// - **Error Handling**: If any I/O operation fails (e.g., file not found, read error), the function
-///   returns an `io::Result` with the corresponding error. It does not handle errors internally beyond
-///   propagating them up.
-///
-/// # Panics
-/// The function does not panic but returns errors for handling by the caller.
-pub fn parse_zipfile<T>(path: &Path) -> io::Result<Vec<T>>
+pub fn parse_zipfile<T>(path: &Path, progress: &mut dyn Progress) -> io::Result<Vec<T>>
 where
     T: DeserializeOwned,
 {
-    println!("Reading file {}:", path.to_str().unwrap());
-
-    // Open the ZIP archive and create a mutable ZipArchive instance.
-    let mut zip_archive = ZipArchive::new(File::open(path)?)?;
-
-    // Retrieve the total number of entries in the ZIP archive to track progress.
-    let bank_count = zip_archive.len();
-    let mut data: Vec<T> = Vec::new();
-
-    // Iterate over each entry in the ZIP archive by index.
-    for index in 0..bank_count {
-        // Access the current entry by its index.
-        let mut bank = zip_archive.by_index(index).unwrap();
-
-        // Retrieve the enclosed name (path within the ZIP) of the entry.
-        let path = bank.enclosed_name().unwrap();
-        let filename = path.file_name().unwrap().to_str().unwrap_or("UNKNOWN");
-        let path = path.to_str().unwrap_or("UNKNOWN");
-
-        // Skip entries that do not match the expected patterns or are unknown.
-        if !(filename.starts_with("term_")
-            || filename.starts_with("kanji_")
-            || filename == "UNKNOWN")
-        {
-            println!("  {}/{} File: {} [IGNORED]", index, bank_count, path);
-
-            continue;
-        } else {
-            // Print the processing status for valid entries.
-            println!("  {}/{} File: {}", index, bank_count, path);
-        }
+    Ok(parse_dictionary(path, progress)?.entries)
+}
 
-        // Read the contents of the entry into a string.
-        let mut raw_data = String::new();
-        bank.read_to_string(&mut raw_data)?;
+/// Opens every `.zip` file of a directory as a [`DictionaryReader`] without reading its banks.
+pub fn open_directory(path: &Path) -> io::Result<Vec<DictionaryReader<File>>> {
+    let mut out = Vec::new();
+
+    for path in fs::read_dir(path)? {
+        let path = path?.path();
 
-        // Parse the JSON string into objects of type T and append to data.
-        data.append(&mut parse_bank::<T>(&raw_data).unwrap());
+        if path.extension().is_some_and(|ext| ext == "zip") {
+            out.push(DictionaryReader::open(&path)?);
+        }
     }
 
-    // Print a completion message after processing all entries.
-    println!();
+    Ok(out)
+}
 
-    Ok(data)
+/// Parses a directory containing ZIP files into a vector of [`Dictionary`], one for every file.
+///
+/// # Arguments
+/// - `path`: A reference to a `Path` specifying the location of the directory containing
+///   ZIP files.
+/// - `progress`: Receives the number of banks read of every file.
+pub fn parse_dictionaries<T>(
+    path: &Path,
+    progress: &mut dyn Progress,
+) -> io::Result<Vec<Dictionary<T>>>
+where
+    T: DeserializeOwned,
+{
+    open_directory(path)?
+        .iter_mut()
+        .map(|reader| read_dictionary(reader, progress))
+        .collect()
 }
 
 /// Parses a directory containing ZIP files into a vector of objects of type T.
 ///
 /// This function reads all files in the specified directory, treating each file as a ZIP
-/// archive. For each ZIP file, it calls `parse_zipfile` to extract data and aggregates all
-/// parsed objects into a single vector.
+/// archive, and aggregates the parsed objects of every file into a single vector.
 ///
 /// # Type Parameters
 /// - `T`: The type of objects to parse from the ZIP files. This type must implement
@@ -130,27 +360,19 @@ where
 /// # Arguments
 /// - `path`: A reference to a `Path` specifying the location of the directory containing
 ///   ZIP files.
+/// - `progress`: Receives the number of banks read of every file.
 ///
 /// # Returns
 /// - `io::Result<Vec<T>>`: A result containing a vector of parsed objects if successful,
 ///   or an error if any I/O operation fails.
-pub fn parse_directory<T>(path: &Path) -> io::Result<Vec<T>>
+pub fn parse_directory<T>(path: &Path, progress: &mut dyn Progress) -> io::Result<Vec<T>>
 where
     T: DeserializeOwned,
 {
-    // Read all entries in the specified directory.
-    let paths = fs::read_dir(path)?;
-
-    // Initialize an empty vector to collect parsed data.
-    let mut data: Vec<T> = Vec::new();
-
-    // Process each entry in the directory.
-    for path in paths {
-        // For each entry, parse the ZIP file and append its data.
-        data.append(&mut parse_zipfile::<T>(&path?.path())?);
-    }
-
-    Ok(data)
+    Ok(parse_dictionaries(path, progress)?
+        .into_iter()
+        .flat_map(|dictionary| dictionary.entries)
+        .collect())
 }
 
 /// Trait for converting JMnedict data into structured formats.
@@ -177,6 +399,202 @@ pub trait ConvertableJmnedicData {
         words: &mut HashMap<(String, String), Word>,
         kanji_readings: &HashMap<char, HashSet<String>>,
     ) -> Result<(), String>;
+
+    /// Returns the `(kanji, kana)` keys of the words the entry adds or updates.
+    fn word_keys(&self) -> Vec<(String, String)> {
+        Vec::new()
+    }
+
+    /// Returns the `(kanji, kana)` key of the word and the accent patterns of a pitch entry.
+    /// The patterns are attached by [`WordConverter::finish`], as pitch banks may be read before
+    /// the terms they describe.
+    fn pitch(&self) -> Option<((String, String), Vec<u8>)> {
+        None
+    }
+}
+
+impl<T> ConvertableJmnedicData for &T
+where
+    T: ConvertableJmnedicData,
+{
+    fn convert_kanji_data(&self, kanji: &mut HashMap<char, Kanji>) -> Result<(), String> {
+        (*self).convert_kanji_data(kanji)
+    }
+
+    fn convert_word_data(
+        &self,
+        words: &mut HashMap<(String, String), Word>,
+        kanji_readings: &HashMap<char, HashSet<String>>,
+    ) -> Result<(), String> {
+        (*self).convert_word_data(words, kanji_readings)
+    }
+
+    fn word_keys(&self) -> Vec<(String, String)> {
+        (*self).word_keys()
+    }
+
+    fn pitch(&self) -> Option<((String, String), Vec<u8>)> {
+        (*self).pitch()
+    }
+}
+
+/// Collects the words converted from entries one at a time, which lets entries of several kinds
+/// of dictionaries update the same words.
+///
+/// # Example
+/// ```
+/// use std::collections::HashMap;
+///
+/// use anki_utill::dict::{dict_parser::WordConverter, jmnedict::jmnedict_entry::JmnedictEntry};
+///
+/// let entry: JmnedictEntry =
+///     serde_json::from_str(r#"["猫", "ねこ", "n", "", 1, ["cat"], 1, ""]"#).unwrap();
+///
+/// let mut words = WordConverter::new(&HashMap::new());
+/// words.add(&entry, None).unwrap();
+///
+/// assert!(words.finish().contains_key("猫[ねこ]"));
+/// ```
+pub struct WordConverter {
+    /// Precomputed readings of all kanji characters.
+    kanji_readings: HashMap<char, HashSet<String>>,
+    /// The words by their `(kanji, kana)` key.
+    words: HashMap<(String, String), Word>,
+    /// The accent patterns of the pitch entries and the dictionaries they were taken from by the
+    /// `(kanji, kana)` key of their word.
+    pitch: HashMap<(String, String), (Vec<u8>, HashSet<Source>)>,
+}
+
+impl WordConverter {
+    /// Creates a converter using the readings of the given kanji.
+    pub fn new(kanji: &HashMap<char, Kanji>) -> Self {
+        Self {
+            kanji_readings: kanji
+                .values()
+                .map(|kanji| (kanji.kanji, kanji.readings()))
+                .collect(),
+            words: HashMap::new(),
+            pitch: HashMap::new(),
+        }
+    }
+
+    /// Adds or updates the word data of an entry, recording the dictionary it was taken from.
+    ///
+    /// # Returns
+    /// * An error of kind `InvalidData` on entries of an unrecognized type.
+    pub fn add<T>(&mut self, entry: &T, source: Option<&Source>) -> io::Result<()>
+    where
+        T: ConvertableJmnedicData,
+    {
+        entry
+            .convert_word_data(&mut self.words, &self.kanji_readings)
+            .map_err(|message| io::Error::new(io::ErrorKind::InvalidData, message))?;
+
+        if let Some((key, positions)) = entry.pitch() {
+            let (pitch, sources) = self.pitch.entry(key).or_default();
+            for position in positions {
+                if !pitch.contains(&position) {
+                    pitch.push(position);
+                }
+            }
+            sources.extend(source.cloned());
+        }
+
+        let Some(source) = source else {
+            return Ok(());
+        };
+
+        for key in entry.word_keys() {
+            if let Some(word) = self.words.get_mut(&key) {
+                word.sources.insert(source.clone());
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Adds the entries of dictionaries bank by bank, see [`convert_archive_words`].
+    pub fn add_archives<T, R>(
+        &mut self,
+        readers: &mut [DictionaryReader<R>],
+        progress: &mut dyn Progress,
+    ) -> io::Result<()>
+    where
+        T: ConvertableJmnedicData + DeserializeOwned,
+        R: Read + Seek,
+    {
+        progress.start("Converting words", count_banks(readers));
+
+        let mut count = 0;
+        for reader in readers.iter_mut() {
+            let source = reader.source();
+
+            for bank in reader.banks::<T>() {
+                for entry in bank? {
+                    self.add(&entry, source.as_ref())?;
+                }
+
+                count += 1;
+                progress.advance(count);
+            }
+        }
+
+        progress.finish();
+
+        Ok(())
+    }
+
+    /// Attaches the accent patterns to their words, sorts the glossaries, shares the kanji forms
+    /// and readings between the words of an entry and returns the words by their furigana.
+    /// Accent patterns of words no term entry added are dropped.
+    pub fn finish(mut self) -> HashMap<String, Word> {
+        for (key, (pitch, sources)) in self.pitch.drain() {
+            let Some(word) = self.words.get_mut(&key) else {
+                continue;
+            };
+
+            // Keep every accent pattern, without duplicates
+            for position in pitch {
+                if !word.pitch.contains(&position) {
+                    word.pitch.push(position);
+                }
+            }
+            word.sources.extend(sources);
+        }
+
+        // Words of dictionaries with one entry per spelling only know their own forms
+        let mut entries: HashMap<i32, Word> = HashMap::new();
+        for word in self.words.values().filter(|word| word.word_id > 0) {
+            entries
+                .entry(word.word_id)
+                .and_modify(|entry| entry.extend_forms(&word.forms, &word.readings))
+                .or_insert_with(|| word.clone());
+        }
+
+        for word in self.words.values_mut() {
+            if let Some(entry) = entries.get(&word.word_id) {
+                // Only the forms the reading of the word applies to are spellings of it
+                let kana = word.furigana.to_kana();
+                let reading = entry.readings.iter().find(|reading| reading.text == kana);
+                let forms: Vec<KanjiForm> = entry
+                    .forms
+                    .iter()
+                    .filter(|form| reading.is_none_or(|reading| reading.applies_to(&form.text)))
+                    .cloned()
+                    .collect();
+
+                word.extend_forms(&forms, &entry.readings);
+            }
+        }
+
+        self.words
+            .into_values()
+            .map(|mut word| {
+                word.glossary.sort_unstable_by_key(|d| -d.order);
+                (word.furigana.clone(), word)
+            })
+            .collect()
+    }
 }
 
 /// Converts JMnedict data into a structured format containing Kanji and Word information.
@@ -187,25 +605,26 @@ pub trait ConvertableJmnedicData {
 ///
 /// # Arguments
 /// * `data` - A slice of Jmnedict entries containing Kanji and Word/Frequency data
+/// * `progress` - Receives the number of entries converted
 ///
 /// # Returns
-/// * `(HashMap<char, Kanji>, HashMap<String, Word>)`
+/// * `io::Result<(HashMap<char, Kanji>, HashMap<String, Word>)>`
 ///   - First map: Kanji character to Kanji info
-///   - Second map: Furigana string to Word info  
-pub fn convert_data<T>(data: &[T]) -> (HashMap<char, Kanji>, HashMap<String, Word>)
+///   - Second map: Furigana string to Word info
+pub fn convert_data<T>(
+    data: &[T],
+    progress: &mut dyn Progress,
+) -> io::Result<(HashMap<char, Kanji>, HashMap<String, Word>)>
 where
     T: ConvertableJmnedicData,
 {
-    // Print initial conversion message
-    println!("Converting data:");
-
     // Converts and formats all kanji entries from the data
-    let kanji = convert_kanji_data(data);
+    let kanji = convert_kanji_data(data, progress);
 
     // Converts and formats word data using precomputed kanji readings
-    let words = convert_word_data(&kanji, data);
+    let words = convert_word_data(&kanji, data, progress)?;
 
-    (kanji, words)
+    Ok((kanji, words))
 }
 
 /// Converts Kanji data from the entries into a HashMap.
@@ -215,28 +634,25 @@ where
 ///
 /// # Arguments
 /// * `data` - A slice of Jmnedict entries containing Kanji data
+/// * `progress` - Receives the number of entries converted
 ///
 /// # Returns
 /// * `HashMap<char, Kanji>` - Maps each Kanji character to its detailed information
-pub fn convert_kanji_data<T>(data: &[T]) -> HashMap<char, Kanji>
+pub fn convert_kanji_data<T>(data: &[T], progress: &mut dyn Progress) -> HashMap<char, Kanji>
 where
     T: ConvertableJmnedicData,
 {
     // Process and collect Kanji information from the entries
-    println!("Converting Kanji:");
+    progress.start("Converting kanji", data.len());
+
     let mut kanji: HashMap<char, Kanji> = HashMap::new();
     for (count, entry) in data.iter().enumerate() {
-        // Print progress for every 5% of total entries or at the end
-        if count % (data.len() / 20) == 0 {
-            println!(
-                "  {:>3}% kanji",
-                ((count as f32 / data.len() as f32) * 100.0).round()
-            );
-        }
-
         let _ = entry.convert_kanji_data(&mut kanji);
+        progress.advance(count + 1);
     }
 
+    progress.finish();
+
     kanji
 }
 
@@ -249,58 +665,314 @@ where
 /// # Arguments
 /// * `kanji` - A precomputed HashMap that maps each Kanji character to its detailed info
 /// * `data` - A slice of Jmnedict entries containing Word data
+/// * `progress` - Receives the number of entries converted
 ///
 /// # Returns
-/// * `HashMap<String, Word>` - Maps word furigana representations to their detailed information
-pub fn convert_word_data<T>(kanji: &HashMap<char, Kanji>, data: &[T]) -> HashMap<String, Word>
+/// * `io::Result<HashMap<String, Word>>` - Maps word furigana representations to their detailed
+///   information
+pub fn convert_word_data<T>(
+    kanji: &HashMap<char, Kanji>,
+    data: &[T],
+    progress: &mut dyn Progress,
+) -> io::Result<HashMap<String, Word>>
 where
     T: ConvertableJmnedicData,
 {
     // Process and collect Word information from the entries
-    println!("\nConverting Words:");
-    let mut words: HashMap<(String, String), Word> = HashMap::new();
+    progress.start("Converting words", data.len());
+
+    let mut words = WordConverter::new(kanji);
+    for (count, entry) in data.iter().enumerate() {
+        words.add(entry, None)?;
+        progress.advance(count + 1);
+    }
+
+    progress.finish();
+
+    Ok(words.finish())
+}
 
-    // Create a map of Kanji readings for later use in word processing
-    let kanji_readings: HashMap<char, HashSet<String>> = kanji
-        .values()
-        .map(|kanji| (kanji.kanji, kanji.readings()))
+/// Converts the entries of several dictionaries into structured Kanji and Word information like
+/// [`convert_data`], recording on every word the dictionaries it was taken from.
+///
+/// # Arguments
+/// * `dictionaries` - The parsed dictionaries, see [`parse_dictionaries`]
+/// * `progress` - Receives the number of entries converted
+///
+/// # Returns
+/// * `io::Result<(HashMap<char, Kanji>, HashMap<String, Word>)>`
+///   - First map: Kanji character to Kanji info
+///   - Second map: Furigana string to Word info
+pub fn convert_dictionaries<T>(
+    dictionaries: &[Dictionary<T>],
+    progress: &mut dyn Progress,
+) -> io::Result<(HashMap<char, Kanji>, HashMap<String, Word>)>
+where
+    T: ConvertableJmnedicData,
+{
+    let entries: Vec<&T> = dictionaries
+        .iter()
+        .flat_map(|dictionary| &dictionary.entries)
         .collect();
+    let kanji = convert_kanji_data(&entries, progress);
 
-    // Iterate through each entry to build Word data
-    for (count, entry) in data.iter().enumerate() {
-        // Print progress for every 5% of total entries or at the end
-        if count % (data.len() / 20) == 0 {
-            println!(
-                "  {:>3}% words",
-                ((count as f32 / data.len() as f32) * 100.0).round()
-            );
+    progress.start("Converting words", entries.len());
+
+    let mut words = WordConverter::new(&kanji);
+    let mut count = 0;
+    for dictionary in dictionaries {
+        let source = dictionary.source();
+
+        for entry in &dictionary.entries {
+            words.add(entry, source.as_ref())?;
+
+            count += 1;
+            progress.advance(count);
+        }
+    }
+
+    progress.finish();
+
+    Ok((kanji, words.finish()))
+}
+
+/// Converts dictionaries bank by bank into structured Kanji and Word information, recording on
+/// every word the dictionaries it was taken from.
+///
+/// The banks are read twice, first for the kanji and then for the words, which keeps a single
+/// bank in memory at a time.
+///
+/// # Arguments
+/// * `readers` - The opened dictionaries, see [`open_directory`]
+/// * `progress` - Receives the number of banks converted
+///
+/// # Returns
+/// * `io::Result<(HashMap<char, Kanji>, HashMap<String, Word>)>`
+///   - First map: Kanji character to Kanji info
+///   - Second map: Furigana string to Word info
+pub fn convert_archives<T, R>(
+    readers: &mut [DictionaryReader<R>],
+    progress: &mut dyn Progress,
+) -> io::Result<(HashMap<char, Kanji>, HashMap<String, Word>)>
+where
+    T: ConvertableJmnedicData + DeserializeOwned,
+    R: Read + Seek,
+{
+    let kanji = convert_archive_kanji::<T, R>(readers, progress)?;
+    let words = convert_archive_words::<T, R>(&kanji, readers, progress)?;
+
+    Ok((kanji, words))
+}
+
+/// Converts the Kanji data of dictionaries bank by bank, see [`convert_kanji_data`].
+pub fn convert_archive_kanji<T, R>(
+    readers: &mut [DictionaryReader<R>],
+    progress: &mut dyn Progress,
+) -> io::Result<HashMap<char, Kanji>>
+where
+    T: ConvertableJmnedicData + DeserializeOwned,
+    R: Read + Seek,
+{
+    progress.start("Converting kanji", count_banks(readers));
+
+    let mut kanji: HashMap<char, Kanji> = HashMap::new();
+    let mut count = 0;
+    for reader in readers.iter_mut() {
+        for bank in reader.banks::<T>() {
+            for entry in bank? {
+                let _ = entry.convert_kanji_data(&mut kanji);
+            }
+
+            count += 1;
+            progress.advance(count);
+        }
+    }
+
+    progress.finish();
+
+    Ok(kanji)
+}
+
+/// Converts the Word data of dictionaries bank by bank, recording on every word the dictionaries
+/// it was taken from, see [`convert_word_data`].
+pub fn convert_archive_words<T, R>(
+    kanji: &HashMap<char, Kanji>,
+    readers: &mut [DictionaryReader<R>],
+    progress: &mut dyn Progress,
+) -> io::Result<HashMap<String, Word>>
+where
+    T: ConvertableJmnedicData + DeserializeOwned,
+    R: Read + Seek,
+{
+    let mut words = WordConverter::new(kanji);
+    words.add_archives::<T, R>(readers, progress)?;
+
+    Ok(words.finish())
+}
+
+/// Returns the total number of banks of the dictionaries.
+fn count_banks<R>(readers: &[DictionaryReader<R>]) -> usize
+where
+    R: Read + Seek,
+{
+    readers.iter().map(|reader| reader.bank_count()).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Cursor, Write};
+
+    use zip::{write::SimpleFileOptions, ZipWriter};
+
+    use super::*;
+    use crate::dict::{jmnedict::jmnedict_entry::JmnedictEntry, progress::NoProgress};
+
+    /// Creates a ZIP archive in memory from `(name, content)` files.
+    fn archive(files: &[(&str, &str)]) -> Cursor<Vec<u8>> {
+        let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+
+        for (name, content) in files {
+            zip.start_file(*name, SimpleFileOptions::default()).unwrap();
+            zip.write_all(content.as_bytes()).unwrap();
+        }
+
+        zip.finish().unwrap()
+    }
+
+    /// Tests that the index and tag banks are parsed and words record their dictionaries.
+    #[test]
+    fn parse_index_and_tags() {
+        let jmdict: Dictionary<JmnedictEntry> = parse_archive(
+            archive(&[
+                (
+                    "index.json",
+                    r#"{"title": "JMdict", "revision": "r1", "format": 3}"#,
+                ),
+                ("styles.css", ""),
+                (
+                    "tag_bank_1.json",
+                    r#"[["n", "partOfSpeech", -3, "noun (common)", 0]]"#,
+                ),
+                (
+                    "term_bank_1.json",
+                    r#"[["猫", "ねこ", "n", "", 1, ["cat"], 1, ""],
+                    ["犬", "いぬ", "n", "", 1, ["dog"], 2, ""]]"#,
+                ),
+            ]),
+            &mut NoProgress,
+        )
+        .unwrap();
+
+        assert_eq!(jmdict.info.as_ref().unwrap().title, "JMdict");
+        assert_eq!(jmdict.tags.get("n").unwrap().notes, "noun (common)");
+        assert_eq!(jmdict.entries.len(), 2);
+
+        let jlpt: Dictionary<JmnedictEntry> = parse_archive(archive(&[
+            ("index.json", r#"{"title": "JLPT", "revision": "r2", "version": 3}"#),
+            (
+                "term_meta_bank_1.json",
+                r#"[["猫", "freq", {"reading": "ねこ", "frequency": {"value": 5, "displayValue": "N5"}}]]"#,
+            ),
+        ]), &mut NoProgress)
+        .unwrap();
+
+        assert!(jlpt.tags.is_empty());
+
+        let (_, words) = convert_dictionaries(&[jmdict, jlpt], &mut NoProgress).unwrap();
+
+        let mut sources: Vec<&Source> = words["猫[ねこ]"].sources.iter().collect();
+        sources.sort_unstable();
+        assert_eq!(
+            sources,
+            vec![
+                &Source::new("JLPT".into(), "r2".into()),
+                &Source::new("JMdict".into(), "r1".into())
+            ]
+        );
+        assert_eq!(words["犬[いぬ]"].sources.len(), 1);
+    }
+
+    /// Records every progress event.
+    #[derive(Default)]
+    struct Recorder(Vec<String>);
+
+    impl Progress for Recorder {
+        fn start(&mut self, stage: &str, total: usize) {
+            self.0.push(format!("{} {}", stage, total));
         }
 
-        // Add or update the word data for a word entry
-        let result = entry.convert_word_data(&mut words, &kanji_readings);
+        fn advance(&mut self, done: usize) {
+            self.0.push(done.to_string());
+        }
 
-        if let Err(message) = result {
-            // Handle unrecognized entry types
-            println!("{}", message);
-            panic!()
+        fn finish(&mut self) {
+            self.0.push("done".to_owned());
         }
     }
 
-    // Sorting glossary
-    for (_, word) in words.iter_mut() {
-        word.glossary.sort_unstable_by_key(|d| -d.order);
+    /// Tests that reading bank by bank gives the same words as reading into memory.
+    #[test]
+    fn stream_banks() {
+        let files = [
+            (
+                "index.json",
+                r#"{"title": "JMdict", "revision": "r1", "format": 3}"#,
+            ),
+            (
+                "term_bank_1.json",
+                r#"[["猫", "ねこ", "n", "", 1, ["cat"], 1, ""]]"#,
+            ),
+            (
+                "term_bank_2.json",
+                r#"[["犬", "いぬ", "n", "", 1, ["dog"], 2, ""]]"#,
+            ),
+        ];
+
+        let mut reader = DictionaryReader::new(archive(&files)).unwrap();
+        assert_eq!(reader.name(), "JMdict");
+        assert_eq!(reader.bank_count(), 2);
+
+        let banks: Vec<Vec<JmnedictEntry>> = reader.banks().map(Result::unwrap).collect();
+        assert_eq!(banks.len(), 2);
+        assert_eq!(banks[0].len(), 1);
+
+        let mut progress = Recorder::default();
+        let (kanji, words) =
+            convert_archives::<JmnedictEntry, _>(&mut [reader], &mut progress).unwrap();
+
+        let dictionary = parse_archive(archive(&files), &mut NoProgress).unwrap();
+        assert_eq!(
+            convert_dictionaries::<JmnedictEntry>(&[dictionary], &mut NoProgress).unwrap(),
+            (kanji, words)
+        );
+
+        assert_eq!(
+            progress.0,
+            vec![
+                "Converting kanji 2",
+                "1",
+                "2",
+                "done",
+                "Converting words 2",
+                "1",
+                "2",
+                "done"
+            ]
+        );
     }
 
-    // Print a blank line after Words processing
-    println!();
+    /// Tests that empty and invalid input is handled without panicking.
+    #[test]
+    fn empty_and_invalid_input() {
+        let (kanji, words) = convert_data::<JmnedictEntry>(&[], &mut NoProgress).unwrap();
+        assert!(kanji.is_empty() && words.is_empty());
 
-    // Convert word map from (kanji, kana) tuples to Furigana strings
-    let words: HashMap<String, Word> = words
-        .clone()
-        .into_values()
-        .map(|word| (word.furigana.clone(), word))
-        .collect();
+        let unknown: JmnedictEntry = serde_json::from_str(r#"{"unknown": true}"#).unwrap();
+        let err = convert_data(&[unknown], &mut NoProgress).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
 
-    // Return the processed Kanji and Words data
-    words
+        let mut reader = DictionaryReader::new(archive(&[("term_bank_1.json", "{")])).unwrap();
+        assert_eq!(reader.name(), "dictionary");
+        assert!(reader.banks::<JmnedictEntry>().next().unwrap().is_err());
+    }
 }
@@ -0,0 +1,382 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fs::{self, File},
+    io::{self, BufRead, BufReader},
+    path::{Path, PathBuf},
+};
+
+use quick_xml::{events::Event, Reader};
+
+use crate::{
+    dict::{
+        dict_parser::{ConvertableJmnedicData, WordConverter},
+        progress::Progress,
+    },
+    entry::{Kanji, Source, Word},
+};
+
+use super::{
+    edrdg_jmdict::JmdictEntry,
+    edrdg_kanjidic::KanjidicCharacter,
+    edrdg_xml::{read_element, text_content},
+};
+
+/// Represents an entry of the EDRDG XML files, `JMdict_e.xml`, `JMnedict.xml` or
+/// `kanjidic2.xml`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EdrdgEntry {
+    /// A word entry of `JMdict_e.xml` or `JMnedict.xml`.
+    Word(JmdictEntry),
+
+    /// A kanji entry of `kanjidic2.xml`.
+    Kanji(KanjidicCharacter),
+}
+
+impl ConvertableJmnedicData for EdrdgEntry {
+    fn convert_kanji_data(&self, kanji: &mut HashMap<char, Kanji>) -> Result<(), String> {
+        match self {
+            EdrdgEntry::Word(jmdict_entry) => jmdict_entry.convert_kanji_data(kanji),
+            EdrdgEntry::Kanji(kanjidic_character) => kanjidic_character.convert_kanji_data(kanji),
+        }
+    }
+
+    fn convert_word_data(
+        &self,
+        words: &mut HashMap<(String, String), Word>,
+        kanji_readings: &HashMap<char, HashSet<String>>,
+    ) -> Result<(), String> {
+        match self {
+            EdrdgEntry::Word(jmdict_entry) => jmdict_entry.convert_word_data(words, kanji_readings),
+            EdrdgEntry::Kanji(kanjidic_character) => {
+                kanjidic_character.convert_word_data(words, kanji_readings)
+            }
+        }
+    }
+
+    fn word_keys(&self) -> Vec<(String, String)> {
+        match self {
+            EdrdgEntry::Word(jmdict_entry) => jmdict_entry.word_keys(),
+            EdrdgEntry::Kanji(kanjidic_character) => kanjidic_character.word_keys(),
+        }
+    }
+}
+
+/// Reads the entries of an EDRDG XML file one at a time.
+///
+/// # Example
+/// ```no_run
+/// use std::path::Path;
+///
+/// use anki_utill::dict::edrdg::edrdg_entry::XmlEntries;
+///
+/// let mut entries = XmlEntries::open(Path::new("JMdict_e.xml")).unwrap();
+/// while let Some(entry) = entries.next() {
+///     println!("{:?} {:?}", entries.source(), entry.unwrap());
+/// }
+/// ```
+pub struct XmlEntries<R> {
+    /// The XML reader.
+    reader: Reader<R>,
+    /// The title of the dictionary.
+    title: String,
+    /// The creation date or database version of the file, once read.
+    revision: Option<String>,
+}
+
+impl XmlEntries<BufReader<File>> {
+    /// Opens an XML file. The dictionary is named after the file.
+    pub fn open(path: &Path) -> io::Result<Self> {
+        let title = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default();
+
+        Ok(Self::new(BufReader::new(File::open(path)?), &title))
+    }
+}
+
+impl<R> XmlEntries<R>
+where
+    R: BufRead,
+{
+    /// Reads an XML file from a reader.
+    pub fn new(reader: R, title: &str) -> Self {
+        let mut reader = Reader::from_reader(reader);
+        reader.config_mut().trim_text(true);
+
+        Self {
+            reader,
+            title: title.to_owned(),
+            revision: None,
+        }
+    }
+
+    /// Returns the name and revision of the dictionary. The revision is the creation date of
+    /// `JMdict` files and the database version of `kanjidic2.xml`, which are known once the
+    /// first entry is read.
+    pub fn source(&self) -> Option<Source> {
+        Some(Source::new(self.title.clone(), self.revision.clone()?))
+    }
+}
+
+impl<R> Iterator for XmlEntries<R>
+where
+    R: BufRead,
+{
+    type Item = io::Result<EdrdgEntry>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut buf = Vec::new();
+
+        loop {
+            let event = match self.reader.read_event_into(&mut buf) {
+                Ok(event) => event.into_owned(),
+                Err(err) => return Some(Err(io::Error::new(io::ErrorKind::InvalidData, err))),
+            };
+
+            match event {
+                Event::Start(start) => {
+                    let name = start.name();
+                    if !matches!(name.as_ref(), b"entry" | b"character" | b"header") {
+                        buf.clear();
+                        continue;
+                    }
+
+                    let element = match read_element(&mut self.reader, &start) {
+                        Ok(element) => element,
+                        Err(err) => {
+                            return Some(Err(io::Error::new(io::ErrorKind::InvalidData, err)))
+                        }
+                    };
+
+                    match element.name.as_str() {
+                        "entry" => {
+                            return Some(Ok(EdrdgEntry::Word(JmdictEntry::from_element(&element))))
+                        }
+                        "character" => {
+                            if let Some(character) = KanjidicCharacter::from_element(&element) {
+                                return Some(Ok(EdrdgEntry::Kanji(character)));
+                            }
+                        }
+                        // The header of kanjidic2.xml
+                        _ => {
+                            self.revision = element
+                                .text_of("database_version")
+                                .or(element.text_of("date_of_creation"))
+                                .map(|revision| revision.to_owned());
+                        }
+                    }
+                }
+                // JMdict files note their creation date in a comment
+                Event::Comment(comment) => {
+                    let comment = text_content(&comment);
+                    if let Some((_, date)) = comment.split_once("created:") {
+                        self.revision = Some(date.trim().to_owned());
+                    }
+                }
+                Event::Eof => return None,
+                _ => {}
+            }
+
+            buf.clear();
+        }
+    }
+}
+
+/// Returns the `.xml` files of a directory sorted by name.
+pub fn xml_files(path: &Path) -> io::Result<Vec<PathBuf>> {
+    let mut out = Vec::new();
+
+    for path in fs::read_dir(path)? {
+        let path = path?.path();
+
+        if path.extension().is_some_and(|ext| ext == "xml") {
+            out.push(path);
+        }
+    }
+
+    out.sort_unstable();
+
+    Ok(out)
+}
+
+/// Converts the kanji of EDRDG XML files into a HashMap, see
+/// [`convert_kanji_data`](crate::dict::dict_parser::convert_kanji_data).
+///
+/// # Arguments
+/// * `paths` - The XML files
+/// * `kanji` - The HashMap collecting Kanji information
+/// * `progress` - Receives the number of files converted
+pub fn convert_xml_kanji(
+    paths: &[PathBuf],
+    kanji: &mut HashMap<char, Kanji>,
+    progress: &mut dyn Progress,
+) -> io::Result<()> {
+    progress.start("Converting XML kanji", paths.len());
+
+    for (count, path) in paths.iter().enumerate() {
+        for entry in XmlEntries::open(path)? {
+            let _ = entry?.convert_kanji_data(kanji);
+        }
+
+        progress.advance(count + 1);
+    }
+
+    progress.finish();
+
+    Ok(())
+}
+
+/// Adds the words of EDRDG XML files to a converter, recording on every word the file it was
+/// taken from.
+///
+/// # Arguments
+/// * `paths` - The XML files
+/// * `words` - The converter collecting Word information
+/// * `progress` - Receives the number of files converted
+pub fn convert_xml_words(
+    paths: &[PathBuf],
+    words: &mut WordConverter,
+    progress: &mut dyn Progress,
+) -> io::Result<()> {
+    progress.start("Converting XML words", paths.len());
+
+    for (count, path) in paths.iter().enumerate() {
+        let mut entries = XmlEntries::open(path)?;
+
+        while let Some(entry) = entries.next() {
+            words.add(&entry?, entries.source().as_ref())?;
+        }
+
+        progress.advance(count + 1);
+    }
+
+    progress.finish();
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Reads every entry of an XML test file.
+    fn read(data: &str, title: &str) -> (Vec<EdrdgEntry>, Option<Source>) {
+        let mut entries = XmlEntries::new(data.as_bytes(), title);
+        let out = entries.by_ref().map(Result::unwrap).collect();
+
+        (out, entries.source())
+    }
+
+    /// Tests reading JMdict entries and converting them into words.
+    #[test]
+    fn jmdict() {
+        let (entries, source) = read(include_str!("./test_jmdict.xml"), "JMdict_e");
+
+        assert_eq!(entries.len(), 3);
+        assert_eq!(
+            source,
+            Some(Source::new("JMdict_e".into(), "2025-01-15".into()))
+        );
+
+        let EdrdgEntry::Word(go) = &entries[0] else {
+            panic!("expected a word");
+        };
+        assert_eq!(go.ent_seq, 1578850);
+        assert_eq!(go.senses[1].pos, vec!["v5k", "vi"]);
+        assert_eq!(go.senses[1].dialects, vec!["ksb"]);
+
+        let mut words = HashMap::new();
+        for entry in &entries {
+            entry
+                .convert_word_data(&mut words, &HashMap::new())
+                .unwrap();
+        }
+
+        assert_eq!(words.len(), 6);
+
+        // Reading restrictions
+        assert!(words.contains_key(&("行く".to_owned(), "ゆく".to_owned())));
+        assert!(!words.contains_key(&("往く".to_owned(), "ゆく".to_owned())));
+        assert_eq!(
+            words[&("あからさま".to_owned(), "あからさま".to_owned())].furigana,
+            "あからさま"
+        );
+
+        let iku = &words[&("行く".to_owned(), "いく".to_owned())];
+        assert_eq!(iku.word_id, 1578850);
+        assert_eq!(iku.glossary.len(), 2);
+        assert_eq!(iku.glossary[0].meaning, vec!["to go", "to move (towards)"]);
+        assert_eq!(iku.glossary[0].references, vec!["来る・くる・1"]);
+        assert_eq!(iku.glossary[0].antonyms, vec!["来る"]);
+        assert_eq!(
+            iku.glossary[1].tags,
+            ["v5く", "vi", "ksb"].map(str::to_owned).into()
+        );
+        assert_eq!(
+            iku.frequency,
+            ["⭐", "ichi", "news", "news1k"].map(str::to_owned).into()
+        );
+
+        // Sense restrictions
+        let yuku = &words[&("行く".to_owned(), "ゆく".to_owned())];
+        assert_eq!(yuku.glossary.len(), 1);
+
+        let meihaku = &words[&("明白".to_owned(), "めいはく".to_owned())];
+        assert_eq!(meihaku.glossary[0].meaning, vec!["obvious"]);
+        assert_eq!(
+            meihaku.glossary[0].tags,
+            ["n", "comp", "uk"].map(str::to_owned).into()
+        );
+        assert_eq!(meihaku.glossary[0].field, ["comp".to_owned()].into());
+        assert!(meihaku.usually_kana());
+        assert_eq!(meihaku.readings[1].text, "あからさま");
+        assert!(meihaku.readings[1].no_kanji);
+
+        // Kanji forms and readings of the entry
+        let yuku_forms: Vec<&str> = yuku.forms.iter().map(|form| form.text.as_str()).collect();
+        assert_eq!(yuku_forms, vec!["行く", "往く"]);
+        assert_eq!(yuku.readings[0].text, "ゆく");
+        assert_eq!(yuku.readings[0].restrictions, vec!["行く"]);
+        assert!(yuku.other_forms().is_empty());
+        assert_eq!(iku.other_forms(), vec!["往く"]);
+
+        let akarasama = &words[&("あからさま".to_owned(), "あからさま".to_owned())];
+        assert!(akarasama.other_forms().is_empty());
+        assert!(akarasama.notes().is_empty());
+
+        // JMnedict translations
+        let tokyo = &words[&("東京".to_owned(), "とうきょう".to_owned())];
+        assert_eq!(tokyo.glossary[0].meaning, vec!["Tokyo"]);
+        assert_eq!(tokyo.glossary[0].tags, ["place".to_owned()].into());
+
+        assert_eq!(entries[0].word_keys().len(), 3);
+    }
+
+    /// Tests reading kanjidic2 characters and converting them into kanji.
+    #[test]
+    fn kanjidic() {
+        let (entries, source) = read(include_str!("./test_kanjidic.xml"), "kanjidic2");
+
+        assert_eq!(
+            source,
+            Some(Source::new("kanjidic2".into(), "2025-015".into()))
+        );
+
+        let mut kanji = HashMap::new();
+        for entry in &entries {
+            entry.convert_kanji_data(&mut kanji).unwrap();
+        }
+
+        let a = &kanji[&'亜'];
+        assert_eq!(a.onyomi, ["ア".to_owned()].into());
+        assert_eq!(a.kunyomi, ["つ.ぐ".to_owned()].into());
+        assert_eq!(a.meaning, vec!["Asia", "rank next"]);
+        assert_eq!(a.strokes, Some(7));
+        assert_eq!(a.tags, ["JLPT-N1".to_owned(), "Grade-8".to_owned()].into());
+        assert_eq!(a.nanori, ["や".to_owned(), "つぎ".to_owned()].into());
+        assert_eq!(a.radical, Some(7));
+        assert_eq!(a.variants, vec!["jis208 1-48-19"]);
+        assert_eq!(a.readings(), ["あ".to_owned(), "つ".to_owned()].into());
+    }
+}
@@ -0,0 +1,320 @@
+use std::collections::{HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    dict::{dict_parser::ConvertableJmnedicData, jmnedict::jmnedict_word::remap_tag},
+    entry::{Glossary, Kanji, KanjiForm, Reading, Word},
+    japanese::to_furigana,
+};
+
+use super::edrdg_xml::Element;
+
+/// Represents an `entry` of the EDRDG `JMdict_e.xml` or `JMnedict.xml` files: a word with its
+/// kanji forms, readings and senses.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct JmdictEntry {
+    /// The unique sequence number of the entry.
+    pub ent_seq: i32,
+    /// The kanji forms of the word.
+    pub kanji: Vec<KanjiElement>,
+    /// The readings of the word.
+    pub readings: Vec<ReadingElement>,
+    /// The senses of the word. The translations of `JMnedict.xml` are read as senses.
+    pub senses: Vec<Sense>,
+}
+
+/// A kanji form of a word, the `k_ele` element.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct KanjiElement {
+    /// The word written with kanji.
+    pub keb: String,
+    /// Information tags about the spelling, e.g. `iK` for irregular kanji.
+    pub info: Vec<String>,
+    /// Priority codes, e.g. `news1` or `nf12`.
+    pub priority: Vec<String>,
+}
+
+/// A reading of a word, the `r_ele` element.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ReadingElement {
+    /// The reading in kana.
+    pub reb: String,
+    /// Whether the reading is not a true reading of the kanji forms.
+    pub no_kanji: bool,
+    /// The kanji forms the reading applies to, all forms if empty.
+    pub restrictions: Vec<String>,
+    /// Information tags about the reading, e.g. `ok` for outdated kana.
+    pub info: Vec<String>,
+    /// Priority codes, e.g. `news1` or `nf12`.
+    pub priority: Vec<String>,
+}
+
+/// A sense of a word, the `sense` element, or a `trans` element of `JMnedict.xml`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+pub struct Sense {
+    /// The kanji forms the sense applies to, all forms if empty.
+    pub kanji_restrictions: Vec<String>,
+    /// The readings the sense applies to, all readings if empty.
+    pub reading_restrictions: Vec<String>,
+    /// Part of speech tags. A sense without its own tags uses those of the sense before it.
+    pub pos: Vec<String>,
+    /// Cross-references to related words.
+    pub references: Vec<String>,
+    /// Words of opposite meaning.
+    pub antonyms: Vec<String>,
+    /// Field of application tags, e.g. `comp` for computing.
+    pub fields: Vec<String>,
+    /// Miscellaneous tags, e.g. `uk` for usually written in kana.
+    pub misc: Vec<String>,
+    /// Dialect tags, e.g. `ksb` for Kansai-ben.
+    pub dialects: Vec<String>,
+    /// Additional information about the sense.
+    pub info: Vec<String>,
+    /// The English translations.
+    pub glosses: Vec<String>,
+}
+
+impl JmdictEntry {
+    /// Reads an entry from its `entry` element.
+    pub fn from_element(element: &Element) -> Self {
+        let mut senses: Vec<Sense> = Vec::new();
+
+        for sense in element.children("sense") {
+            let mut pos = sense.texts_of("pos");
+            if pos.is_empty() {
+                pos = senses
+                    .last()
+                    .map(|last| last.pos.clone())
+                    .unwrap_or_default();
+            }
+
+            senses.push(Sense {
+                kanji_restrictions: sense.texts_of("stagk"),
+                reading_restrictions: sense.texts_of("stagr"),
+                pos,
+                references: sense.texts_of("xref"),
+                antonyms: sense.texts_of("ant"),
+                fields: sense.texts_of("field"),
+                misc: sense.texts_of("misc"),
+                dialects: sense.texts_of("dial"),
+                info: sense.texts_of("s_inf"),
+                glosses: sense
+                    .children("gloss")
+                    .filter(|gloss| gloss.attribute("xml:lang").is_none_or(|lang| lang == "eng"))
+                    .map(|gloss| gloss.text.clone())
+                    .collect(),
+            });
+        }
+
+        for trans in element.children("trans") {
+            senses.push(Sense {
+                pos: trans.texts_of("name_type"),
+                references: trans.texts_of("xref"),
+                glosses: trans.texts_of("trans_det"),
+                ..Sense::default()
+            });
+        }
+
+        Self {
+            ent_seq: element
+                .text_of("ent_seq")
+                .and_then(|seq| seq.parse().ok())
+                .unwrap_or_default(),
+            kanji: element
+                .children("k_ele")
+                .map(|k_ele| KanjiElement {
+                    keb: k_ele.text_of("keb").unwrap_or_default().to_owned(),
+                    info: k_ele.texts_of("ke_inf"),
+                    priority: k_ele.texts_of("ke_pri"),
+                })
+                .collect(),
+            readings: element
+                .children("r_ele")
+                .map(|r_ele| ReadingElement {
+                    reb: r_ele.text_of("reb").unwrap_or_default().to_owned(),
+                    no_kanji: r_ele.child("re_nokanji").is_some(),
+                    restrictions: r_ele.texts_of("re_restr"),
+                    info: r_ele.texts_of("re_inf"),
+                    priority: r_ele.texts_of("re_pri"),
+                })
+                .collect(),
+            senses,
+        }
+    }
+
+    /// Returns every `(kanji, reading)` pair of the entry, each being a word. Readings marked
+    /// `re_nokanji` and entries without kanji forms give words written in kana.
+    pub fn pairs(&self) -> Vec<(Option<&KanjiElement>, &ReadingElement)> {
+        let mut out = Vec::new();
+
+        for reading in &self.readings {
+            if reading.no_kanji || self.kanji.is_empty() {
+                out.push((None, reading));
+                continue;
+            }
+
+            out.extend(
+                self.kanji
+                    .iter()
+                    .filter(|kanji| {
+                        reading.restrictions.is_empty() || reading.restrictions.contains(&kanji.keb)
+                    })
+                    .map(|kanji| (Some(kanji), reading)),
+            );
+        }
+
+        out
+    }
+
+    /// Returns the glossary of a word of the entry, one entry for every sense that applies to
+    /// the pair, in order.
+    fn glossary(&self, kanji: Option<&KanjiElement>, reading: &ReadingElement) -> Vec<Glossary> {
+        self.senses
+            .iter()
+            .enumerate()
+            .filter(|(_, sense)| {
+                kanji.is_none_or(|kanji| {
+                    sense.kanji_restrictions.is_empty()
+                        || sense.kanji_restrictions.contains(&kanji.keb)
+                }) && (sense.reading_restrictions.is_empty()
+                    || sense.reading_restrictions.contains(&reading.reb))
+            })
+            .map(|(index, sense)| {
+                let mut glossary =
+                    Glossary::new(-(index as i32), sense.tags(), sense.glosses.clone());
+                glossary.pos = sense.pos.iter().filter_map(|tag| remap_tag(tag)).collect();
+                glossary.field = sense.fields.iter().cloned().collect();
+                glossary.misc = sense.misc.iter().cloned().collect();
+                glossary.dialect = sense.dialects.iter().cloned().collect();
+                glossary.references = sense.references.clone();
+                glossary.antonyms = sense.antonyms.clone();
+
+                glossary
+            })
+            .collect()
+    }
+
+    /// Returns the kanji forms of the entry, the one of a word first.
+    fn forms(&self, kanji: Option<&KanjiElement>) -> Vec<KanjiForm> {
+        let mut elements: Vec<&KanjiElement> = kanji.into_iter().collect();
+        elements.extend(self.kanji.iter().filter(|k| kanji != Some(*k)));
+
+        elements
+            .into_iter()
+            .map(|element| KanjiForm {
+                text: element.keb.clone(),
+                info: element.info.iter().cloned().collect(),
+                priority: element.priority.iter().cloned().collect(),
+            })
+            .collect()
+    }
+
+    /// Returns the readings of the entry, the one of a word first.
+    fn readings(&self, reading: &ReadingElement) -> Vec<Reading> {
+        let mut elements = vec![reading];
+        elements.extend(self.readings.iter().filter(|r| *r != reading));
+
+        elements
+            .into_iter()
+            .map(|element| Reading {
+                text: element.reb.clone(),
+                no_kanji: element.no_kanji,
+                restrictions: element.restrictions.clone(),
+                info: element.info.iter().cloned().collect(),
+                priority: element.priority.iter().cloned().collect(),
+            })
+            .collect()
+    }
+}
+
+impl Sense {
+    /// Returns the part of speech, field, misc and dialect tags, named like the tags of the
+    /// Yomitan dictionaries.
+    pub fn tags(&self) -> HashSet<String> {
+        self.pos
+            .iter()
+            .chain(&self.fields)
+            .chain(&self.misc)
+            .chain(&self.dialects)
+            .filter_map(|tag| remap_tag(tag))
+            .collect()
+    }
+}
+
+/// Returns the frequency tags of priority codes, named like the tags of the Yomitan
+/// dictionaries: `nf12` becomes `news12k` and common words are tagged `⭐`.
+pub fn priority_tags<'a>(priority: impl IntoIterator<Item = &'a String>) -> HashSet<String> {
+    let mut out = HashSet::new();
+
+    for code in priority {
+        if let Some(band) = code.strip_prefix("nf") {
+            if let Ok(band) = band.parse::<u8>() {
+                out.insert(format!("news{}k", band));
+            }
+            continue;
+        }
+
+        if ["news1", "ichi1", "spec1", "spec2", "gai1"].contains(&code.as_str()) {
+            out.insert("⭐".to_owned());
+        }
+
+        out.insert(code.trim_end_matches(['1', '2']).to_owned());
+    }
+
+    out
+}
+
+impl ConvertableJmnedicData for JmdictEntry {
+    fn convert_kanji_data(&self, _: &mut HashMap<char, Kanji>) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn convert_word_data(
+        &self,
+        words: &mut HashMap<(String, String), Word>,
+        kanji_readings: &HashMap<char, HashSet<String>>,
+    ) -> Result<(), String> {
+        for (kanji, reading) in self.pairs() {
+            let keb = kanji.map_or(reading.reb.as_str(), |kanji| kanji.keb.as_str());
+
+            let glossary = self.glossary(kanji, reading);
+            let frequency = priority_tags(
+                kanji
+                    .into_iter()
+                    .flat_map(|kanji| &kanji.priority)
+                    .chain(&reading.priority),
+            );
+
+            // Add or update the word in the words HashMap
+            if let Some(word) = words.get_mut(&(keb.to_owned(), reading.reb.clone())) {
+                word.word_id = self.ent_seq;
+                word.glossary.extend(glossary);
+                word.glossary.sort_unstable_by_key(|w| w.order);
+                word.frequency.extend(frequency);
+            } else {
+                let furigana = to_furigana(keb, &reading.reb, kanji_readings)
+                    .unwrap_or_else(|| format!("{}[{}]", keb, reading.reb));
+
+                let mut word =
+                    Word::new(self.ent_seq, furigana, glossary, frequency, HashSet::new());
+                word.forms = self.forms(kanji);
+                word.readings = self.readings(reading);
+
+                words.insert((keb.to_owned(), reading.reb.clone()), word);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn word_keys(&self) -> Vec<(String, String)> {
+        self.pairs()
+            .into_iter()
+            .map(|(kanji, reading)| {
+                let keb = kanji.map_or(&reading.reb, |kanji| &kanji.keb);
+                (keb.clone(), reading.reb.clone())
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,163 @@
+use std::collections::{HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    dict::dict_parser::ConvertableJmnedicData,
+    entry::{Kanji, Word},
+};
+
+use super::edrdg_xml::Element;
+
+/// Represents a `character` of the EDRDG `kanjidic2.xml` file.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct KanjidicCharacter {
+    /// The kanji character itself.
+    pub literal: char,
+    /// Radical numbers by their type, `classical` or `nelson_c`.
+    pub radicals: Vec<(String, u8)>,
+    /// The school grade the kanji is taught in, 9 and 10 for jinmeiyou kanji.
+    pub grade: Option<u8>,
+    /// The stroke count. Further counts are common miscounts.
+    pub stroke_counts: Vec<u8>,
+    /// Variant forms as `(type, code)` pairs, e.g. `("jis208", "1-48-19")`.
+    pub variants: Vec<(String, String)>,
+    /// The frequency rank among the 2500 most used kanji.
+    pub frequency: Option<u16>,
+    /// The level of the former four level JLPT.
+    pub jlpt: Option<u8>,
+    /// The onyomi readings in katakana.
+    pub onyomi: Vec<String>,
+    /// The kunyomi readings in hiragana, with okurigana after a `.`.
+    pub kunyomi: Vec<String>,
+    /// Readings only used in names.
+    pub nanori: Vec<String>,
+    /// The English meanings.
+    pub meanings: Vec<String>,
+}
+
+impl KanjidicCharacter {
+    /// Reads a character from its `character` element. Returns `None` if it has no literal.
+    pub fn from_element(element: &Element) -> Option<Self> {
+        let misc = element.child("misc");
+        let misc_numbers = |name: &str| -> Vec<u8> {
+            misc.map(|misc| {
+                misc.children(name)
+                    .filter_map(|e| e.text.parse().ok())
+                    .collect()
+            })
+            .unwrap_or_default()
+        };
+
+        let readings: Vec<&Element> = element
+            .child("reading_meaning")
+            .map(|reading_meaning| reading_meaning.children("rmgroup").collect())
+            .unwrap_or_default();
+        let readings_of = |r_type: &str| -> Vec<String> {
+            readings
+                .iter()
+                .flat_map(|group| group.children("reading"))
+                .filter(|reading| reading.attribute("r_type") == Some(r_type))
+                .map(|reading| reading.text.clone())
+                .collect()
+        };
+
+        Some(Self {
+            literal: element.text_of("literal")?.chars().next()?,
+            radicals: element
+                .child("radical")
+                .map(|radical| {
+                    radical
+                        .children("rad_value")
+                        .filter_map(|value| {
+                            Some((
+                                value.attribute("rad_type")?.to_owned(),
+                                value.text.parse().ok()?,
+                            ))
+                        })
+                        .collect()
+                })
+                .unwrap_or_default(),
+            grade: misc_numbers("grade").first().copied(),
+            stroke_counts: misc_numbers("stroke_count"),
+            variants: misc
+                .map(|misc| {
+                    misc.children("variant")
+                        .map(|variant| {
+                            (
+                                variant.attribute("var_type").unwrap_or_default().to_owned(),
+                                variant.text.clone(),
+                            )
+                        })
+                        .collect()
+                })
+                .unwrap_or_default(),
+            frequency: misc
+                .and_then(|misc| misc.text_of("freq"))
+                .and_then(|freq| freq.parse().ok()),
+            jlpt: misc_numbers("jlpt").first().copied(),
+            onyomi: readings_of("ja_on"),
+            kunyomi: readings_of("ja_kun"),
+            nanori: element
+                .child("reading_meaning")
+                .map(|reading_meaning| reading_meaning.texts_of("nanori"))
+                .unwrap_or_default(),
+            meanings: readings
+                .iter()
+                .flat_map(|group| group.children("meaning"))
+                .filter(|meaning| meaning.attribute("m_lang").is_none_or(|lang| lang == "en"))
+                .map(|meaning| meaning.text.clone())
+                .collect(),
+        })
+    }
+
+    /// Returns the number of the classical (Kangxi) radical.
+    pub fn classical_radical(&self) -> Option<u8> {
+        self.radicals
+            .iter()
+            .find(|(rad_type, _)| rad_type == "classical")
+            .map(|(_, radical)| *radical)
+    }
+
+    /// Returns the JLPT and school grade tags of the kanji, named like the tags of the Yomitan
+    /// dictionaries.
+    pub fn tags(&self) -> HashSet<String> {
+        self.jlpt
+            .map(|jlpt| format!("JLPT-N{}", jlpt))
+            .into_iter()
+            .chain(self.grade.map(|grade| format!("Grade-{}", grade)))
+            .collect()
+    }
+}
+
+impl ConvertableJmnedicData for KanjidicCharacter {
+    fn convert_kanji_data(&self, kanji: &mut HashMap<char, Kanji>) -> Result<(), String> {
+        let mut entry = Kanji::new(
+            self.literal,
+            self.onyomi.iter().cloned().collect(),
+            self.kunyomi.iter().cloned().collect(),
+            self.meanings.clone(),
+            self.stroke_counts.first().copied(),
+            self.tags(),
+        );
+        entry.nanori = self.nanori.iter().cloned().collect();
+        entry.radical = self.classical_radical();
+        entry.variants = self
+            .variants
+            .iter()
+            .map(|(var_type, code)| format!("{} {}", var_type, code))
+            .collect();
+
+        kanji.insert(self.literal, entry);
+
+        Ok(())
+    }
+
+    fn convert_word_data(
+        &self,
+        _: &mut HashMap<(String, String), Word>,
+        _: &HashMap<char, HashSet<String>>,
+    ) -> Result<(), String> {
+        Ok(())
+    }
+}
@@ -0,0 +1,304 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use encoding_rs::EUC_JP;
+
+use crate::{
+    dict::{dict_parser::ConvertableJmnedicData, progress::Progress},
+    entry::{Kanji, Word},
+};
+
+/// A line of `KRADFILE`: a kanji and the components it is made of, e.g. `明 : 日 月`.
+///
+/// Components that are no kanji themselves are written as a kanji containing them, e.g. `化`
+/// for the person radical `亻`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KradEntry {
+    /// The kanji.
+    pub kanji: char,
+    /// The components of the kanji.
+    pub components: Vec<char>,
+}
+
+/// A radical of `RADKFILE` and the kanji containing it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RadkEntry {
+    /// The radical, written like the components of [`KradEntry`].
+    pub radical: char,
+    /// The stroke count of the radical.
+    pub strokes: u8,
+    /// The kanji containing the radical.
+    pub kanji: Vec<char>,
+}
+
+/// A tab separated line of ideographic description sequence data, e.g. `U+660E 明 ⿰日月`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IdsEntry {
+    /// The kanji.
+    pub kanji: char,
+    /// The first description sequence of the kanji without its region tags.
+    pub ids: String,
+}
+
+/// Represents an entry of the kanji component files, `KRADFILE`, `RADKFILE` or IDS data.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ComponentEntry {
+    /// A kanji of `KRADFILE`.
+    Krad(KradEntry),
+    /// A radical of `RADKFILE`.
+    Radk(RadkEntry),
+    /// A kanji of IDS data.
+    Ids(IdsEntry),
+}
+
+impl ConvertableJmnedicData for ComponentEntry {
+    /// Adds the components to the kanji already known. Unknown kanji are skipped.
+    fn convert_kanji_data(&self, kanji: &mut HashMap<char, Kanji>) -> Result<(), String> {
+        match self {
+            ComponentEntry::Krad(entry) => {
+                if let Some(kanji) = kanji.get_mut(&entry.kanji) {
+                    for component in &entry.components {
+                        kanji.add_component(*component);
+                    }
+                }
+            }
+            ComponentEntry::Radk(entry) => {
+                for c in &entry.kanji {
+                    if let Some(kanji) = kanji.get_mut(c) {
+                        kanji.add_component(entry.radical);
+                    }
+                }
+            }
+            ComponentEntry::Ids(entry) => {
+                if let Some(kanji) = kanji.get_mut(&entry.kanji) {
+                    kanji.ids = Some(entry.ids.clone());
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn convert_word_data(
+        &self,
+        _: &mut HashMap<(String, String), Word>,
+        _: &HashMap<char, HashSet<String>>,
+    ) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+/// Decodes a component file, which is UTF-8 or, like the EDRDG originals, EUC-JP.
+pub fn decode(data: &[u8]) -> String {
+    match std::str::from_utf8(data) {
+        Ok(data) => data.to_owned(),
+        Err(_) => EUC_JP.decode(data).0.into_owned(),
+    }
+}
+
+/// Parses the entries of a component file. The format is recognized line by line, so the
+/// `KRADFILE`, `RADKFILE` and IDS formats can all be read.
+///
+/// # Example
+/// ```
+/// use anki_utill::dict::edrdg::edrdg_radicals::{parse_components, ComponentEntry, KradEntry};
+///
+/// let entries = parse_components("# comment\n明 : 日 月\n");
+///
+/// assert_eq!(
+///     entries,
+///     vec![ComponentEntry::Krad(KradEntry {
+///         kanji: '明',
+///         components: vec!['日', '月'],
+///     })]
+/// );
+/// ```
+pub fn parse_components(data: &str) -> Vec<ComponentEntry> {
+    let mut out = Vec::new();
+
+    for line in data.lines() {
+        let line = line.trim_end();
+        if line.is_empty() || line.starts_with('#') || line.starts_with(";;") {
+            continue;
+        }
+
+        // A radical of RADKFILE, followed by lines of the kanji containing it
+        if let Some(radical) = line.strip_prefix("$ ") {
+            let mut fields = radical.split_whitespace();
+            let (Some(radical), Some(strokes)) = (fields.next(), fields.next()) else {
+                continue;
+            };
+
+            if let (Some(radical), Ok(strokes)) = (radical.chars().next(), strokes.parse()) {
+                out.push(ComponentEntry::Radk(RadkEntry {
+                    radical,
+                    strokes,
+                    kanji: Vec::new(),
+                }));
+            }
+            continue;
+        }
+
+        if let Some((kanji, components)) = line.split_once(" : ") {
+            if let Some(kanji) = kanji.chars().next() {
+                out.push(ComponentEntry::Krad(KradEntry {
+                    kanji,
+                    components: components
+                        .split_whitespace()
+                        .filter_map(|c| c.chars().next())
+                        .collect(),
+                }));
+            }
+            continue;
+        }
+
+        if line.starts_with("U+") {
+            let mut fields = line.split('\t').skip(1);
+            if let (Some(kanji), Some(ids)) =
+                (fields.next().and_then(|k| k.chars().next()), fields.next())
+            {
+                let ids = ids.split_once('[').map_or(ids, |(ids, _)| ids);
+                out.push(ComponentEntry::Ids(IdsEntry {
+                    kanji,
+                    ids: ids.to_owned(),
+                }));
+            }
+            continue;
+        }
+
+        if let Some(ComponentEntry::Radk(entry)) = out.last_mut() {
+            entry
+                .kanji
+                .extend(line.chars().filter(|c| !c.is_whitespace()));
+        }
+    }
+
+    out
+}
+
+/// Returns the files of a directory sorted by name, none if the directory does not exist.
+pub fn component_files(path: &Path) -> io::Result<Vec<PathBuf>> {
+    if !path.is_dir() {
+        return Ok(Vec::new());
+    }
+
+    let mut out = Vec::new();
+    for path in fs::read_dir(path)? {
+        let path = path?.path();
+
+        if path.is_file() {
+            out.push(path);
+        }
+    }
+
+    out.sort_unstable();
+
+    Ok(out)
+}
+
+/// Adds the components of the kanji component files to the kanji, see [`ComponentEntry`].
+///
+/// # Arguments
+/// * `paths` - The component files
+/// * `kanji` - The HashMap collecting Kanji information
+/// * `progress` - Receives the number of files converted
+pub fn convert_component_kanji(
+    paths: &[PathBuf],
+    kanji: &mut HashMap<char, Kanji>,
+    progress: &mut dyn Progress,
+) -> io::Result<()> {
+    progress.start("Converting kanji components", paths.len());
+
+    for (count, path) in paths.iter().enumerate() {
+        for entry in parse_components(&decode(&fs::read(path)?)) {
+            let _ = entry.convert_kanji_data(kanji);
+        }
+
+        progress.advance(count + 1);
+    }
+
+    progress.finish();
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::entry::kanji_by_component;
+
+    use super::*;
+
+    /// Tests the parsing of the three formats and attaching their components to kanji.
+    #[test]
+    fn components() {
+        let data = "\
+# KRADFILE
+明 : 日 月
+$ 日 4
+明間
+$ 門 8 js01
+間
+;; IDS
+U+660E\t明\t⿰日月
+U+9593\t間\t⿵門日[GTJ]\t⿵門月[K]
+";
+        let entries = parse_components(data);
+
+        assert_eq!(entries.len(), 5);
+        assert_eq!(
+            entries[1],
+            ComponentEntry::Radk(RadkEntry {
+                radical: '日',
+                strokes: 4,
+                kanji: vec!['明', '間'],
+            })
+        );
+        assert_eq!(
+            entries[4],
+            ComponentEntry::Ids(IdsEntry {
+                kanji: '間',
+                ids: "⿵門日".to_owned(),
+            })
+        );
+
+        let mut kanji: HashMap<char, Kanji> = "明間日"
+            .chars()
+            .map(|c| {
+                let kanji = Kanji::new(
+                    c,
+                    HashSet::new(),
+                    HashSet::new(),
+                    Vec::new(),
+                    None,
+                    HashSet::new(),
+                );
+                (c, kanji)
+            })
+            .collect();
+        for entry in &entries {
+            entry.convert_kanji_data(&mut kanji).unwrap();
+        }
+
+        assert_eq!(kanji[&'明'].components, vec!['日', '月']);
+        assert_eq!(kanji[&'明'].ids.as_deref(), Some("⿰日月"));
+        assert_eq!(kanji[&'間'].components, vec!['日', '門']);
+        assert_eq!(kanji[&'間'].parts(), vec!['門', '日']);
+        assert!(kanji[&'日'].components.is_empty());
+
+        let lookup = kanji_by_component(&kanji);
+        assert_eq!(lookup[&'日'], vec!['明', '間']);
+        assert_eq!(lookup[&'門'], vec!['間']);
+    }
+
+    /// Tests that EUC-JP files are decoded.
+    #[test]
+    fn decode_euc_jp() {
+        let (data, _, _) = EUC_JP.encode("明 : 日 月\n");
+
+        assert_eq!(decode(&data), "明 : 日 月\n");
+        assert_eq!(decode("間 : 門 日".as_bytes()), "間 : 門 日");
+    }
+}
@@ -0,0 +1,151 @@
+use std::{collections::HashMap, io::BufRead};
+
+use quick_xml::{
+    events::{BytesStart, BytesText, Event},
+    Reader,
+};
+
+/// An XML element read into memory with its attributes, text and child elements.
+///
+/// The EDRDG files are read one entry at a time, so only a single entry is held as elements.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Element {
+    /// The name of the element.
+    pub name: String,
+    /// The attributes of the element.
+    pub attributes: HashMap<String, String>,
+    /// The text directly inside the element. An entity reference like `&n;` is kept as the name
+    /// of the entity, `n`, which is the tag the EDRDG files use it for.
+    pub text: String,
+    /// The child elements, in order.
+    pub children: Vec<Element>,
+}
+
+impl Element {
+    /// Returns the first child element with a name.
+    pub fn child(&self, name: &str) -> Option<&Element> {
+        self.children.iter().find(|child| child.name == name)
+    }
+
+    /// Returns every child element with a name.
+    pub fn children<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Element> {
+        self.children.iter().filter(move |child| child.name == name)
+    }
+
+    /// Returns the text of the first child element with a name.
+    pub fn text_of(&self, name: &str) -> Option<&str> {
+        self.child(name).map(|child| child.text.as_str())
+    }
+
+    /// Returns the text of every child element with a name.
+    pub fn texts_of(&self, name: &str) -> Vec<String> {
+        self.children(name)
+            .map(|child| child.text.clone())
+            .collect()
+    }
+
+    /// Returns the value of an attribute.
+    pub fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes.get(name).map(|value| value.as_str())
+    }
+
+    /// Creates an element without content from its start tag.
+    fn from_start(start: &BytesStart) -> Self {
+        Self {
+            name: String::from_utf8_lossy(start.name().as_ref()).into_owned(),
+            attributes: start
+                .attributes()
+                .flatten()
+                .map(|attribute| {
+                    let value = attribute
+                        .unescape_value()
+                        .map(|value| value.into_owned())
+                        .unwrap_or_else(|_| String::from_utf8_lossy(&attribute.value).into_owned());
+
+                    (
+                        String::from_utf8_lossy(attribute.key.as_ref()).into_owned(),
+                        value,
+                    )
+                })
+                .collect(),
+            ..Self::default()
+        }
+    }
+}
+
+/// Reads the content of an element whose start tag was just read, up to its end tag.
+pub fn read_element<R>(reader: &mut Reader<R>, start: &BytesStart) -> quick_xml::Result<Element>
+where
+    R: BufRead,
+{
+    let mut element = Element::from_start(start);
+    let mut buf = Vec::new();
+
+    loop {
+        match reader.read_event_into(&mut buf)? {
+            Event::Start(start) => {
+                let start = start.into_owned();
+                element.children.push(read_element(reader, &start)?);
+            }
+            Event::Empty(start) => element.children.push(Element::from_start(&start)),
+            Event::Text(text) => element.text += &text_content(&text),
+            Event::CData(data) => element.text += &String::from_utf8_lossy(&data),
+            Event::End(_) => return Ok(element),
+            Event::Eof => {
+                return Err(quick_xml::Error::Syntax(
+                    quick_xml::errors::SyntaxError::UnclosedTag,
+                ))
+            }
+            _ => {}
+        }
+
+        buf.clear();
+    }
+}
+
+/// Returns the text of a text event, with an entity reference like `&n;` as its name.
+pub fn text_content(text: &BytesText) -> String {
+    let raw = String::from_utf8_lossy(text);
+
+    if let Some(entity) = raw.strip_prefix('&').and_then(|raw| raw.strip_suffix(';')) {
+        if !entity.is_empty() && !entity.contains(['&', ';']) && !entity.starts_with('#') {
+            return entity.to_owned();
+        }
+    }
+
+    text.unescape()
+        .map(|text| text.into_owned())
+        .unwrap_or_else(|_| raw.into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tests reading nested elements, attributes and entity references.
+    #[test]
+    fn read_elements() {
+        let mut reader = Reader::from_reader(
+            r#"<entry><pos>&v5k;</pos><re_nokanji/><gloss g_type="lit">a &amp; b</gloss></entry>"#
+                .as_bytes(),
+        );
+        reader.config_mut().trim_text(true);
+
+        let mut buf = Vec::new();
+        let Event::Start(start) = reader.read_event_into(&mut buf).unwrap() else {
+            panic!("expected a start tag");
+        };
+        let start = start.into_owned();
+        let entry = read_element(&mut reader, &start).unwrap();
+
+        assert_eq!(entry.name, "entry");
+        assert_eq!(entry.text_of("pos"), Some("v5k"));
+        assert!(entry.child("re_nokanji").is_some());
+        assert_eq!(entry.texts_of("gloss"), vec!["a & b"]);
+        assert_eq!(
+            entry.child("gloss").unwrap().attribute("g_type"),
+            Some("lit")
+        );
+        assert_eq!(entry.text_of("ke_inf"), None);
+    }
+}
@@ -0,0 +1,5 @@
+pub mod edrdg_entry;
+pub mod edrdg_jmdict;
+pub mod edrdg_kanjidic;
+pub mod edrdg_radicals;
+pub mod edrdg_xml;
@@ -7,7 +7,7 @@ use crate::{
     entry::Word,
 };
 
-use super::{jmnedict_frequency, jmnedict_word};
+use super::{jmnedict_frequency, jmnedict_pitch, jmnedict_word};
 
 /// Represents an entry containing information about various aspects of the Japanese language,
 /// including words, kanji characters, and their associated properties.
@@ -24,6 +24,10 @@ pub enum JmnedictEntry {
     /// its default frequency value and additional metadata properties.
     Frequency(jmnedict_frequency::JmnedictFrequency),
 
+    /// A pitch accent entry that includes the kanji form of a word along with
+    /// the accent patterns of one of its readings.
+    Pitch(jmnedict_pitch::JmnedictPitch),
+
     /// A kanji entry that provides detailed information about a single kanji character,
     /// including its various readings (both onyomi and kunyomi), its frequency or dictionary
     /// information, meanings in English, and additional metadata stored in a HashMap.
@@ -44,6 +48,7 @@ impl ConvertableJmnedicData for JmnedictEntry {
             JmnedictEntry::Frequency(jmnedict_frequency) => {
                 jmnedict_frequency.convert_kanji_data(kanji)
             }
+            JmnedictEntry::Pitch(jmnedict_pitch) => jmnedict_pitch.convert_kanji_data(kanji),
             JmnedictEntry::Kanji(kanjidic_entry) => kanjidic_entry.convert_kanji_data(kanji),
             JmnedictEntry::Unknown(_) => Err("Unknown value".to_owned()),
         }
@@ -61,6 +66,9 @@ impl ConvertableJmnedicData for JmnedictEntry {
             JmnedictEntry::Frequency(jmnedict_frequency) => {
                 jmnedict_frequency.convert_word_data(words, kanji_readings)
             }
+            JmnedictEntry::Pitch(jmnedict_pitch) => {
+                jmnedict_pitch.convert_word_data(words, kanji_readings)
+            }
             JmnedictEntry::Kanji(kanjidic_entry) => {
                 kanjidic_entry.convert_word_data(words, kanji_readings)
             }
@@ -75,4 +83,20 @@ impl ConvertableJmnedicData for JmnedictEntry {
             }
         }
     }
+
+    fn word_keys(&self) -> Vec<(String, String)> {
+        match self {
+            JmnedictEntry::Word(jmnedict_word) => jmnedict_word.word_keys(),
+            JmnedictEntry::Frequency(jmnedict_frequency) => jmnedict_frequency.word_keys(),
+            JmnedictEntry::Pitch(jmnedict_pitch) => jmnedict_pitch.word_keys(),
+            JmnedictEntry::Kanji(_) | JmnedictEntry::Unknown(_) => Vec::new(),
+        }
+    }
+
+    fn pitch(&self) -> Option<((String, String), Vec<u8>)> {
+        match self {
+            JmnedictEntry::Pitch(jmnedict_pitch) => jmnedict_pitch.pitch(),
+            _ => None,
+        }
+    }
 }
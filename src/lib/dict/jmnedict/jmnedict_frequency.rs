@@ -121,6 +121,10 @@ impl ConvertableJmnedicData for JmnedictFrequency {
 
         Ok(())
     }
+
+    fn word_keys(&self) -> Vec<(String, String)> {
+        vec![(self.kanji().to_owned(), self.kana().to_owned())]
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,182 @@
+use std::collections::{HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    dict::dict_parser::ConvertableJmnedicData,
+    entry::{Kanji, Word},
+};
+
+/// Represents a pitch accent entry of a Yomitan `term_meta_bank`, listing the accent patterns of
+/// one reading of a word.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct JmnedictPitch(
+    /// The word represented in kanji characters.
+    String,
+    /// Default value `pitch`
+    String,
+    /// The reading and its accent patterns.
+    PitchProperties,
+);
+
+impl JmnedictPitch {
+    /// Returns the word in kanji for this pitch entry.
+    pub fn kanji(&self) -> &str {
+        &self.0
+    }
+
+    /// Returns the reading of the word in kana.
+    pub fn kana(&self) -> &str {
+        &self.2.reading
+    }
+
+    /// Returns the mora after which the pitch drops for every accent pattern, where 0 means the
+    /// pitch never drops. Patterns that are not valid are left out.
+    pub fn positions(&self) -> Vec<u8> {
+        self.2
+            .pitches
+            .iter()
+            .filter_map(|pitch| pitch.position.downstep())
+            .collect()
+    }
+}
+
+/// Contains the reading of a word and its accent patterns.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+struct PitchProperties {
+    reading: String,
+    pitches: Vec<PitchData>,
+}
+
+/// Represents a single accent pattern.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+struct PitchData {
+    /// The position of the downstep or the pitch of every mora.
+    position: PitchPosition,
+}
+
+/// The accent pattern of a Yomitan pitch entry, either as a downstep position or as the high and
+/// low pitch of every mora.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(untagged)]
+enum PitchPosition {
+    /// The mora after which the pitch drops, or 0 if it never drops.
+    Downstep(u8),
+    /// The pitch of every mora, e.g. `LHL`, optionally followed by the pitch of a particle.
+    Pattern(String),
+}
+
+impl PitchPosition {
+    /// Returns the mora after which the pitch drops, or 0 if it never drops.
+    ///
+    /// # Returns
+    /// * The position, or `None` for a pattern that is not made of `H` and `L`.
+    fn downstep(&self) -> Option<u8> {
+        let pattern = match self {
+            PitchPosition::Downstep(position) => return Some(*position),
+            PitchPosition::Pattern(pattern) => pattern.to_uppercase(),
+        };
+        if pattern.is_empty() || pattern.chars().any(|c| c != 'H' && c != 'L') {
+            return None;
+        }
+
+        // The pitch drops after the first high mora followed by a low one
+        let position = pattern
+            .as_bytes()
+            .windows(2)
+            .position(|pair| pair == b"HL")
+            .map_or(0, |i| i + 1);
+
+        u8::try_from(position).ok()
+    }
+}
+
+impl ConvertableJmnedicData for JmnedictPitch {
+    fn convert_kanji_data(&self, _: &mut HashMap<char, Kanji>) -> Result<(), String> {
+        Ok(())
+    }
+
+    /// Pitch entries only describe words of term entries, see [`JmnedictPitch::pitch`].
+    fn convert_word_data(
+        &self,
+        _: &mut HashMap<(String, String), Word>,
+        _: &HashMap<char, HashSet<String>>,
+    ) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn pitch(&self) -> Option<((String, String), Vec<u8>)> {
+        let key = (self.kanji().to_owned(), self.kana().to_owned());
+        Some((key, self.positions()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::dict::{dict_parser::WordConverter, jmnedict::jmnedict_entry::JmnedictEntry};
+
+    use super::*;
+
+    /// Tests parsing pitch entries and attaching every pattern to the word, even when the pitch
+    /// entries come before the term entry.
+    #[test]
+    fn pitch() {
+        let data: Vec<JmnedictPitch> = serde_json::from_str(TEST_DATA).unwrap();
+
+        assert_eq!(data[0].kanji(), "箸");
+        assert_eq!(data[0].kana(), "はし");
+        assert_eq!(data[0].positions(), vec![1]);
+        assert_eq!(data[1].positions(), vec![0, 3]);
+        assert_eq!(data[2].positions(), vec![2, 1, 0]);
+
+        let mut words = WordConverter::new(&HashMap::new());
+        for entry in data.iter().chain(data.iter()) {
+            words.add(entry, None).unwrap();
+        }
+
+        let term: JmnedictEntry =
+            serde_json::from_str(r#"["昨日", "きのう", "n", "", 1, ["yesterday"], 1, ""]"#)
+                .unwrap();
+        words.add(&term, None).unwrap();
+
+        // Only words of term entries get a pitch
+        let words = words.finish();
+        assert_eq!(words.len(), 1);
+        assert_eq!(words["昨日[きのう]"].pitch, vec![0, 3]);
+    }
+
+    /// The JSON data used for testing parsing of pitch entries.
+    const TEST_DATA: &str = r#"
+[
+    [
+        "箸",
+        "pitch",
+        {
+            "reading": "はし",
+            "pitches": [{ "position": 1 }]
+        }
+    ],
+    [
+        "昨日",
+        "pitch",
+        {
+            "reading": "きのう",
+            "pitches": [{ "position": 0 }, { "position": 3, "tags": ["副"] }]
+        }
+    ],
+    [
+        "橋",
+        "pitch",
+        {
+            "reading": "はし",
+            "pitches": [
+                { "position": "LHL" },
+                { "position": "HL" },
+                { "position": "LHH" },
+                { "position": "LXH" }
+            ]
+        }
+    ]
+]
+"#;
+}
@@ -506,6 +506,10 @@ impl ConvertableJmnedicData for JmnedictWord {
 
         Ok(())
     }
+
+    fn word_keys(&self) -> Vec<(String, String)> {
+        vec![(self.kanji().to_owned(), self.kana().to_owned())]
+    }
 }
 
 #[cfg(test)]
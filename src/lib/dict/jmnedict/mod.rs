@@ -1,3 +1,4 @@
 pub mod jmnedict_entry;
 pub mod jmnedict_frequency;
+pub mod jmnedict_pitch;
 pub mod jmnedict_word;
@@ -87,17 +87,24 @@ impl KanjidicEntry {
         self.5.get("jlpt")?.parse::<u8>().ok()
     }
 
-    /// Returns the tags associated with the kanji.
+    /// Returns the school grade the kanji is taught in, if available.
+    pub fn grade(&self) -> Option<u8> {
+        self.5.get("grade")?.parse::<u8>().ok()
+    }
+
+    /// Returns the JLPT and school grade tags associated with the kanji.
     pub fn tags(&self) -> HashSet<String> {
-        if let Some(jlpt) = self.jlpt() {
-            let mut out: HashSet<String> = HashSet::new();
+        let mut out: HashSet<String> = HashSet::new();
 
+        if let Some(jlpt) = self.jlpt() {
             out.insert(format!("JLPT-N{}", jlpt));
+        }
 
-            out
-        } else {
-            HashSet::new()
+        if let Some(grade) = self.grade() {
+            out.insert(format!("Grade-{}", grade));
         }
+
+        out
     }
 }
 
@@ -126,7 +133,6 @@ impl ConvertableJmnedicData for KanjidicEntry {
                 self.strokes(),
                 self.tags()
                     .iter()
-                    .cloned()
                     .map(|meaning| meaning.to_owned())
                     .collect(),
             ),
@@ -1,4 +1,8 @@
+pub mod dict_cache;
+pub mod dict_index;
 pub mod dict_parser;
+pub mod edrdg;
 pub mod jitendex;
 pub mod jmnedict;
 pub mod kanjidic;
+pub mod progress;
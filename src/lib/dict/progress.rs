@@ -0,0 +1,74 @@
+/// Receives the progress of parsing and converting dictionaries, e.g. to draw a progress bar or
+/// write a log. Every method does nothing by default.
+///
+/// # Example
+/// ```
+/// use anki_utill::dict::progress::Progress;
+///
+/// /// Counts the finished stages.
+/// #[derive(Default)]
+/// struct Stages(usize);
+///
+/// impl Progress for Stages {
+///     fn finish(&mut self) {
+///         self.0 += 1;
+///     }
+/// }
+/// ```
+pub trait Progress {
+    /// Called when a stage starts with the number of steps it takes.
+    fn start(&mut self, _stage: &str, _total: usize) {}
+
+    /// Called when `done` of the steps of the current stage are done.
+    fn advance(&mut self, _done: usize) {}
+
+    /// Called with a message about the current stage, e.g. the file being read.
+    fn message(&mut self, _message: &str) {}
+
+    /// Called when the current stage ends.
+    fn finish(&mut self) {}
+}
+
+/// Reports nothing.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoProgress;
+
+impl Progress for NoProgress {}
+
+/// Prints the progress of every stage in steps of 5%.
+#[derive(Debug, Clone, Default)]
+pub struct PrintProgress {
+    /// The name of the current stage.
+    stage: String,
+    /// The number of steps of the current stage.
+    total: usize,
+    /// The last printed step of 5%.
+    printed: Option<usize>,
+}
+
+impl Progress for PrintProgress {
+    fn start(&mut self, stage: &str, total: usize) {
+        println!("{}:", stage);
+
+        self.stage = stage.to_lowercase();
+        self.total = total;
+        self.printed = None;
+    }
+
+    fn advance(&mut self, done: usize) {
+        let percent = (done * 100).checked_div(self.total).unwrap_or(100);
+
+        if self.printed != Some(percent / 5) {
+            println!("  {:>3}% {}", percent, self.stage);
+            self.printed = Some(percent / 5);
+        }
+    }
+
+    fn message(&mut self, message: &str) {
+        println!("  {}", message);
+    }
+
+    fn finish(&mut self) {
+        println!();
+    }
+}
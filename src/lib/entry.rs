@@ -1,22 +1,57 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
 use serde::{Deserialize, Serialize};
 
-use crate::japanese::{split_kanji_reading, JapaneseStr};
+use crate::{
+    dict::dict_index::{Tag, TagBank},
+    japanese::{conjugate, split_kanji_reading, JapaneseStr, WordClass},
+    pitch::{pitch_html, pitch_notation},
+    romaji::{to_romaji_verb, RomajiSystem},
+};
+
+/// The version of the [`Word`] schema written by this crate. Words of older versions are
+/// upgraded by [`Word::migrate`].
+pub const WORD_VERSION: u32 = 1;
+
+/// Parses a word list, upgrading words written with an older schema.
+pub fn parse_wordlist(data: &str) -> serde_json::Result<HashMap<String, Word>> {
+    let mut words: HashMap<String, Word> = serde_json::from_str(data)?;
+
+    for word in words.values_mut() {
+        word.migrate();
+    }
+
+    Ok(words)
+}
 
 /// Represents a Japanese word with its associated data.
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 pub struct Word {
+    /// Version of the schema the word was written with, 0 for words written before it had one
+    #[serde(default)]
+    pub version: u32,
     /// Unique identifier for the word
     pub word_id: i32,
     /// Furigana representation of the word (kanji + kana in furigana format)
     pub furigana: String,
-    /// List of glossary entries containing meaning and tags
+    /// Every kanji spelling of the word's entry
+    #[serde(default)]
+    pub forms: Vec<KanjiForm>,
+    /// Every kana reading of the word's entry
+    #[serde(default)]
+    pub readings: Vec<Reading>,
+    /// List of glossary entries, the senses of the word, containing meaning and tags
     pub glossary: Vec<Glossary>,
     /// Set of frequency tags associated with the word
     pub frequency: HashSet<String>,
     /// Set of example sentences
     pub examples: HashSet<Example>,
+    /// Downstep positions of every accent pattern of the reading, 0 for heiban
+    #[serde(default)]
+    pub pitch: Vec<u8>,
+    /// The dictionaries the word was taken from
+    #[serde(default)]
+    pub sources: HashSet<Source>,
 }
 
 impl Word {
@@ -28,15 +63,117 @@ impl Word {
         frequency: HashSet<String>,
         examples: HashSet<Example>,
     ) -> Self {
+        let kanji = furigana.to_kanji();
+        let kana = furigana.to_kana();
+
         Self {
+            version: WORD_VERSION,
             word_id,
+            forms: if kanji != kana {
+                vec![KanjiForm::new(kanji)]
+            } else {
+                Vec::new()
+            },
+            readings: vec![Reading::new(kana)],
             furigana,
             glossary,
             frequency,
             examples,
+            pitch: Vec::new(),
+            sources: HashSet::new(),
+        }
+    }
+
+    /// Upgrades a word written with an older schema to [`WORD_VERSION`]:
+    /// - Takes the kanji form and reading from the furigana
+    /// - Sorts the glossary tags into their kinds
+    pub fn migrate(&mut self) {
+        if self.version >= WORD_VERSION {
+            return;
+        }
+
+        if self.readings.is_empty() {
+            let Word {
+                forms, readings, ..
+            } = Word::new(
+                0,
+                self.furigana.clone(),
+                Vec::new(),
+                HashSet::new(),
+                HashSet::new(),
+            );
+
+            self.forms = forms;
+            self.readings = readings;
+        }
+
+        for glossary in self.glossary.iter_mut() {
+            glossary.classify_tags();
+        }
+
+        self.version = WORD_VERSION;
+    }
+
+    /// Adds the kanji forms and readings the word does not have yet, keeping their order.
+    pub fn extend_forms(&mut self, forms: &[KanjiForm], readings: &[Reading]) {
+        for form in forms {
+            if !self.forms.iter().any(|f| f.text == form.text) {
+                self.forms.push(form.clone());
+            }
+        }
+
+        for reading in readings {
+            if !self.readings.iter().any(|r| r.text == reading.text) {
+                self.readings.push(reading.clone());
+            }
         }
     }
 
+    /// Returns whether the main sense of the word is usually written in kana alone.
+    pub fn usually_kana(&self) -> bool {
+        self.glossary
+            .iter()
+            .find(|gloss| !gloss.tags.contains("forms"))
+            .is_some_and(|gloss| gloss.misc.contains("uk"))
+    }
+
+    /// Returns the reading of the word among its readings, `None` if it is not known.
+    pub fn reading(&self) -> Option<&Reading> {
+        let kana = self.furigana.to_kana();
+
+        self.readings.iter().find(|reading| reading.text == kana)
+    }
+
+    /// Returns the other kanji spellings of the word, leaving out search-only forms and the
+    /// forms its reading does not apply to.
+    pub fn other_forms(&self) -> Vec<&str> {
+        let kanji = self.furigana.to_kanji();
+        let reading = self.reading();
+
+        self.forms
+            .iter()
+            .filter(|form| form.text != kanji && !form.info.contains("sK"))
+            .filter(|form| reading.is_none_or(|reading| reading.applies_to(&form.text)))
+            .map(|form| form.text.as_str())
+            .collect()
+    }
+
+    /// Returns usage notes about the word, e.g. that it is usually written in kana.
+    pub fn notes(&self) -> Vec<String> {
+        let mut out = Vec::new();
+
+        if self.usually_kana() && self.furigana.to_kanji() != self.furigana.to_kana() {
+            out.push("Usually written in kana".to_owned());
+        }
+
+        let other_forms = self.other_forms();
+        if !other_forms.is_empty() {
+            out.push(format!("Also written {}", other_forms.join("、")));
+        }
+
+        out
+    }
+
     /// Returns a set of all tags from the word's glossary entries.
     ///
     /// # Description
@@ -58,26 +195,392 @@ impl Word {
 
         out
     }
+
+    /// Returns the definitions of the word's tags, sorted by category and order.
+    pub fn tag_info<'a>(&self, tags: &'a TagBank) -> Vec<&'a Tag> {
+        tags.resolve(self.get_all_tags())
+    }
+
+    /// Constructs the meaning field of a note by:
+    /// - Formatting glossary entries with their tags
+    /// - Adding a separator between multiple entries
+    /// - Highlighting tags in square brackets
+    ///
+    /// Glossary entries tagged with `forms` are skipped. The notes of the word come first, in
+    /// italics.
+    pub fn meaning_html(&self) -> String {
+        let mut output: String = self
+            .notes()
+            .iter()
+            .map(|note| format!("<i>{}</i><br>", note))
+            .collect();
+        let mut previus_tags: HashSet<String> = HashSet::new();
+
+        for (i, glossary) in self
+            .glossary
+            .iter()
+            .filter(|gloss| !gloss.tags.contains("forms"))
+            .enumerate()
+        {
+            if i != 0 {
+                output += "<br>";
+            }
+
+            let meaning = glossary.meaning.join(" | ");
+
+            // Add tags if they are new or not empty
+            if glossary.tags.is_empty() || glossary.tags.iter().all(|k| previus_tags.contains(k)) {
+                output += &meaning;
+                continue;
+            }
+
+            let mut tags: Vec<&str> = glossary.tags.iter().map(|t| t.as_str()).collect();
+            tags.sort_unstable();
+
+            output += &format!("[ {} ] {}", tags.join(" "), meaning);
+
+            previus_tags = glossary.tags.clone();
+        }
+
+        output
+    }
+
+    /// Constructs the meaning field like [`meaning_html`](Self::meaning_html), followed by the
+    /// descriptions of the word's tags found in the tag bank.
+    pub fn meaning_html_with_tags(&self, tags: &TagBank) -> String {
+        with_tag_notes(self.meaning_html(), self.tag_info(tags))
+    }
+
+    /// Returns the reading of the word in modified Hepburn romaji. The ending of a verb is kept
+    /// apart from the vowel before it, e.g. `omou` for 思う.
+    pub fn romaji(&self) -> String {
+        let kana = self.furigana.to_kana();
+
+        match WordClass::from_tags(self.get_all_tags()) {
+            Some(class) if class.is_verb() => to_romaji_verb(&kana, RomajiSystem::Hepburn),
+            _ => kana.to_romaji(),
+        }
+    }
+
+    /// Constructs the pitch accent field of a note as HTML pitch graphs, one for every accent
+    /// pattern.
+    pub fn pitch_html(&self) -> String {
+        let kana = self.furigana.to_kana();
+
+        self.pitch
+            .iter()
+            .map(|&position| pitch_html(&kana, position))
+            .collect::<Vec<_>>()
+            .join("<br>")
+    }
+
+    /// Constructs the conjugations field of a note as an HTML table of the inflected forms of the
+    /// word, in furigana format. Empty if the tags of the word give no inflection class.
+    pub fn conjugations_html(&self) -> String {
+        let Some(table) = WordClass::from_tags(self.get_all_tags())
+            .and_then(|class| conjugate(&self.furigana, class))
+        else {
+            return String::new();
+        };
+
+        let rows: String = table
+            .iter()
+            .map(|conjugation| {
+                format!(
+                    "<tr><td>{}</td><td>{}</td></tr>",
+                    conjugation.name, conjugation.form
+                )
+            })
+            .collect();
+
+        format!("<table>{}</table>", rows)
+    }
+
+    /// Returns every accent pattern of the word in bracketed notation, e.g. `はꜜし [1]`.
+    pub fn pitch_notation(&self) -> String {
+        let kana = self.furigana.to_kana();
+
+        self.pitch
+            .iter()
+            .map(|&position| pitch_notation(&kana, position))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// Constructs the example sentences field of a note by:
+    /// - Formatting Japanese-English example pairs
+    /// - Separating examples with line breaks
+    pub fn examples_html(&self) -> String {
+        self.examples
+            .iter()
+            .filter_map(|example| {
+                match (!example.japanese.is_empty(), !example.english.is_empty()) {
+                    (true, true) => Some(format!("{}<br>{}", example.japanese, example.english)),
+                    (true, false) => Some(example.japanese.clone()),
+                    _ => None,
+                }
+            })
+            .reduce(|a, b| a + "<br><br>" + &b)
+            .unwrap_or("".to_owned())
+    }
+}
+
+/// Represents a kanji spelling of a word.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct KanjiForm {
+    /// The word written with kanji
+    pub text: String,
+    /// Information tags about the spelling, e.g. `iK` for irregular kanji
+    pub info: HashSet<String>,
+    /// Priority codes of the spelling, e.g. `news1`
+    pub priority: HashSet<String>,
+}
+
+impl KanjiForm {
+    /// Creates a new KanjiForm without tags.
+    pub fn new(text: String) -> Self {
+        Self {
+            text,
+            info: HashSet::new(),
+            priority: HashSet::new(),
+        }
+    }
+}
+
+/// Represents a kana reading of a word.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Reading {
+    /// The reading in kana
+    pub text: String,
+    /// Whether the reading is not a true reading of the kanji forms
+    pub no_kanji: bool,
+    /// The kanji forms the reading applies to, all forms if empty
+    pub restrictions: Vec<String>,
+    /// Information tags about the reading, e.g. `ok` for outdated kana
+    pub info: HashSet<String>,
+    /// Priority codes of the reading, e.g. `news1`
+    pub priority: HashSet<String>,
+}
+
+impl Reading {
+    /// Creates a new Reading of every kanji form without tags.
+    pub fn new(text: String) -> Self {
+        Self {
+            text,
+            no_kanji: false,
+            restrictions: Vec::new(),
+            info: HashSet::new(),
+            priority: HashSet::new(),
+        }
+    }
+
+    /// Returns whether the reading is a reading of a kanji form.
+    pub fn applies_to(&self, form: &str) -> bool {
+        !self.no_kanji
+            && (self.restrictions.is_empty() || self.restrictions.iter().any(|r| r == form))
+    }
+}
+
+/// The kinds of tags of a sense.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TagKind {
+    /// Part of speech, e.g. `n` or `v5く`
+    PartOfSpeech,
+    /// Field of application, e.g. `comp` or `med`
+    Field,
+    /// Miscellaneous, e.g. `uk` or `col`
+    Misc,
+    /// Dialect, e.g. `ksb`
+    Dialect,
+    /// Any other tag, e.g. frequency tags
+    Other,
 }
 
-/// Represents a glossary entry containing meaning and tags.
+impl TagKind {
+    /// Dialect tags of JMdict.
+    const DIALECTS: &[&str] = &[
+        "bra", "hob", "ksb", "ktb", "kyb", "kyu", "nab", "osb", "rkb", "thb", "tsb", "tsug",
+    ];
+
+    /// Field tags of JMdict.
+    const FIELDS: &[&str] = &[
+        "agric", "anat", "archeol", "archit", "art", "astron", "audvid", "aviat", "baseb",
+        "biochem", "biol", "bot", "boxing", "Buddh", "bus", "cards", "chem", "chmyth", "Christn",
+        "civeng", "cloth", "comp", "cryst", "dent", "ecol", "econ", "elec", "electr", "embryo",
+        "engr", "ent", "figskt", "film", "finc", "fish", "food", "gardn", "genet", "geogr", "geol",
+        "geom", "go", "golf", "gramm", "grmyth", "hanaf", "horse", "internet", "jpmyth", "kabuki",
+        "law", "ling", "logic", "MA", "mahj", "manga", "math", "mech", "med", "met", "mil", "min",
+        "mining", "motor", "music", "noh", "ornith", "paleo", "pathol", "pharm", "phil", "photo",
+        "physics", "physiol", "politics", "print", "prof", "psy", "psyanal", "psych", "rail",
+        "rommyth", "Shinto", "shogi", "ski", "sports", "stat", "stockm", "sumo", "surg", "telec",
+        "tradem", "tv", "vet", "vidg", "zool",
+    ];
+
+    /// Miscellaneous tags of JMdict.
+    const MISC: &[&str] = &[
+        "abbr",
+        "aphorism",
+        "arch",
+        "char",
+        "chn",
+        "col",
+        "company",
+        "creat",
+        "dated",
+        "dei",
+        "derog",
+        "doc",
+        "euph",
+        "ev",
+        "fam",
+        "fem",
+        "fict",
+        "form",
+        "given",
+        "group",
+        "hist",
+        "hon",
+        "hum",
+        "id",
+        "joc",
+        "leg",
+        "m-sl",
+        "male",
+        "myth",
+        "net-sl",
+        "obj",
+        "obs",
+        "on-mim",
+        "organization",
+        "oth",
+        "person",
+        "place",
+        "poet",
+        "pol",
+        "product",
+        "proverb",
+        "quote",
+        "rare",
+        "sens",
+        "serv",
+        "ship",
+        "sl",
+        "station",
+        "surname",
+        "uk",
+        "unclass",
+        "vulg",
+        "work",
+        "X",
+        "yoji",
+    ];
+
+    /// Part of speech tags that are not recognized by their prefix.
+    const PARTS_OF_SPEECH: &[&str] = &[
+        "n", "pn", "conj", "cop", "ctr", "exp", "int", "num", "pref", "prt", "suf", "unc", "vi",
+        "vt", "vk", "vn", "vr",
+    ];
+
+    /// Returns the kind of a JMdict tag, also recognizing the part of speech tags renamed by
+    /// [`remap_tag`](crate::dict::jmnedict::jmnedict_word::remap_tag).
+    ///
+    /// # Example
+    /// ```
+    /// use anki_utill::entry::TagKind;
+    ///
+    /// assert_eq!(TagKind::of("v5く"), TagKind::PartOfSpeech);
+    /// assert_eq!(TagKind::of("uk"), TagKind::Misc);
+    /// assert_eq!(TagKind::of("news1k"), TagKind::Other);
+    /// ```
+    pub fn of(tag: &str) -> Self {
+        let is_pos = Self::PARTS_OF_SPEECH.contains(&tag)
+            || [
+                "adj-", "adv", "aux", "n-", "v-", "vs", "vす", "vず", "vく", "vぬ", "vり",
+            ]
+            .iter()
+            .any(|prefix| tag.starts_with(prefix))
+            || ["-adj", "-adv", "-adjective"]
+                .iter()
+                .any(|suffix| tag.ends_with(suffix))
+            || tag
+                .strip_prefix('v')
+                .is_some_and(|rest| rest.starts_with(|c: char| c.is_ascii_digit()));
+
+        if is_pos {
+            TagKind::PartOfSpeech
+        } else if Self::FIELDS.contains(&tag) {
+            TagKind::Field
+        } else if Self::DIALECTS.contains(&tag) {
+            TagKind::Dialect
+        } else if Self::MISC.contains(&tag) {
+            TagKind::Misc
+        } else {
+            TagKind::Other
+        }
+    }
+}
+
+/// Represents a glossary entry, a sense of a word, containing meaning and tags.
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 pub struct Glossary {
     /// Order of the glossary entry
     pub order: i32,
     /// Set of tags associated with the glossary entry
     pub tags: HashSet<String>,
+    /// Part of speech tags among the tags
+    #[serde(default)]
+    pub pos: HashSet<String>,
+    /// Field of application tags among the tags
+    #[serde(default)]
+    pub field: HashSet<String>,
+    /// Miscellaneous tags among the tags
+    #[serde(default)]
+    pub misc: HashSet<String>,
+    /// Dialect tags among the tags
+    #[serde(default)]
+    pub dialect: HashSet<String>,
     /// List of meanings for the word
     pub meaning: Vec<String>,
+    /// Words to see for related meanings
+    #[serde(default)]
+    pub references: Vec<String>,
+    /// Words of opposite meaning
+    #[serde(default)]
+    pub antonyms: Vec<String>,
 }
 
 impl Glossary {
-    /// Creates a new Glossary entry.
+    /// Creates a new Glossary entry, sorting the tags into their kinds.
     pub fn new(order: i32, tags: HashSet<String>, meaning: Vec<String>) -> Self {
-        Self {
+        let mut glossary = Self {
             order,
             tags,
+            pos: HashSet::new(),
+            field: HashSet::new(),
+            misc: HashSet::new(),
+            dialect: HashSet::new(),
             meaning,
+            references: Vec::new(),
+            antonyms: Vec::new(),
+        };
+        glossary.classify_tags();
+
+        glossary
+    }
+
+    /// Sorts the tags into the part of speech, field, misc and dialect tags, see
+    /// [`TagKind::of`].
+    pub fn classify_tags(&mut self) {
+        for tag in self.tags.iter() {
+            let kind = match TagKind::of(tag) {
+                TagKind::PartOfSpeech => &mut self.pos,
+                TagKind::Field => &mut self.field,
+                TagKind::Misc => &mut self.misc,
+                TagKind::Dialect => &mut self.dialect,
+                TagKind::Other => continue,
+            };
+
+            kind.insert(tag.clone());
         }
     }
 }
@@ -98,6 +601,42 @@ impl Example {
     }
 }
 
+/// Identifies the dictionary, and its revision, that data was taken from.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Source {
+    /// The title of the dictionary
+    pub title: String,
+    /// The revision of the dictionary
+    pub revision: String,
+}
+
+impl Source {
+    /// Creates a new Source instance.
+    pub fn new(title: String, revision: String) -> Self {
+        Self { title, revision }
+    }
+}
+
+/// Returns the kanji containing each component, sorted, see [`Kanji::parts`].
+pub fn kanji_by_component(kanji: &HashMap<char, Kanji>) -> HashMap<char, Vec<char>> {
+    let mut out: HashMap<char, Vec<char>> = HashMap::new();
+
+    for kanji in kanji.values() {
+        for component in kanji.components.iter().chain(&kanji.parts()) {
+            let list = out.entry(*component).or_default();
+            if !list.contains(&kanji.kanji) {
+                list.push(kanji.kanji);
+            }
+        }
+    }
+
+    for list in out.values_mut() {
+        list.sort_unstable();
+    }
+
+    out
+}
+
 /// Represents a kanji character with its associated data.
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 pub struct Kanji {
@@ -113,6 +652,21 @@ pub struct Kanji {
     pub strokes: Option<u8>,
     /// Set of tags associated with the kanji
     pub tags: HashSet<String>,
+    /// Readings only used in names
+    #[serde(default)]
+    pub nanori: HashSet<String>,
+    /// Number of the classical (Kangxi) radical of the kanji (if available)
+    #[serde(default)]
+    pub radical: Option<u8>,
+    /// Codes of variant forms of the kanji, as `type code` pairs, e.g. `jis208 1-48-19`
+    #[serde(default)]
+    pub variants: Vec<String>,
+    /// Radicals and components the kanji is made of, as listed by KRADFILE and RADKFILE
+    #[serde(default)]
+    pub components: Vec<char>,
+    /// Ideographic description sequence of the kanji, e.g. `⿰日月` for 明 (if available)
+    #[serde(default)]
+    pub ids: Option<String>,
 }
 
 impl Kanji {
@@ -132,9 +686,57 @@ impl Kanji {
             meaning,
             strokes,
             tags,
+            nanori: HashSet::new(),
+            radical: None,
+            variants: Vec::new(),
+            components: Vec::new(),
+            ids: None,
+        }
+    }
+
+    /// Adds a component the kanji does not list yet. The kanji is no component of itself.
+    pub fn add_component(&mut self, component: char) {
+        if component != self.kanji && !self.components.contains(&component) {
+            self.components.push(component);
+        }
+    }
+
+    /// Returns the parts the kanji is directly made of: those of its ideographic description
+    /// sequence if available, otherwise its components.
+    pub fn parts(&self) -> Vec<char> {
+        match &self.ids {
+            Some(ids) => ids
+                .chars()
+                .filter(|c| !('\u{2FF0}'..='\u{2FFF}').contains(c) && *c != self.kanji)
+                .collect(),
+            None => self.components.clone(),
+        }
+    }
+
+    /// Returns the breakdown of the kanji into its parts, e.g. `⿰日月` or `日 月`.
+    pub fn breakdown(&self) -> String {
+        match &self.ids {
+            Some(ids) if *ids != self.kanji.to_string() => ids.clone(),
+            _ => self
+                .components
+                .iter()
+                .map(|c| c.to_string())
+                .collect::<Vec<_>>()
+                .join(" "),
         }
     }
 
+    /// Returns the definitions of the kanji's tags, sorted by category and order.
+    pub fn tag_info<'a>(&self, tags: &'a TagBank) -> Vec<&'a Tag> {
+        tags.resolve(self.tags.iter().map(|tag| tag.as_str()))
+    }
+
+    /// Constructs the meaning field of a kanji note from its meanings, followed by the
+    /// descriptions of the kanji's tags found in the tag bank.
+    pub fn meaning_html_with_tags(&self, tags: &TagBank) -> String {
+        with_tag_notes(self.meaning.join(", "), self.tag_info(tags))
+    }
+
     /// Returns a set of all possible readings for the kanji.
     ///
     /// The readings are returned as Hiragana strings, derived from both Onyomi and Kunyomi readings,
@@ -161,3 +763,178 @@ impl Kanji {
         out
     }
 }
+
+/// Appends a line per tag with its description to a meaning field, e.g. `n: noun (common)`.
+fn with_tag_notes(meaning: String, tags: Vec<&Tag>) -> String {
+    let notes: Vec<String> = tags
+        .iter()
+        .filter(|tag| !tag.notes.is_empty())
+        .map(|tag| format!("{}: {}", tag.name, tag.notes))
+        .collect();
+
+    if notes.is_empty() {
+        return meaning;
+    }
+
+    format!("{}<br><br><small>{}</small>", meaning, notes.join("<br>"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tests that words of a word list without a schema version are upgraded.
+    #[test]
+    fn migrate_wordlist() {
+        let words = parse_wordlist(
+            r#"{
+                "其[そ]れ": {
+                    "word_id": 1006970,
+                    "furigana": "其[そ]れ",
+                    "glossary": [{"order": 0, "tags": ["pn", "uk", "⭐"], "meaning": ["that"]}],
+                    "frequency": [],
+                    "examples": []
+                }
+            }"#,
+        )
+        .unwrap();
+
+        let sore = &words["其[そ]れ"];
+        assert_eq!(sore.version, WORD_VERSION);
+        assert_eq!(sore.forms, vec![KanjiForm::new("其れ".to_owned())]);
+        assert_eq!(sore.readings, vec![Reading::new("それ".to_owned())]);
+        assert_eq!(sore.glossary[0].pos, ["pn".to_owned()].into());
+        assert_eq!(sore.glossary[0].misc, ["uk".to_owned()].into());
+        assert!(sore.glossary[0].field.is_empty());
+        assert!(sore.usually_kana());
+    }
+
+    /// Tests that the conjugation table is built from the inflection class in the tags.
+    #[test]
+    fn conjugations() {
+        let word = |furigana: &str, tag: &str| {
+            Word::new(
+                0,
+                furigana.to_owned(),
+                vec![Glossary::new(
+                    0,
+                    HashSet::from([tag.to_owned()]),
+                    Vec::new(),
+                )],
+                HashSet::new(),
+                HashSet::new(),
+            )
+        };
+
+        let html = word("勉強[べんきょう]", "vする").conjugations_html();
+        assert!(html.starts_with("<table><tr><td>non-past</td><td>勉強[べんきょう]する</td></tr>"));
+        assert!(html.contains("<tr><td>polite</td><td>勉強[べんきょう]します</td></tr>"));
+        assert_eq!(word("猫[ねこ]", "n").conjugations_html(), "");
+    }
+
+    /// Tests that the ending of a verb is kept apart in its romaji.
+    #[test]
+    fn romaji_verb_ending() {
+        let word = |furigana: &str, tag: &str| {
+            Word::new(
+                0,
+                furigana.to_owned(),
+                vec![Glossary::new(
+                    0,
+                    HashSet::from([tag.to_owned()]),
+                    Vec::new(),
+                )],
+                HashSet::new(),
+                HashSet::new(),
+            )
+        };
+
+        assert_eq!(word("思[おも]う", "v5う").romaji(), "omou");
+        assert_eq!(word("吸[す]う", "v5う").romaji(), "suu");
+        assert_eq!(word("問[と]う", "v5う-s").romaji(), "tou");
+        assert_eq!(word("東京[とうきょう]", "n").romaji(), "tōkyō");
+        assert_eq!(word("大[おお]きい", "adj-い").romaji(), "ōkii");
+    }
+
+    /// Tests that the notes of a word come before its meaning.
+    #[test]
+    fn meaning_notes() {
+        let mut word = Word::new(
+            1006970,
+            "其[そ]れ".to_owned(),
+            vec![Glossary::new(
+                0,
+                HashSet::from(["pn".to_owned(), "uk".to_owned()]),
+                vec!["that".to_owned()],
+            )],
+            HashSet::new(),
+            HashSet::new(),
+        );
+        word.extend_forms(&[KanjiForm::new("夫れ".to_owned())], &[]);
+
+        assert_eq!(
+            word.meaning_html(),
+            "<i>Usually written in kana</i><br><i>Also written 夫れ</i><br>[ pn uk ] that"
+        );
+    }
+
+    /// Tests that the descriptions of known tags follow the meaning.
+    #[test]
+    fn meaning_tags() {
+        let word = Word::new(
+            1,
+            "猫[ねこ]".to_owned(),
+            vec![Glossary::new(
+                0,
+                HashSet::from(["n".to_owned()]),
+                vec!["cat".to_owned()],
+            )],
+            HashSet::from(["JLPT-N5".to_owned()]),
+            HashSet::new(),
+        );
+        let tags: TagBank = [
+            ("n", "partOfSpeech", "noun (common)"),
+            ("JLPT-N5", "frequent", "JLPT level N5"),
+            ("vt", "partOfSpeech", "transitive verb"),
+        ]
+        .into_iter()
+        .map(|(name, category, notes)| {
+            Tag::new(name.to_owned(), category.to_owned(), 0, notes.to_owned(), 0)
+        })
+        .collect();
+
+        assert_eq!(
+            word.meaning_html_with_tags(&tags),
+            "[ n ] cat<br><br><small>JLPT-N5: JLPT level N5<br>n: noun (common)</small>"
+        );
+        assert_eq!(
+            word.meaning_html_with_tags(&TagBank::default()),
+            word.meaning_html()
+        );
+    }
+
+    /// Tests that the other forms of a word only are the forms its reading applies to.
+    #[test]
+    fn other_forms_restrictions() {
+        let mut word = Word::new(
+            1578850,
+            "行[ゆ]く".to_owned(),
+            Vec::new(),
+            HashSet::new(),
+            HashSet::new(),
+        );
+        let forms = ["行く", "往く"].map(|form| KanjiForm::new(form.to_owned()));
+        word.extend_forms(&forms, &[]);
+        assert_eq!(word.other_forms(), vec!["往く"]);
+
+        let mut yuku = Reading::new("ゆく".to_owned());
+        yuku.restrictions = vec!["行く".to_owned()];
+        word.readings = vec![yuku];
+        assert!(word.other_forms().is_empty());
+
+        word.readings[0].restrictions.clear();
+        word.readings[0].no_kanji = true;
+        assert!(word.other_forms().is_empty());
+        assert!(word.notes().is_empty());
+    }
+}
@@ -1,7 +1,12 @@
-use std::collections::{HashMap, HashSet};
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    sync::OnceLock,
+};
 
 use regex::Regex;
 
+use crate::romaji::{self, RomajiSystem};
+
 /// Trait for converting between Japanese Katakana and Hiragana characters
 pub trait JapaneseChar {
     /// Converts a single Japanese character from Katakana to Hiragana.
@@ -54,6 +59,18 @@ pub trait JapaneseStr {
 
     /// Extracts kanji from kanji-kana pairs in a string.
     fn to_kanji(&self) -> String;
+
+    /// Transliterates kana to modified Hepburn romaji.
+    fn to_romaji(&self) -> String {
+        self.to_romaji_with(RomajiSystem::Hepburn)
+    }
+
+    /// Transliterates kana to romaji using the given system, see [`romaji::to_romaji`].
+    fn to_romaji_with(&self, system: RomajiSystem) -> String;
+
+    /// Converts romaji to hiragana like an IME, see [`romaji::from_romaji`].
+    #[allow(clippy::wrong_self_convention)]
+    fn from_romaji(&self) -> String;
 }
 
 impl JapaneseStr for &str {
@@ -78,6 +95,16 @@ impl JapaneseStr for &str {
         let regex = Regex::new(r" ?(?<kanji>[^\s\[\]]+?)\[(?<kana>[^\s\[\]]+?)\]").unwrap();
         regex.replace_all(self, "${kanji}").to_string()
     }
+
+    /// Transliterates kana to romaji.
+    fn to_romaji_with(&self, system: RomajiSystem) -> String {
+        romaji::to_romaji(self, system)
+    }
+
+    /// Converts romaji to hiragana.
+    fn from_romaji(&self) -> String {
+        romaji::from_romaji(self)
+    }
 }
 
 impl JapaneseStr for String {
@@ -102,6 +129,16 @@ impl JapaneseStr for String {
         let regex = Regex::new(r" ?(?<kanji>[^\s\[\]]+?)\[(?<kana>[^\s\[\]]+?)\]").unwrap();
         regex.replace_all(self, "${kanji}").to_string()
     }
+
+    /// Transliterates kana to romaji.
+    fn to_romaji_with(&self, system: RomajiSystem) -> String {
+        romaji::to_romaji(self, system)
+    }
+
+    /// Converts romaji to hiragana.
+    fn from_romaji(&self) -> String {
+        romaji::from_romaji(self)
+    }
 }
 
 /// Parses a kanji reading string into its components: prefix, main reading, okurigana, and suffix.
@@ -294,6 +331,700 @@ fn to_furigana_blocks_check(kana: &str, blocks: &[(String, bool, String)]) -> Op
     None
 }
 
+/// The inflection class of a word in dictionary form.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum WordClass {
+    /// Ichidan (group 2) verbs, e.g. 食べる.
+    Ichidan,
+    /// Godan (group 1) verbs, e.g. 書く.
+    Godan,
+    /// The irregular verb する and suru verbs.
+    Suru,
+    /// The irregular verb 来る.
+    Kuru,
+    /// The irregular verb ある, whose negative is ない.
+    Aru,
+    /// Honorific godan verbs, e.g. くださる, whose polite stem and imperative end in い.
+    Kudasaru,
+    /// Godan verbs ending in う whose て and past forms keep the う, e.g. 問う.
+    Tou,
+    /// い-adjectives, e.g. 高い.
+    IAdjective,
+    /// The adjective いい, which inflects as よい.
+    IxAdjective,
+    /// な-adjectives, e.g. 静か.
+    NaAdjective,
+}
+
+// Conditions of a term, used to decide which rules can be applied to it next.
+const V1: u32 = 1 << 0;
+const V5: u32 = 1 << 1;
+const VS: u32 = 1 << 2;
+const VK: u32 = 1 << 3;
+const ADJ_I: u32 = 1 << 4;
+/// The ます form, which can still be inflected.
+const MASU: u32 = 1 << 5;
+/// The て form, which can still be followed by auxiliaries.
+const TE: u32 = 1 << 6;
+/// The text as written, or with a sentence ending removed. Any rule can be applied.
+const ANY: u32 = u32::MAX;
+
+impl WordClass {
+    /// Returns the class of a word from the part of speech tags produced by `remap_tag`, e.g.
+    /// `v5る`, `vする` or `adj-い`. Returns `None` if the word does not inflect.
+    pub fn from_tags<'a>(tags: impl IntoIterator<Item = &'a str>) -> Option<Self> {
+        let tags: Vec<&str> = tags.into_iter().collect();
+        let has_tag = |test: &dyn Fn(&str) -> bool| tags.iter().any(|tag| test(tag));
+
+        if has_tag(&|tag| tag == "adj-いx") {
+            Some(WordClass::IxAdjective)
+        } else if has_tag(&|tag| tag == "adj-い") {
+            Some(WordClass::IAdjective)
+        } else if has_tag(&|tag| tag == "adj-な") {
+            Some(WordClass::NaAdjective)
+        } else if has_tag(&|tag| tag == "v5る-i") {
+            Some(WordClass::Aru)
+        } else if has_tag(&|tag| tag == "v5ある") {
+            Some(WordClass::Kudasaru)
+        } else if has_tag(&|tag| tag == "v5う-s") {
+            Some(WordClass::Tou)
+        } else if has_tag(&|tag| tag == "vくる") {
+            Some(WordClass::Kuru)
+        } else if has_tag(&|tag| tag.starts_with("vする")) {
+            Some(WordClass::Suru)
+        } else if has_tag(&|tag| tag.starts_with("v5")) {
+            Some(WordClass::Godan)
+        } else if has_tag(&|tag| tag.starts_with("v1")) {
+            Some(WordClass::Ichidan)
+        } else {
+            None
+        }
+    }
+
+    /// Returns whether the class is a verb class rather than an adjective class.
+    pub fn is_verb(self) -> bool {
+        !matches!(
+            self,
+            WordClass::IAdjective | WordClass::IxAdjective | WordClass::NaAdjective
+        )
+    }
+
+    /// Returns the condition of a dictionary form of the class.
+    fn condition(self) -> u32 {
+        match self {
+            WordClass::Ichidan => V1,
+            WordClass::Godan | WordClass::Aru | WordClass::Kudasaru | WordClass::Tou => V5,
+            WordClass::Suru => VS,
+            WordClass::Kuru => VK,
+            WordClass::IAdjective | WordClass::IxAdjective => ADJ_I,
+            WordClass::NaAdjective => 0,
+        }
+    }
+
+    /// Returns the class of a dictionary form with the given condition.
+    fn from_condition(condition: u32) -> Option<Self> {
+        [
+            WordClass::Ichidan,
+            WordClass::Godan,
+            WordClass::Suru,
+            WordClass::Kuru,
+            WordClass::IAdjective,
+        ]
+        .into_iter()
+        .find(|class| class.condition() == condition)
+    }
+}
+
+/// A possible dictionary form of an inflected text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Deinflection {
+    /// The dictionary form.
+    pub term: String,
+    /// The class the dictionary form must have, or `None` if the term is the text itself.
+    pub class: Option<WordClass>,
+    /// The transformations applied to the dictionary form, from the innermost outwards.
+    pub reasons: Vec<&'static str>,
+}
+
+impl Deinflection {
+    /// Returns whether a word of the given class can be the dictionary form.
+    pub fn matches(&self, class: Option<WordClass>) -> bool {
+        match (self.class, class) {
+            (None, _) => true,
+            (Some(own), Some(class)) => own.condition() & class.condition() != 0,
+            (Some(_), None) => false,
+        }
+    }
+}
+
+/// A rule replacing an inflected ending with the ending it was inflected from.
+struct DeinflectRule {
+    /// The name of the transformation.
+    name: &'static str,
+    /// The inflected ending.
+    inflected: String,
+    /// The ending before the inflection.
+    deinflected: String,
+    /// The conditions a term must have for the rule to apply, or 0 if the term must be the text
+    /// itself.
+    conditions_in: u32,
+    /// The conditions of the deinflected term.
+    conditions_out: u32,
+}
+
+/// Creates a rule.
+fn rule(
+    name: &'static str,
+    inflected: &str,
+    deinflected: &str,
+    conditions_in: u32,
+    conditions_out: u32,
+) -> DeinflectRule {
+    DeinflectRule {
+        name,
+        inflected: inflected.to_owned(),
+        deinflected: deinflected.to_owned(),
+        conditions_in,
+        conditions_out,
+    }
+}
+
+/// Returns the deinflection rules, similar to the ones used by Yomitan.
+fn deinflect_rules() -> &'static [DeinflectRule] {
+    static RULES: OnceLock<Vec<DeinflectRule>> = OnceLock::new();
+
+    RULES.get_or_init(|| {
+        let mut rules = Vec::new();
+
+        // Ichidan verbs
+        for (name, inflected, conditions_in) in [
+            ("negative", "ない", ADJ_I),
+            ("polite", "ます", MASU),
+            ("-te", "て", TE),
+            ("past", "た", 0),
+            ("-tara", "たら", 0),
+            ("-ba", "れば", 0),
+            ("imperative", "ろ", 0),
+            ("imperative", "よ", 0),
+            ("volitional", "よう", 0),
+            ("passive/potential", "られる", V1),
+            ("causative", "させる", V1),
+            ("prohibitive", "るな", 0),
+            ("-tai", "たい", ADJ_I),
+            ("-zu", "ず", 0),
+        ] {
+            rules.push(rule(name, inflected, "る", conditions_in, V1));
+        }
+
+        // Godan verbs: dictionary ending, あ, い, え and お rows, て and た forms
+        for [u, a, i, e, o, te, ta] in [
+            ["う", "わ", "い", "え", "お", "って", "った"],
+            ["く", "か", "き", "け", "こ", "いて", "いた"],
+            ["ぐ", "が", "ぎ", "げ", "ご", "いで", "いだ"],
+            ["す", "さ", "し", "せ", "そ", "して", "した"],
+            ["つ", "た", "ち", "て", "と", "って", "った"],
+            ["ぬ", "な", "に", "ね", "の", "んで", "んだ"],
+            ["ぶ", "ば", "び", "べ", "ぼ", "んで", "んだ"],
+            ["む", "ま", "み", "め", "も", "んで", "んだ"],
+            ["る", "ら", "り", "れ", "ろ", "って", "った"],
+        ] {
+            for (name, inflected, conditions_in) in [
+                ("negative", format!("{a}ない"), ADJ_I),
+                ("polite", format!("{i}ます"), MASU),
+                ("-te", te.to_owned(), TE),
+                ("past", ta.to_owned(), 0),
+                ("-tara", format!("{ta}ら"), 0),
+                ("-ba", format!("{e}ば"), 0),
+                ("imperative", e.to_owned(), 0),
+                ("volitional", format!("{o}う"), 0),
+                ("potential", format!("{e}る"), V1),
+                ("passive", format!("{a}れる"), V1),
+                ("causative", format!("{a}せる"), V1),
+                ("prohibitive", format!("{u}な"), 0),
+                ("-tai", format!("{i}たい"), ADJ_I),
+                ("-zu", format!("{a}ず"), 0),
+            ] {
+                rules.push(rule(name, &inflected, u, conditions_in, V5));
+            }
+        }
+
+        // 行く has irregular て and た forms
+        for stem in ["行", "い"] {
+            for (name, ending, conditions_in) in [
+                ("-te", "って", TE),
+                ("past", "った", 0),
+                ("-tara", "ったら", 0),
+            ] {
+                rules.push(rule(
+                    name,
+                    &format!("{stem}{ending}"),
+                    &format!("{stem}く"),
+                    conditions_in,
+                    V5,
+                ));
+            }
+        }
+
+        // する
+        for (name, inflected, conditions_in) in [
+            ("negative", "しない", ADJ_I),
+            ("polite", "します", MASU),
+            ("-te", "して", TE),
+            ("past", "した", 0),
+            ("-tara", "したら", 0),
+            ("-ba", "すれば", 0),
+            ("imperative", "しろ", 0),
+            ("imperative", "せよ", 0),
+            ("volitional", "しよう", 0),
+            ("passive", "される", V1),
+            ("causative", "させる", V1),
+            ("potential", "できる", V1),
+            ("prohibitive", "するな", 0),
+            ("-tai", "したい", ADJ_I),
+            ("-zu", "せず", 0),
+        ] {
+            rules.push(rule(name, inflected, "する", conditions_in, VS));
+        }
+
+        // 来る, written in kana or with its kanji replacing the first kana
+        for (name, inflected, conditions_in) in [
+            ("negative", "こない", ADJ_I),
+            ("polite", "きます", MASU),
+            ("-te", "きて", TE),
+            ("past", "きた", 0),
+            ("-tara", "きたら", 0),
+            ("-ba", "くれば", 0),
+            ("imperative", "こい", 0),
+            ("volitional", "こよう", 0),
+            ("passive/potential", "こられる", V1),
+            ("causative", "こさせる", V1),
+            ("prohibitive", "くるな", 0),
+            ("-tai", "きたい", ADJ_I),
+            ("-zu", "こず", 0),
+        ] {
+            rules.push(rule(name, inflected, "くる", conditions_in, VK));
+
+            let kanji = format!(
+                "来{}",
+                &inflected[inflected.chars().next().unwrap().len_utf8()..]
+            );
+            rules.push(rule(name, &kanji, "来る", conditions_in, VK));
+        }
+
+        // い-adjectives
+        for (name, inflected, conditions_in) in [
+            ("negative", "くない", ADJ_I),
+            ("past", "かった", 0),
+            ("-te", "くて", TE),
+            ("-ba", "ければ", 0),
+            ("-tara", "かったら", 0),
+            ("adverbial", "く", 0),
+        ] {
+            rules.push(rule(name, inflected, "い", conditions_in, ADJ_I));
+        }
+
+        // The ます form
+        for (name, inflected, conditions_in) in [
+            ("negative", "ません", 0),
+            ("past", "ました", 0),
+            ("negative past", "ませんでした", 0),
+            ("volitional", "ましょう", 0),
+            ("-te", "まして", TE),
+            ("-tara", "ましたら", 0),
+        ] {
+            rules.push(rule(name, inflected, "ます", conditions_in, MASU));
+        }
+
+        // Auxiliaries following the て form
+        for te in ["て", "で"] {
+            for (name, ending, conditions_in, conditions_out) in [
+                ("progressive", "いる", V1, TE),
+                ("progressive", "る", V1, TE),
+                ("request", "ください", 0, TE),
+                ("completive", "しまう", V5, TE),
+            ] {
+                rules.push(rule(
+                    name,
+                    &format!("{te}{ending}"),
+                    te,
+                    conditions_in,
+                    conditions_out,
+                ));
+            }
+        }
+        rules.push(rule("-te", "ないで", "ない", TE, ADJ_I));
+
+        // Sentence endings following a plain form
+        for (name, inflected) in [
+            ("presumptive", "でしょう"),
+            ("presumptive", "だろう"),
+            ("polite", "です"),
+        ] {
+            rules.push(rule(name, inflected, "", 0, ANY));
+        }
+
+        rules
+    })
+}
+
+/// Maps an inflected text back to its possible dictionary forms, similar to Yomitan.
+///
+/// Every candidate is returned, including the text itself, so the caller has to check which
+/// candidates are real words of the matching [`WordClass`].
+///
+/// # Example
+/// ```
+/// use anki_utill::japanese::{deinflect, WordClass};
+///
+/// let candidate = deinflect("食べさせられた")
+///     .into_iter()
+///     .find(|candidate| candidate.term == "食べる")
+///     .unwrap();
+///
+/// assert_eq!(candidate.class, Some(WordClass::Ichidan));
+/// assert_eq!(candidate.reasons, vec!["causative", "passive/potential", "past"]);
+/// ```
+pub fn deinflect(text: &str) -> Vec<Deinflection> {
+    let mut out = Vec::new();
+    let mut seen: HashSet<(String, u32)> = HashSet::from([(text.to_owned(), ANY)]);
+    let mut queue: VecDeque<(String, u32, Vec<&'static str>)> =
+        VecDeque::from([(text.to_owned(), ANY, Vec::new())]);
+
+    while let Some((term, conditions, reasons)) = queue.pop_front() {
+        for rule in deinflect_rules() {
+            let applies = if rule.conditions_in == 0 {
+                conditions == ANY
+            } else {
+                conditions & rule.conditions_in != 0
+            };
+
+            if !applies || !term.ends_with(&rule.inflected) {
+                continue;
+            }
+
+            let stem = &term[..term.len() - rule.inflected.len()];
+            let deinflected = format!("{stem}{}", rule.deinflected);
+            if deinflected.is_empty() || !seen.insert((deinflected.clone(), rule.conditions_out)) {
+                continue;
+            }
+
+            let mut reasons = reasons.clone();
+            reasons.insert(0, rule.name);
+            queue.push_back((deinflected, rule.conditions_out, reasons));
+        }
+
+        // Terms still needing another rule, e.g. a bare て form, are not dictionary forms
+        if conditions == ANY {
+            out.push(Deinflection {
+                term,
+                class: None,
+                reasons,
+            });
+        } else if let Some(class) = WordClass::from_condition(conditions) {
+            out.push(Deinflection {
+                term,
+                class: Some(class),
+                reasons,
+            });
+        }
+    }
+
+    out
+}
+
+/// A named form of a conjugated word.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Conjugation {
+    /// The name of the form, e.g. `polite negative`.
+    pub name: &'static str,
+    /// The conjugated word.
+    pub form: String,
+}
+
+/// The stems and irregular forms a verb table is built from.
+struct VerbStems {
+    /// The ます stem, e.g. 書き.
+    masu: String,
+    /// The plain negative, e.g. 書かない.
+    negative: String,
+    /// The て form.
+    te: String,
+    /// The plain past.
+    past: String,
+    /// The ば conditional.
+    conditional: String,
+    /// The potential form.
+    potential: String,
+    /// The passive form.
+    passive: String,
+    /// The causative form.
+    causative: String,
+    /// The causative passive form.
+    causative_passive: String,
+    /// The volitional form.
+    volitional: String,
+    /// The imperative form.
+    imperative: String,
+}
+
+/// Returns the stems of an ichidan verb.
+fn ichidan_stems(word: &str) -> Option<VerbStems> {
+    let stem = word.strip_suffix('る')?;
+
+    Some(VerbStems {
+        masu: stem.to_owned(),
+        negative: format!("{stem}ない"),
+        te: format!("{stem}て"),
+        past: format!("{stem}た"),
+        conditional: format!("{stem}れば"),
+        potential: format!("{stem}られる"),
+        passive: format!("{stem}られる"),
+        causative: format!("{stem}させる"),
+        causative_passive: format!("{stem}させられる"),
+        volitional: format!("{stem}よう"),
+        imperative: format!("{stem}ろ"),
+    })
+}
+
+/// Returns the stems of a godan verb, including the irregular て form of 行く.
+fn godan_stems(word: &str) -> Option<VerbStems> {
+    let end = word.chars().last()?;
+    let stem = &word[..word.len() - end.len_utf8()];
+
+    let [a, i, e, o, te, ta] = match end {
+        'う' => ["わ", "い", "え", "お", "って", "った"],
+        'く' if word.to_kanji().ends_with("行く") || word == "いく" => {
+            ["か", "き", "け", "こ", "って", "った"]
+        }
+        'く' => ["か", "き", "け", "こ", "いて", "いた"],
+        'ぐ' => ["が", "ぎ", "げ", "ご", "いで", "いだ"],
+        'す' => ["さ", "し", "せ", "そ", "して", "した"],
+        'つ' => ["た", "ち", "て", "と", "って", "った"],
+        'ぬ' => ["な", "に", "ね", "の", "んで", "んだ"],
+        'ぶ' => ["ば", "び", "べ", "ぼ", "んで", "んだ"],
+        'む' => ["ま", "み", "め", "も", "んで", "んだ"],
+        'る' => ["ら", "り", "れ", "ろ", "って", "った"],
+        _ => return None,
+    };
+
+    Some(VerbStems {
+        masu: format!("{stem}{i}"),
+        negative: format!("{stem}{a}ない"),
+        te: format!("{stem}{te}"),
+        past: format!("{stem}{ta}"),
+        conditional: format!("{stem}{e}ば"),
+        potential: format!("{stem}{e}る"),
+        passive: format!("{stem}{a}れる"),
+        causative: format!("{stem}{a}せる"),
+        causative_passive: format!("{stem}{a}せられる"),
+        volitional: format!("{stem}{o}う"),
+        imperative: format!("{stem}{e}"),
+    })
+}
+
+/// Returns the stems of する or a suru verb. A noun without する is conjugated as if it had it.
+fn suru_stems(word: &str) -> VerbStems {
+    let stem = word.strip_suffix("する").unwrap_or(word);
+
+    VerbStems {
+        masu: format!("{stem}し"),
+        negative: format!("{stem}しない"),
+        te: format!("{stem}して"),
+        past: format!("{stem}した"),
+        conditional: format!("{stem}すれば"),
+        potential: format!("{stem}できる"),
+        passive: format!("{stem}される"),
+        causative: format!("{stem}させる"),
+        causative_passive: format!("{stem}させられる"),
+        volitional: format!("{stem}しよう"),
+        imperative: format!("{stem}しろ"),
+    }
+}
+
+/// Returns the stems of 来る, written in kana, kanji or furigana format.
+fn kuru_stems(word: &str) -> Option<VerbStems> {
+    // Writes 来 with the reading of the given form
+    let ku: Box<dyn Fn(&str) -> String> = if let Some(prefix) = word.strip_suffix("来[く]る") {
+        let prefix = prefix.to_owned();
+        Box::new(move |kana| format!("{prefix}来[{kana}]"))
+    } else if let Some(prefix) = word.strip_suffix("来る") {
+        let prefix = prefix.to_owned();
+        Box::new(move |_| format!("{prefix}来"))
+    } else {
+        let prefix = word.strip_suffix("くる")?.to_owned();
+        Box::new(move |kana| format!("{prefix}{kana}"))
+    };
+
+    Some(VerbStems {
+        masu: ku("き"),
+        negative: format!("{}ない", ku("こ")),
+        te: format!("{}て", ku("き")),
+        past: format!("{}た", ku("き")),
+        conditional: format!("{}れば", ku("く")),
+        potential: format!("{}られる", ku("こ")),
+        passive: format!("{}られる", ku("こ")),
+        causative: format!("{}させる", ku("こ")),
+        causative_passive: format!("{}させられる", ku("こ")),
+        volitional: format!("{}よう", ku("こ")),
+        imperative: format!("{}い", ku("こ")),
+    })
+}
+
+/// Builds the table of a verb from its stems.
+fn verb_table(word: &str, stems: VerbStems) -> Vec<(&'static str, String)> {
+    let VerbStems {
+        masu,
+        negative,
+        te,
+        past,
+        conditional,
+        potential,
+        passive,
+        causative,
+        causative_passive,
+        volitional,
+        imperative,
+    } = stems;
+    let nai = negative.strip_suffix('い').unwrap_or(&negative).to_owned();
+
+    vec![
+        ("non-past", word.to_owned()),
+        ("polite", format!("{masu}ます")),
+        ("negative", negative.clone()),
+        ("polite negative", format!("{masu}ません")),
+        ("past", past.clone()),
+        ("polite past", format!("{masu}ました")),
+        ("negative past", format!("{nai}かった")),
+        ("polite negative past", format!("{masu}ませんでした")),
+        ("te-form", te),
+        ("negative te-form", format!("{negative}で")),
+        ("potential", potential),
+        ("passive", passive),
+        ("causative", causative),
+        ("causative passive", causative_passive),
+        ("volitional", volitional),
+        ("polite volitional", format!("{masu}ましょう")),
+        ("imperative", imperative),
+        ("conditional", conditional),
+        ("negative conditional", format!("{nai}ければ")),
+        ("tara-conditional", format!("{past}ら")),
+        ("desire", format!("{masu}たい")),
+    ]
+}
+
+/// Builds the table of an い-adjective.
+fn i_adjective_table(word: &str) -> Option<Vec<(&'static str, String)>> {
+    let stem = word.strip_suffix('い')?;
+
+    Some(vec![
+        ("non-past", word.to_owned()),
+        ("polite", format!("{word}です")),
+        ("negative", format!("{stem}くない")),
+        ("polite negative", format!("{stem}くないです")),
+        ("past", format!("{stem}かった")),
+        ("polite past", format!("{stem}かったです")),
+        ("negative past", format!("{stem}くなかった")),
+        ("polite negative past", format!("{stem}くなかったです")),
+        ("te-form", format!("{stem}くて")),
+        ("adverbial", format!("{stem}く")),
+        ("conditional", format!("{stem}ければ")),
+        ("negative conditional", format!("{stem}くなければ")),
+        ("tara-conditional", format!("{stem}かったら")),
+    ])
+}
+
+/// Builds the table of a な-adjective.
+fn na_adjective_table(word: &str) -> Vec<(&'static str, String)> {
+    vec![
+        ("non-past", format!("{word}だ")),
+        ("polite", format!("{word}です")),
+        ("negative", format!("{word}ではない")),
+        ("polite negative", format!("{word}ではありません")),
+        ("past", format!("{word}だった")),
+        ("polite past", format!("{word}でした")),
+        ("negative past", format!("{word}ではなかった")),
+        (
+            "polite negative past",
+            format!("{word}ではありませんでした"),
+        ),
+        ("te-form", format!("{word}で")),
+        ("adverbial", format!("{word}に")),
+        ("attributive", format!("{word}な")),
+        ("conditional", format!("{word}なら")),
+        ("tara-conditional", format!("{word}だったら")),
+    ]
+}
+
+/// Returns the conjugation table of a word in dictionary form. The word can be written in kana,
+/// kanji or furigana format, as long as the inflected ending is written in kana.
+///
+/// Returns `None` if the word does not have the ending of its class.
+///
+/// # Example
+/// ```
+/// use anki_utill::japanese::{conjugate, WordClass};
+///
+/// let table = conjugate("書く", WordClass::Godan).unwrap();
+/// let form = |name: &str| table.iter().find(|form| form.name == name).unwrap().form.clone();
+///
+/// assert_eq!(form("polite negative"), "書きません");
+/// assert_eq!(form("te-form"), "書いて");
+/// ```
+pub fn conjugate(word: &str, class: WordClass) -> Option<Vec<Conjugation>> {
+    let table = match class {
+        WordClass::Ichidan => verb_table(word, ichidan_stems(word)?),
+        WordClass::Godan => verb_table(word, godan_stems(word)?),
+        WordClass::Suru => {
+            // A noun without する is listed with it
+            let stem = word.strip_suffix("する").unwrap_or(word);
+            verb_table(&format!("{stem}する"), suru_stems(word))
+        }
+        WordClass::Kuru => verb_table(word, kuru_stems(word)?),
+        WordClass::Aru => {
+            // ある has no negative stem, its negative is the adjective ない
+            let mut stems = godan_stems(word)?;
+            stems.negative = "ない".to_owned();
+            verb_table(word, stems)
+        }
+        WordClass::Kudasaru => {
+            // くださる is polite as ください, not くださり
+            let mut stems = godan_stems(word)?;
+            let stem = word.strip_suffix('る')?;
+            stems.masu = format!("{stem}い");
+            stems.imperative = format!("{stem}い");
+            verb_table(word, stems)
+        }
+        WordClass::Tou => {
+            // 問う keeps its う before て and た
+            let mut stems = godan_stems(word)?;
+            stems.te = format!("{word}て");
+            stems.past = format!("{word}た");
+            verb_table(word, stems)
+        }
+        WordClass::IAdjective => i_adjective_table(word)?,
+        WordClass::IxAdjective => {
+            // いい only keeps its own stem in the non-past forms
+            let yoi = match word.strip_suffix("いい") {
+                Some(prefix) => format!("{prefix}よい"),
+                None => word.to_owned(),
+            };
+
+            let mut table = i_adjective_table(&yoi)?;
+            table[0].1 = word.to_owned();
+            table[1].1 = format!("{word}です");
+            table
+        }
+        WordClass::NaAdjective => na_adjective_table(word),
+    };
+
+    Some(
+        table
+            .into_iter()
+            .map(|(name, form)| Conjugation { name, form })
+            .collect(),
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -448,4 +1179,134 @@ mod tests {
             Some("建[たて]物[もの]".to_owned())
         );
     }
+
+    /// Tests that inflected verbs and adjectives map back to their dictionary form.
+    #[test]
+    fn test_deinflect() {
+        let find = |text: &str, term: &str| {
+            deinflect(text)
+                .into_iter()
+                .find(|candidate| candidate.term == term)
+        };
+
+        let candidate = find("書いていました", "書く").unwrap();
+        assert_eq!(candidate.class, Some(WordClass::Godan));
+        assert_eq!(
+            candidate.reasons,
+            vec!["-te", "progressive", "polite", "past"]
+        );
+
+        let cases = [
+            ("食べない", "食べる", WordClass::Ichidan),
+            ("読んでください", "読む", WordClass::Godan),
+            ("待たなかった", "待つ", WordClass::Godan),
+            ("行った", "行く", WordClass::Godan),
+            ("泳いだ", "泳ぐ", WordClass::Godan),
+            ("読めます", "読む", WordClass::Godan),
+            ("しなかった", "する", WordClass::Suru),
+            ("勉強させられる", "勉強する", WordClass::Suru),
+            ("来ませんでした", "来る", WordClass::Kuru),
+            ("こない", "くる", WordClass::Kuru),
+            ("高くなかった", "高い", WordClass::IAdjective),
+            ("寒かったでしょう", "寒い", WordClass::IAdjective),
+            ("食べたくない", "食べる", WordClass::Ichidan),
+        ];
+
+        for (text, term, class) in cases {
+            assert!(
+                deinflect(text)
+                    .iter()
+                    .any(|candidate| candidate.term == term && candidate.class == Some(class)),
+                "{text} -> {term}"
+            );
+        }
+
+        // The text itself is always a candidate, and bare て forms are not
+        assert_eq!(find("猫", "猫").unwrap().class, None);
+        assert!(find("読んでいる", "読んで").is_none());
+        assert!(find("書く", "書く")
+            .unwrap()
+            .matches(Some(WordClass::Godan)));
+    }
+
+    /// Tests the conjugation tables of every class.
+    #[test]
+    fn test_conjugate() {
+        let form = |word: &str, class: WordClass, name: &str| {
+            conjugate(word, class)
+                .unwrap()
+                .into_iter()
+                .find(|conjugation| conjugation.name == name)
+                .unwrap()
+                .form
+        };
+
+        assert_eq!(
+            form("食べる", WordClass::Ichidan, "negative past"),
+            "食べなかった"
+        );
+        assert_eq!(
+            form("食べる", WordClass::Ichidan, "potential"),
+            "食べられる"
+        );
+        assert_eq!(form("買う", WordClass::Godan, "negative"), "買わない");
+        assert_eq!(form("泳ぐ", WordClass::Godan, "te-form"), "泳いで");
+        assert_eq!(form("行[い]く", WordClass::Godan, "past"), "行[い]った");
+        assert_eq!(form("死ぬ", WordClass::Godan, "volitional"), "死のう");
+        assert_eq!(form("勉強", WordClass::Suru, "polite"), "勉強します");
+        assert_eq!(form("勉強", WordClass::Suru, "non-past"), "勉強する");
+        assert_eq!(form("勉強する", WordClass::Suru, "non-past"), "勉強する");
+        assert_eq!(form("する", WordClass::Suru, "potential"), "できる");
+        assert_eq!(form("来[く]る", WordClass::Kuru, "negative"), "来[こ]ない");
+        assert_eq!(form("くる", WordClass::Kuru, "imperative"), "こい");
+        assert_eq!(form("ある", WordClass::Aru, "negative past"), "なかった");
+        assert_eq!(
+            form("ある", WordClass::Aru, "polite negative"),
+            "ありません"
+        );
+        assert_eq!(
+            form("くださる", WordClass::Kudasaru, "polite"),
+            "くださいます"
+        );
+        assert_eq!(form("なさる", WordClass::Kudasaru, "imperative"), "なさい");
+        assert_eq!(form("なさる", WordClass::Kudasaru, "past"), "なさった");
+        assert_eq!(form("問う", WordClass::Tou, "te-form"), "問うて");
+        assert_eq!(form("問う", WordClass::Tou, "tara-conditional"), "問うたら");
+        assert_eq!(form("請う", WordClass::Tou, "negative"), "請わない");
+        assert_eq!(form("高い", WordClass::IAdjective, "negative"), "高くない");
+        assert_eq!(form("いい", WordClass::IxAdjective, "past"), "よかった");
+        assert_eq!(form("いい", WordClass::IxAdjective, "polite"), "いいです");
+        assert_eq!(
+            form("静か", WordClass::NaAdjective, "attributive"),
+            "静かな"
+        );
+
+        assert_eq!(conjugate("ふ", WordClass::Godan), None);
+        assert_eq!(conjugate("猫", WordClass::Ichidan), None);
+
+        // Every form maps back to the dictionary form
+        for (word, class) in [("書く", WordClass::Godan), ("見る", WordClass::Ichidan)] {
+            for conjugation in conjugate(word, class).unwrap() {
+                assert!(
+                    deinflect(&conjugation.form)
+                        .iter()
+                        .any(|candidate| candidate.term == word && candidate.matches(Some(class))),
+                    "{}",
+                    conjugation.form
+                );
+            }
+        }
+
+        assert_eq!(
+            WordClass::from_tags(["n", "vする-s"]),
+            Some(WordClass::Suru)
+        );
+        assert_eq!(WordClass::from_tags(["v5る-i"]), Some(WordClass::Aru));
+        assert_eq!(
+            WordClass::from_tags(["v5ある", "vt"]),
+            Some(WordClass::Kudasaru)
+        );
+        assert_eq!(WordClass::from_tags(["v5う-s"]), Some(WordClass::Tou));
+        assert_eq!(WordClass::from_tags(["n"]), None);
+    }
 }
@@ -0,0 +1,318 @@
+//! Stroke order diagrams from the KanjiVG project.
+//!
+//! KanjiVG has an SVG file for every kanji named after its code point, e.g. `04e9c.svg`, with a
+//! path for every stroke in writing order. The files are read from the release archive or from
+//! a directory of the extracted files. Variant forms, e.g. `04e9c-Kaisho.svg`, are ignored.
+
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+    fs::{self, File},
+    io::{self, Read},
+    path::{Path, PathBuf},
+};
+
+use regex::Regex;
+use zip::ZipArchive;
+
+/// The width and height of a KanjiVG diagram.
+pub const SIZE: usize = 109;
+
+/// Returns whether a character is a CJK ideograph KanjiVG may have a diagram of.
+pub fn is_kanji(c: char) -> bool {
+    ('\u{4E00}'..='\u{9FFF}').contains(&c) || ('\u{3400}'..='\u{4DBF}').contains(&c) || c == '々'
+}
+
+/// Returns the name of the KanjiVG file of a kanji, e.g. `04e9c.svg`.
+pub fn file_name(kanji: char) -> String {
+    format!("{:05x}.svg", kanji as u32)
+}
+
+/// Returns the media filename of the stroke order diagram of a kanji.
+pub fn media_name(kanji: char) -> String {
+    format!("kanjivg_{:05x}.svg", kanji as u32)
+}
+
+/// Returns the media filename of the numbered stroke order strip of a kanji.
+pub fn strip_media_name(kanji: char) -> String {
+    format!("kanjivg_{:05x}_strip.svg", kanji as u32)
+}
+
+/// Returns the HTML showing the stroke order diagram of every kanji of a text, once each.
+///
+/// # Example
+/// ```
+/// use anki_utill::kanjivg::stroke_order_html;
+///
+/// assert_eq!(
+///     stroke_order_html("猫[ねこ]"),
+///     "<img src=\"kanjivg_0732b.svg\">"
+/// );
+/// ```
+pub fn stroke_order_html(text: &str) -> String {
+    let mut seen = HashSet::new();
+
+    text.chars()
+        .filter(|c| is_kanji(*c) && seen.insert(*c))
+        .map(|c| format!("<img src=\"{}\">", media_name(c)))
+        .collect()
+}
+
+/// Returns the HTML showing the numbered stroke order strip of a kanji.
+pub fn strip_html(kanji: char) -> String {
+    format!("<img src=\"{}\">", strip_media_name(kanji))
+}
+
+/// Where the KanjiVG files are read from.
+enum Files {
+    /// A directory of the extracted files.
+    Directory(PathBuf),
+    /// The release archive and the indices of the files by kanji.
+    Archive(ZipArchive<File>, HashMap<char, usize>),
+}
+
+/// Reads the stroke order diagrams of a KanjiVG release.
+pub struct KanjiVg {
+    files: Files,
+}
+
+impl KanjiVg {
+    /// Opens a KanjiVG release archive or a directory of its `kanji` files.
+    pub fn open(path: &Path) -> io::Result<Self> {
+        if path.is_dir() {
+            let kanji = path.join("kanji");
+            let path = if kanji.is_dir() { kanji } else { path.into() };
+
+            return Ok(Self {
+                files: Files::Directory(path),
+            });
+        }
+
+        let mut archive = ZipArchive::new(File::open(path)?)?;
+        let mut index = HashMap::new();
+        for i in 0..archive.len() {
+            let file = archive.by_index(i)?;
+            let Some(name) = file.name().rsplit('/').next() else {
+                continue;
+            };
+
+            if let Some(kanji) = kanji_of_file(name) {
+                index.insert(kanji, i);
+            }
+        }
+
+        Ok(Self {
+            files: Files::Archive(archive, index),
+        })
+    }
+
+    /// Returns the stroke order diagram of a kanji, if KanjiVG has one.
+    pub fn get(&mut self, kanji: char) -> io::Result<Option<StrokeOrder>> {
+        let svg = match &mut self.files {
+            Files::Directory(path) => match fs::read_to_string(path.join(file_name(kanji))) {
+                Ok(svg) => svg,
+                Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+                Err(err) => return Err(err),
+            },
+            Files::Archive(archive, index) => {
+                let Some(i) = index.get(&kanji) else {
+                    return Ok(None);
+                };
+
+                let mut svg = String::new();
+                archive.by_index(*i)?.read_to_string(&mut svg)?;
+                svg
+            }
+        };
+
+        Ok(Some(StrokeOrder::new(kanji, svg)))
+    }
+}
+
+/// Returns the kanji of a KanjiVG file name, `None` for variant forms and other files.
+fn kanji_of_file(name: &str) -> Option<char> {
+    let code = name.strip_suffix(".svg")?;
+    if code.contains('-') {
+        return None;
+    }
+
+    char::from_u32(u32::from_str_radix(code, 16).ok()?)
+}
+
+/// The stroke order diagram of a kanji.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StrokeOrder {
+    /// The kanji.
+    pub kanji: char,
+    /// The KanjiVG SVG file.
+    pub svg: String,
+}
+
+impl StrokeOrder {
+    /// Creates a new StrokeOrder from a KanjiVG SVG file.
+    pub fn new(kanji: char, svg: String) -> Self {
+        Self { kanji, svg }
+    }
+
+    /// Returns the path data of every stroke, in writing order.
+    pub fn strokes(&self) -> Vec<&str> {
+        let regex = Regex::new(r#"<path\s[^>]*?\bd="([^"]*)""#).unwrap();
+
+        regex
+            .captures_iter(&self.svg)
+            .filter_map(|captures| Some(captures.get(1)?.as_str()))
+            .collect()
+    }
+
+    /// Returns the number of strokes.
+    pub fn stroke_count(&self) -> usize {
+        self.strokes().len()
+    }
+
+    /// Compares the number of strokes with the stroke count of KANJIDIC, see
+    /// [`KanjidicEntry::strokes`](crate::dict::kanjidic::kanjidic_kanji::KanjidicEntry::strokes).
+    ///
+    /// # Returns
+    /// * The mismatch, or `None` if the counts agree or KANJIDIC has none.
+    pub fn check_strokes(&self, kanjidic: Option<u8>) -> Option<StrokeMismatch> {
+        let kanjidic = kanjidic?;
+        let kanjivg = self.stroke_count();
+
+        (kanjivg != kanjidic as usize).then_some(StrokeMismatch {
+            kanji: self.kanji,
+            kanjivg,
+            kanjidic,
+        })
+    }
+
+    /// Renders the numbered stroke order strip: one frame for every stroke, showing the strokes
+    /// before it in grey and the stroke itself in black with a dot where it starts.
+    pub fn strip_svg(&self) -> String {
+        let strokes = self.strokes();
+        let start = Regex::new(r"^\s*[Mm]\s*(-?[\d.]+)[\s,]*(-?[\d.]+)").unwrap();
+
+        let mut frames = String::new();
+        for (i, stroke) in strokes.iter().enumerate() {
+            frames += &format!("<g transform=\"translate({},0)\">", i * SIZE);
+
+            for before in &strokes[..i] {
+                frames += &format!("<path d=\"{}\" stroke=\"#999\"/>", before);
+            }
+            frames += &format!("<path d=\"{}\" stroke=\"#000\"/>", stroke);
+
+            if let Some(captures) = start.captures(stroke) {
+                frames += &format!(
+                    "<circle cx=\"{}\" cy=\"{}\" r=\"4\" fill=\"#c00\" stroke=\"none\"/>",
+                    &captures[1], &captures[2]
+                );
+            }
+
+            frames += &format!(
+                "<text x=\"4\" y=\"14\" font-size=\"12\" fill=\"#c00\" stroke=\"none\">{}</text></g>",
+                i + 1
+            );
+        }
+
+        format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"{SIZE}\" \
+             viewBox=\"0 0 {width} {SIZE}\"><g fill=\"none\" stroke-width=\"3\" \
+             stroke-linecap=\"round\" stroke-linejoin=\"round\">{frames}</g></svg>",
+            width = strokes.len() * SIZE,
+        )
+    }
+}
+
+/// A kanji whose stroke count differs between KanjiVG and KANJIDIC.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StrokeMismatch {
+    /// The kanji.
+    pub kanji: char,
+    /// The number of strokes of the KanjiVG diagram.
+    pub kanjivg: usize,
+    /// The stroke count of KANJIDIC.
+    pub kanjidic: u8,
+}
+
+impl fmt::Display for StrokeMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}: {} strokes in KanjiVG, {} in KANJIDIC",
+            self.kanji, self.kanjivg, self.kanjidic
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use zip::{write::SimpleFileOptions, ZipWriter};
+
+    use super::*;
+
+    /// A shortened KanjiVG file of 十.
+    const TEN: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<svg xmlns="http://www.w3.org/2000/svg" width="109" height="109" viewBox="0 0 109 109">
+<g id="kvg:StrokePaths_05341" style="fill:none;stroke:#000000;stroke-width:3;">
+<g id="kvg:05341" kvg:element="十">
+	<path id="kvg:05341-s1" kvg:type="㇐" d="M13.5,55.5c3,0.5,6,0.5,9,0.3c14-0.8,49-3.3,69-3.3"/>
+	<path id="kvg:05341-s2" kvg:type="㇑" d="M53.8,16.5c1.1,1.1,1.6,2.6,1.6,4.5c0,12,0,65,0,75"/>
+</g>
+</g>
+<g id="kvg:StrokeNumbers_05341" style="font-size:8;fill:#808080">
+	<text transform="matrix(1 0 0 1 5.5 52.5)">1</text>
+	<text transform="matrix(1 0 0 1 45.5 16.5)">2</text>
+</g>
+</svg>"#;
+
+    /// Tests reading the strokes, the strip and the stroke count check.
+    #[test]
+    fn strokes() {
+        let ten = StrokeOrder::new('十', TEN.to_owned());
+
+        assert_eq!(ten.stroke_count(), 2);
+        assert!(ten.strokes()[1].starts_with("M53.8,16.5"));
+        assert_eq!(ten.check_strokes(Some(2)), None);
+        assert_eq!(ten.check_strokes(None), None);
+        assert_eq!(
+            ten.check_strokes(Some(3)).unwrap().to_string(),
+            "十: 2 strokes in KanjiVG, 3 in KANJIDIC"
+        );
+
+        let strip = ten.strip_svg();
+        assert!(strip.contains("width=\"218\""));
+        assert_eq!(strip.matches("<path").count(), 3);
+        assert!(strip.contains("<circle cx=\"53.8\" cy=\"16.5\""));
+        assert!(strip.contains(">2</text>"));
+
+        assert_eq!(media_name('十'), "kanjivg_05341.svg");
+        assert_eq!(kanji_of_file("05341.svg"), Some('十'));
+        assert_eq!(kanji_of_file("05341-Kaisho.svg"), None);
+    }
+
+    /// Tests reading the files from a directory and from the release archive.
+    #[test]
+    fn open() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        fs::create_dir_all(dir.join("kanji")).unwrap();
+        fs::write(dir.join("kanji").join("05341.svg"), TEN).unwrap();
+
+        let mut kanjivg = KanjiVg::open(dir).unwrap();
+        assert_eq!(kanjivg.get('十').unwrap().unwrap().stroke_count(), 2);
+        assert_eq!(kanjivg.get('一').unwrap(), None);
+
+        let archive = dir.join("kanjivg.zip");
+        let mut zip = ZipWriter::new(File::create(&archive).unwrap());
+        for name in ["kanji/05341.svg", "kanji/05341-Kaisho.svg"] {
+            zip.start_file(name, SimpleFileOptions::default()).unwrap();
+            zip.write_all(TEN.as_bytes()).unwrap();
+        }
+        zip.finish().unwrap();
+
+        let mut kanjivg = KanjiVg::open(&archive).unwrap();
+        assert_eq!(kanjivg.get('十').unwrap().unwrap().svg, TEN);
+        assert_eq!(kanjivg.get('一').unwrap(), None);
+    }
+}
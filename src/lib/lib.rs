@@ -1,5 +1,10 @@
 pub mod anki;
+pub mod audio;
+pub mod config;
 pub mod dict;
 pub mod entry;
 pub mod japanese;
+pub mod kanjivg;
+pub mod pitch;
+pub mod romaji;
 pub mod tatoeba;
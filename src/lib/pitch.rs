@@ -0,0 +1,165 @@
+//! Rendering of Japanese pitch accent.
+//!
+//! An accent pattern is stored as its downstep position: the number of the mora after which the
+//! pitch drops, or 0 for words whose pitch never drops (heiban). The first mora is low unless the
+//! downstep follows it.
+
+/// Splits kana into morae.
+///
+/// Small ゃ, ゅ, ょ and small vowels belong to the mora before them, while っ, ん and ー are
+/// morae of their own.
+///
+/// # Example
+/// ```
+/// use anki_utill::pitch::split_morae;
+///
+/// assert_eq!(split_morae("きょうと"), vec!["きょ", "う", "と"]);
+/// ```
+pub fn split_morae(kana: &str) -> Vec<String> {
+    let mut out: Vec<String> = Vec::new();
+
+    for c in kana.chars() {
+        match out.last_mut() {
+            Some(mora) if is_small(c) => mora.push(c),
+            _ => out.push(c.to_string()),
+        }
+    }
+
+    out
+}
+
+/// Returns whether a kana is written small and joins the mora before it.
+fn is_small(c: char) -> bool {
+    "ぁぃぅぇぉゃゅょゎァィゥェォャュョヮ".contains(c)
+}
+
+/// Returns for every mora whether it is pronounced high.
+///
+/// # Arguments
+/// * `morae` - The number of morae in the word.
+/// * `position` - The downstep position, 0 for heiban.
+pub fn pitch_pattern(morae: usize, position: u8) -> Vec<bool> {
+    let position = position as usize;
+
+    (1..=morae)
+        .map(|mora| match position {
+            1 => mora == 1,
+            0 => mora != 1,
+            _ => mora != 1 && mora <= position,
+        })
+        .collect()
+}
+
+/// Renders an accent pattern as an HTML pitch graph.
+///
+/// Every high mora is overlined and the downstep is drawn as a line down after its mora, so a
+/// drop after the last mora (odaka) is distinct from heiban. The styles are inline so the graph
+/// needs no CSS in the note type.
+///
+/// # Example
+/// ```
+/// use anki_utill::pitch::pitch_html;
+///
+/// assert_eq!(
+///     pitch_html("はし", 1),
+///     "<span class=\"pitch\">\
+///      <span style=\"border-top: 1px solid; border-right: 1px solid\">は</span>\
+///      <span>し</span></span>"
+/// );
+/// ```
+pub fn pitch_html(kana: &str, position: u8) -> String {
+    let morae = split_morae(kana);
+    let pattern = pitch_pattern(morae.len(), position);
+
+    let mut out = "<span class=\"pitch\">".to_owned();
+
+    for (i, (mora, high)) in morae.iter().zip(pattern).enumerate() {
+        let mut style = Vec::new();
+
+        if high {
+            style.push("border-top: 1px solid");
+        }
+        if i + 1 == position as usize {
+            style.push("border-right: 1px solid");
+        }
+
+        if style.is_empty() {
+            out += &format!("<span>{}</span>", mora);
+        } else {
+            out += &format!("<span style=\"{}\">{}</span>", style.join("; "), mora);
+        }
+    }
+
+    out + "</span>"
+}
+
+/// Renders an accent pattern in a bracketed notation: `ꜛ` marks the rise in pitch, `ꜜ` the
+/// downstep and the downstep position follows in brackets.
+///
+/// # Example
+/// ```
+/// use anki_utill::pitch::pitch_notation;
+///
+/// assert_eq!(pitch_notation("はし", 2), "はꜛしꜜ [2]");
+/// assert_eq!(pitch_notation("さかな", 0), "さꜛかな [0]");
+/// ```
+pub fn pitch_notation(kana: &str, position: u8) -> String {
+    let morae = split_morae(kana);
+    let pattern = pitch_pattern(morae.len(), position);
+
+    let mut out = String::new();
+
+    for (i, mora) in morae.iter().enumerate() {
+        out += mora;
+
+        let next_high = pattern.get(i + 1).copied();
+        if pattern[i] && (next_high == Some(false) || i + 1 == position as usize) {
+            out.push('ꜜ');
+        } else if !pattern[i] && next_high == Some(true) {
+            out.push('ꜛ');
+        }
+    }
+
+    format!("{} [{}]", out, position)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tests splitting kana into morae.
+    #[test]
+    fn morae() {
+        assert_eq!(split_morae("がっこう"), vec!["が", "っ", "こ", "う"]);
+        assert_eq!(split_morae("シャツ"), vec!["シャ", "ツ"]);
+        assert_eq!(split_morae("ラーメン"), vec!["ラ", "ー", "メ", "ン"]);
+        assert!(split_morae("").is_empty());
+    }
+
+    /// Tests the high and low morae of every kind of pattern.
+    #[test]
+    fn patterns() {
+        // Heiban, atamadaka, nakadaka and odaka
+        assert_eq!(pitch_pattern(3, 0), vec![false, true, true]);
+        assert_eq!(pitch_pattern(3, 1), vec![true, false, false]);
+        assert_eq!(pitch_pattern(3, 2), vec![false, true, false]);
+        assert_eq!(pitch_pattern(3, 3), vec![false, true, true]);
+        assert_eq!(pitch_pattern(1, 0), vec![false]);
+    }
+
+    /// Tests that heiban and odaka render differently.
+    #[test]
+    fn render() {
+        assert_eq!(pitch_notation("はし", 1), "はꜜし [1]");
+        assert_eq!(pitch_notation("きのう", 0), "きꜛのう [0]");
+        assert_eq!(pitch_notation("おとうと", 4), "おꜛとうとꜜ [4]");
+        assert_eq!(pitch_notation("きょうと", 1), "きょꜜうと [1]");
+
+        assert_ne!(pitch_html("はし", 0), pitch_html("はし", 2));
+        assert_eq!(
+            pitch_html("はし", 2),
+            "<span class=\"pitch\"><span>は</span>\
+             <span style=\"border-top: 1px solid; border-right: 1px solid\">し</span></span>"
+        );
+    }
+}
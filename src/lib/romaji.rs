@@ -0,0 +1,550 @@
+use std::sync::OnceLock;
+
+use crate::japanese::JapaneseStr;
+
+/// The romanization systems supported by [`to_romaji`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RomajiSystem {
+    /// Modified Hepburn, e.g. `shinbun`, `chotto` and `tōkyō`.
+    #[default]
+    Hepburn,
+    /// Kunrei-shiki, e.g. `sinbun`, `tyotto` and `tôkyô`.
+    Kunrei,
+    /// Nihon-shiki, like Kunrei-shiki but keeping ぢ, づ and を apart, e.g. `dukue`.
+    Nihon,
+}
+
+/// Hiragana with their Hepburn, Kunrei-shiki and Nihon-shiki spellings.
+const KANA: [(&str, [&str; 3]); 83] = [
+    ("あ", ["a", "a", "a"]),
+    ("い", ["i", "i", "i"]),
+    ("う", ["u", "u", "u"]),
+    ("え", ["e", "e", "e"]),
+    ("お", ["o", "o", "o"]),
+    ("か", ["ka", "ka", "ka"]),
+    ("き", ["ki", "ki", "ki"]),
+    ("く", ["ku", "ku", "ku"]),
+    ("け", ["ke", "ke", "ke"]),
+    ("こ", ["ko", "ko", "ko"]),
+    ("が", ["ga", "ga", "ga"]),
+    ("ぎ", ["gi", "gi", "gi"]),
+    ("ぐ", ["gu", "gu", "gu"]),
+    ("げ", ["ge", "ge", "ge"]),
+    ("ご", ["go", "go", "go"]),
+    ("さ", ["sa", "sa", "sa"]),
+    ("し", ["shi", "si", "si"]),
+    ("す", ["su", "su", "su"]),
+    ("せ", ["se", "se", "se"]),
+    ("そ", ["so", "so", "so"]),
+    ("ざ", ["za", "za", "za"]),
+    ("じ", ["ji", "zi", "zi"]),
+    ("ず", ["zu", "zu", "zu"]),
+    ("ぜ", ["ze", "ze", "ze"]),
+    ("ぞ", ["zo", "zo", "zo"]),
+    ("た", ["ta", "ta", "ta"]),
+    ("ち", ["chi", "ti", "ti"]),
+    ("つ", ["tsu", "tu", "tu"]),
+    ("て", ["te", "te", "te"]),
+    ("と", ["to", "to", "to"]),
+    ("だ", ["da", "da", "da"]),
+    ("ぢ", ["ji", "zi", "di"]),
+    ("づ", ["zu", "zu", "du"]),
+    ("で", ["de", "de", "de"]),
+    ("ど", ["do", "do", "do"]),
+    ("な", ["na", "na", "na"]),
+    ("に", ["ni", "ni", "ni"]),
+    ("ぬ", ["nu", "nu", "nu"]),
+    ("ね", ["ne", "ne", "ne"]),
+    ("の", ["no", "no", "no"]),
+    ("は", ["ha", "ha", "ha"]),
+    ("ひ", ["hi", "hi", "hi"]),
+    ("ふ", ["fu", "hu", "hu"]),
+    ("へ", ["he", "he", "he"]),
+    ("ほ", ["ho", "ho", "ho"]),
+    ("ば", ["ba", "ba", "ba"]),
+    ("び", ["bi", "bi", "bi"]),
+    ("ぶ", ["bu", "bu", "bu"]),
+    ("べ", ["be", "be", "be"]),
+    ("ぼ", ["bo", "bo", "bo"]),
+    ("ぱ", ["pa", "pa", "pa"]),
+    ("ぴ", ["pi", "pi", "pi"]),
+    ("ぷ", ["pu", "pu", "pu"]),
+    ("ぺ", ["pe", "pe", "pe"]),
+    ("ぽ", ["po", "po", "po"]),
+    ("ま", ["ma", "ma", "ma"]),
+    ("み", ["mi", "mi", "mi"]),
+    ("む", ["mu", "mu", "mu"]),
+    ("め", ["me", "me", "me"]),
+    ("も", ["mo", "mo", "mo"]),
+    ("や", ["ya", "ya", "ya"]),
+    ("ゆ", ["yu", "yu", "yu"]),
+    ("よ", ["yo", "yo", "yo"]),
+    ("ら", ["ra", "ra", "ra"]),
+    ("り", ["ri", "ri", "ri"]),
+    ("る", ["ru", "ru", "ru"]),
+    ("れ", ["re", "re", "re"]),
+    ("ろ", ["ro", "ro", "ro"]),
+    ("わ", ["wa", "wa", "wa"]),
+    ("ゐ", ["i", "i", "wi"]),
+    ("ゑ", ["e", "e", "we"]),
+    ("を", ["o", "o", "wo"]),
+    ("ゔ", ["vu", "vu", "vu"]),
+    ("ぁ", ["a", "a", "a"]),
+    ("ぃ", ["i", "i", "i"]),
+    ("ぅ", ["u", "u", "u"]),
+    ("ぇ", ["e", "e", "e"]),
+    ("ぉ", ["o", "o", "o"]),
+    ("ゃ", ["ya", "ya", "ya"]),
+    ("ゅ", ["yu", "yu", "yu"]),
+    ("ょ", ["yo", "yo", "yo"]),
+    ("ゎ", ["wa", "wa", "wa"]),
+    ("ゕ", ["ka", "ka", "ka"]),
+];
+
+/// The consonants of the kana combined with small ゃ, ゅ and ょ.
+const YOON: [(&str, [&str; 3]); 12] = [
+    ("き", ["ky", "ky", "ky"]),
+    ("ぎ", ["gy", "gy", "gy"]),
+    ("し", ["sh", "sy", "sy"]),
+    ("じ", ["j", "zy", "zy"]),
+    ("ち", ["ch", "ty", "ty"]),
+    ("ぢ", ["j", "zy", "dy"]),
+    ("に", ["ny", "ny", "ny"]),
+    ("ひ", ["hy", "hy", "hy"]),
+    ("び", ["by", "by", "by"]),
+    ("ぴ", ["py", "py", "py"]),
+    ("み", ["my", "my", "my"]),
+    ("り", ["ry", "ry", "ry"]),
+];
+
+/// Combinations used for loanwords, spelled the same in every system.
+const EXTENDED: [(&str, &str); 18] = [
+    ("ふぁ", "fa"),
+    ("ふぃ", "fi"),
+    ("ふぇ", "fe"),
+    ("ふぉ", "fo"),
+    ("てぃ", "ti"),
+    ("でぃ", "di"),
+    ("とぅ", "tu"),
+    ("どぅ", "du"),
+    ("しぇ", "she"),
+    ("じぇ", "je"),
+    ("ちぇ", "che"),
+    ("うぃ", "wi"),
+    ("うぇ", "we"),
+    ("うぉ", "wo"),
+    ("ゔぁ", "va"),
+    ("ゔぃ", "vi"),
+    ("ゔぇ", "ve"),
+    ("ゔぉ", "vo"),
+];
+
+/// Romaji only accepted by [`from_romaji`], e.g. the `x` and `l` prefixes of small kana.
+const INPUT_ONLY: [(&str, &str); 16] = [
+    ("xa", "ぁ"),
+    ("xi", "ぃ"),
+    ("xu", "ぅ"),
+    ("xe", "ぇ"),
+    ("xo", "ぉ"),
+    ("la", "ぁ"),
+    ("li", "ぃ"),
+    ("lu", "ぅ"),
+    ("le", "ぇ"),
+    ("lo", "ぉ"),
+    ("xya", "ゃ"),
+    ("xyu", "ゅ"),
+    ("xyo", "ょ"),
+    ("xtu", "っ"),
+    ("xtsu", "っ"),
+    ("ltu", "っ"),
+];
+
+/// Returns every kana or kana combination with its spelling in each system.
+fn romaji_table() -> &'static [(String, [String; 3])] {
+    static TABLE: OnceLock<Vec<(String, [String; 3])>> = OnceLock::new();
+
+    TABLE.get_or_init(|| {
+        let mut table: Vec<(String, [String; 3])> = KANA
+            .iter()
+            .map(|(kana, romaji)| (kana.to_string(), romaji.map(str::to_owned)))
+            .collect();
+
+        for (kana, consonant) in YOON {
+            for (small, vowel) in [("ゃ", "a"), ("ゅ", "u"), ("ょ", "o")] {
+                table.push((
+                    format!("{kana}{small}"),
+                    consonant.map(|consonant| format!("{consonant}{vowel}")),
+                ));
+            }
+        }
+
+        for (kana, romaji) in EXTENDED {
+            table.push((kana.to_owned(), [romaji, romaji, romaji].map(str::to_owned)));
+        }
+
+        table
+    })
+}
+
+/// Returns the spelling of the kana at the start of the slice, preferring combinations, together
+/// with the number of characters used.
+fn lookup(chars: &[char], system: RomajiSystem) -> Option<(&'static str, usize)> {
+    let table = romaji_table();
+
+    for len in [2, 1] {
+        if chars.len() < len {
+            continue;
+        }
+
+        let kana: String = chars[..len].iter().collect();
+        if let Some((_, romaji)) = table.iter().find(|(entry, _)| *entry == kana) {
+            return Some((romaji[system as usize].as_str(), len));
+        }
+    }
+
+    None
+}
+
+/// Returns the long form of a vowel: with a macron for Hepburn, and a circumflex otherwise.
+fn long_vowel(vowel: char, system: RomajiSystem) -> Option<char> {
+    let (macron, circumflex) = match vowel {
+        'a' => ('ā', 'â'),
+        'i' => ('ī', 'î'),
+        'u' => ('ū', 'û'),
+        'e' => ('ē', 'ê'),
+        'o' => ('ō', 'ô'),
+        _ => return None,
+    };
+
+    Some(match system {
+        RomajiSystem::Hepburn => macron,
+        _ => circumflex,
+    })
+}
+
+/// Transliterates hiragana and katakana to romaji. Other characters are kept as is.
+///
+/// * っ doubles the next consonant, written `tch` before `ch` in Hepburn.
+/// * ん is written `n'` before a vowel or `y`.
+/// * ー and the long vowels おう, おお, うう, ああ and ええ are written with a macron, or a
+///   circumflex in Kunrei-shiki and Nihon-shiki.
+///
+/// # Example
+/// ```
+/// use anki_utill::romaji::{to_romaji, RomajiSystem};
+///
+/// assert_eq!(to_romaji("とうきょう", RomajiSystem::Hepburn), "tōkyō");
+/// assert_eq!(to_romaji("ちょっと", RomajiSystem::Kunrei), "tyotto");
+/// ```
+pub fn to_romaji(text: &str, system: RomajiSystem) -> String {
+    transliterate(text, system, false)
+}
+
+/// Transliterates the reading of a verb like [`to_romaji`], but keeps the final kana apart as
+/// it is the verb ending, e.g. `omou` for 思う rather than `omō`.
+///
+/// # Example
+/// ```
+/// use anki_utill::romaji::{to_romaji_verb, RomajiSystem};
+///
+/// assert_eq!(to_romaji_verb("すう", RomajiSystem::Hepburn), "suu");
+/// assert_eq!(to_romaji_verb("そうだんする", RomajiSystem::Hepburn), "sōdansuru");
+/// ```
+pub fn to_romaji_verb(text: &str, system: RomajiSystem) -> String {
+    transliterate(text, system, true)
+}
+
+/// Transliterates kana to romaji, see [`to_romaji`]. The final kana is not merged into a long
+/// vowel if `verb` is set.
+fn transliterate(text: &str, system: RomajiSystem, verb: bool) -> String {
+    let chars: Vec<char> = text.to_hiragana().chars().collect();
+    let mut out = String::new();
+    let mut geminate = false;
+    // The vowel that ended the last syllable, which a following vowel can lengthen
+    let mut last_vowel: Option<char> = None;
+    let mut i = 0;
+
+    while i < chars.len() {
+        let char = chars[i];
+
+        if char == 'っ' {
+            geminate = true;
+            last_vowel = None;
+            i += 1;
+            continue;
+        }
+
+        if char == 'ー' {
+            match last_vowel.and_then(|vowel| long_vowel(vowel, system)) {
+                Some(long) => {
+                    out.pop();
+                    out.push(long);
+                }
+                None => out.push('-'),
+            }
+            last_vowel = None;
+            i += 1;
+            continue;
+        }
+
+        if char == 'ん' {
+            out.push('n');
+            if let Some((next, _)) = lookup(&chars[i + 1..], system) {
+                if next.starts_with(['a', 'i', 'u', 'e', 'o', 'y']) {
+                    out.push('\'');
+                }
+            }
+            geminate = false;
+            last_vowel = None;
+            i += 1;
+            continue;
+        }
+
+        let Some((romaji, len)) = lookup(&chars[i..], system) else {
+            out.push(char);
+            geminate = false;
+            last_vowel = None;
+            i += 1;
+            continue;
+        };
+        i += len;
+
+        // A lone vowel lengthening the previous one
+        let lengthens = matches!(
+            (last_vowel, romaji),
+            (Some('o'), "u" | "o") | (Some('u'), "u") | (Some('a'), "a") | (Some('e'), "e")
+        );
+        let ending = verb && i == chars.len();
+        if lengthens && len == 1 && !ending {
+            let long = long_vowel(last_vowel.unwrap(), system).unwrap();
+            out.pop();
+            out.push(long);
+            last_vowel = None;
+            continue;
+        }
+
+        if geminate {
+            if system == RomajiSystem::Hepburn && romaji.starts_with("ch") {
+                out.push('t');
+            } else if let Some(consonant) = romaji.chars().next().filter(|c| !"aiueo".contains(*c))
+            {
+                out.push(consonant);
+            }
+            geminate = false;
+        }
+
+        out += romaji;
+        last_vowel = romaji.chars().last();
+    }
+
+    out
+}
+
+/// Returns every romaji spelling accepted by [`from_romaji`] with its kana, longest first.
+fn input_table() -> &'static [(String, String)] {
+    static TABLE: OnceLock<Vec<(String, String)>> = OnceLock::new();
+
+    TABLE.get_or_init(|| {
+        let mut table: Vec<(String, String)> = Vec::new();
+
+        // The first kana of a spelling wins, e.g. `ji` is じ rather than ぢ
+        for (kana, spellings) in romaji_table() {
+            for romaji in spellings {
+                if !table.iter().any(|(entry, _)| entry == romaji) {
+                    table.push((romaji.clone(), kana.clone()));
+                }
+            }
+        }
+
+        for (romaji, kana) in INPUT_ONLY {
+            table.push((romaji.to_owned(), kana.to_owned()));
+        }
+
+        table.sort_by_key(|(romaji, _)| std::cmp::Reverse(romaji.len()));
+        table
+    })
+}
+
+/// Converts romaji to hiragana like an IME. Accepts Hepburn, Kunrei-shiki and Nihon-shiki.
+///
+/// * A doubled consonant, or `t` before `ch`, becomes っ.
+/// * `n` becomes ん before a consonant, at the end, or when written as `nn` or `n'`.
+/// * `-` becomes ー, and vowels with a macron or circumflex are written as two kana, with ō as
+///   おう.
+///
+/// Characters that are not romaji are kept as is.
+///
+/// # Example
+/// ```
+/// use anki_utill::romaji::from_romaji;
+///
+/// assert_eq!(from_romaji("gakkō"), "がっこう");
+/// assert_eq!(from_romaji("kon'ya"), "こんや");
+/// ```
+pub fn from_romaji(text: &str) -> String {
+    let text: String = text
+        .to_lowercase()
+        .chars()
+        .map(|char| match char {
+            'ā' | 'â' => "aa".to_owned(),
+            'ī' | 'î' => "ii".to_owned(),
+            'ū' | 'û' => "uu".to_owned(),
+            'ē' | 'ê' => "ee".to_owned(),
+            'ō' | 'ô' => "ou".to_owned(),
+            _ => char.to_string(),
+        })
+        .collect();
+
+    let is_vowel = |char: Option<char>| char.is_some_and(|char| "aiueoy".contains(char));
+    let mut out = String::new();
+    let mut rest = text.as_str();
+
+    while let Some(char) = rest.chars().next() {
+        let next = rest[char.len_utf8()..].chars().next();
+
+        if char == 'n' && next == Some('\'') {
+            out.push('ん');
+            rest = &rest[2..];
+            continue;
+        }
+
+        if char == 'n' && !is_vowel(next) {
+            out.push('ん');
+            // `nn` is a single ん unless the second n starts a syllable
+            let after = rest.get(2..).and_then(|after| after.chars().next());
+            rest = if next == Some('n') && !is_vowel(after) {
+                &rest[2..]
+            } else {
+                &rest[1..]
+            };
+            continue;
+        }
+
+        let doubled = next == Some(char) || (char == 't' && rest[1..].starts_with("ch"));
+        if char.is_ascii_alphabetic() && !"aiueon".contains(char) && doubled {
+            out.push('っ');
+            rest = &rest[1..];
+            continue;
+        }
+
+        if char == '-' {
+            out.push('ー');
+            rest = &rest[1..];
+            continue;
+        }
+
+        match input_table()
+            .iter()
+            .find(|(romaji, _)| rest.starts_with(romaji.as_str()))
+        {
+            Some((romaji, kana)) => {
+                out += kana;
+                rest = &rest[romaji.len()..];
+            }
+            None => {
+                out.push(char);
+                rest = &rest[char.len_utf8()..];
+            }
+        }
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tests every system, including gemination, ん, long vowels and ー.
+    #[test]
+    fn convert_to_romaji() {
+        let cases = [
+            ("しんぶん", ["shinbun", "sinbun", "sinbun"]),
+            ("ちょっと", ["chotto", "tyotto", "tyotto"]),
+            ("まっちゃ", ["matcha", "mattya", "mattya"]),
+            ("こんや", ["kon'ya", "kon'ya", "kon'ya"]),
+            ("きんえん", ["kin'en", "kin'en", "kin'en"]),
+            ("とうきょう", ["tōkyō", "tôkyô", "tôkyô"]),
+            ("ラーメン", ["rāmen", "râmen", "râmen"]),
+            ("つづく", ["tsuzuku", "tuzuku", "tuduku"]),
+            ("ふじさん", ["fujisan", "huzisan", "huzisan"]),
+            ("パーティー", ["pātī", "pâtî", "pâtî"]),
+            ("ほんをよむ", ["hon'oyomu", "hon'oyomu", "honwoyomu"]),
+        ];
+
+        for (kana, expected) in cases {
+            for (system, expected) in [
+                RomajiSystem::Hepburn,
+                RomajiSystem::Kunrei,
+                RomajiSystem::Nihon,
+            ]
+            .into_iter()
+            .zip(expected)
+            {
+                assert_eq!(to_romaji(kana, system), expected, "{kana} {system:?}");
+            }
+        }
+
+        // Non kana are kept
+        assert_eq!(to_romaji("猫はねこ", RomajiSystem::Hepburn), "猫haneko");
+    }
+
+    /// Tests that the ending of a verb is not merged into a long vowel.
+    #[test]
+    fn verb_to_romaji() {
+        let cases = [
+            ("おもう", "omou"),
+            ("すう", "suu"),
+            ("とう", "tou"),
+            ("かう", "kau"),
+            ("おおう", "ōu"),
+            ("べんきょうする", "benkyōsuru"),
+        ];
+
+        for (kana, expected) in cases {
+            assert_eq!(
+                to_romaji_verb(kana, RomajiSystem::Hepburn),
+                expected,
+                "{kana}"
+            );
+        }
+
+        assert_eq!(to_romaji("おもう", RomajiSystem::Hepburn), "omō");
+        assert_eq!(to_romaji_verb("すう", RomajiSystem::Kunrei), "suu");
+    }
+
+    /// Tests the romaji conversions of strings and furigana.
+    #[test]
+    fn japanese_str() {
+        assert_eq!("tokkyū", "特[とっ]急[きゅう]".to_kana().to_romaji());
+        assert_eq!("tokkyû", "とっきゅう".to_romaji_with(RomajiSystem::Kunrei));
+        assert_eq!("とっきゅう", "tokkyuu".from_romaji());
+    }
+
+    /// Tests IME style input in every system.
+    #[test]
+    fn convert_from_romaji() {
+        let cases = [
+            ("shinbun", "しんぶん"),
+            ("sinbun", "しんぶん"),
+            ("konnichiha", "こんにちは"),
+            ("kon'ya", "こんや"),
+            ("konnya", "こんにゃ"),
+            ("kanji", "かんじ"),
+            ("kannji", "かんじ"),
+            ("matcha", "まっちゃ"),
+            ("tyotto", "ちょっと"),
+            ("gakkō", "がっこう"),
+            ("Tôkyô", "とうきょう"),
+            ("ra-men", "らーめん"),
+            ("tuduku", "つづく"),
+            ("hon", "ほん"),
+            ("wo", "を"),
+            ("xtu", "っ"),
+        ];
+
+        for (romaji, kana) in cases {
+            assert_eq!(from_romaji(romaji), kana, "{romaji}");
+        }
+    }
+}
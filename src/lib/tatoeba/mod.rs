@@ -1 +1,3 @@
+pub mod tatoeba_cache;
+pub mod tatoeba_corpus;
 pub mod tatoeba_search;
@@ -0,0 +1,184 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use serde::{Deserialize, Serialize};
+use sha1::{Digest, Sha1};
+
+/// A response saved by [`TatoebaCache`].
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct CachedResponse {
+    /// The full url of the request.
+    pub url: String,
+    /// When the response was fetched, in seconds since the Unix epoch.
+    pub fetched: u64,
+    /// The body of the response.
+    pub body: String,
+}
+
+/// Saves the responses of the Tatoeba API on disk, one JSON file per full request url, so
+/// repeated searches cost no requests while the responses are younger than the time to live.
+///
+/// A replaying cache never expires responses and never fetches: searches missing from it fail.
+/// Tests use it to replay saved responses offline. A read-only cache uses the saved responses
+/// but does not save new ones, e.g. during a dry run.
+///
+/// # Example
+/// ```no_run
+/// use std::{path::Path, time::Duration};
+///
+/// use anki_utill::tatoeba::{tatoeba_cache::TatoebaCache, tatoeba_search::TatoebaSearch};
+///
+/// let cache = TatoebaCache::new(
+///     Path::new("result/tatoeba_cache"),
+///     Some(Duration::from_secs(30 * 24 * 60 * 60)),
+/// );
+/// let response = TatoebaSearch::new("jpn", "eng")
+///     .search_cached(&cache, "猫", None)
+///     .unwrap();
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TatoebaCache {
+    /// The directory of the saved responses.
+    dir: PathBuf,
+    /// How long responses are used, `None` to keep them forever.
+    ttl: Option<Duration>,
+    /// Whether only saved responses are used.
+    replay: bool,
+    /// Whether new responses are not saved.
+    read_only: bool,
+}
+
+impl TatoebaCache {
+    /// Creates a cache saving responses in `dir` and using them for `ttl`.
+    pub fn new(dir: &Path, ttl: Option<Duration>) -> Self {
+        Self {
+            dir: dir.to_owned(),
+            ttl,
+            replay: false,
+            read_only: false,
+        }
+    }
+
+    /// Creates a cache only replaying the responses saved in `dir`.
+    pub fn replay(dir: &Path) -> Self {
+        Self {
+            dir: dir.to_owned(),
+            ttl: None,
+            replay: true,
+            read_only: false,
+        }
+    }
+
+    /// Makes the cache leave new responses unsaved.
+    pub fn read_only(mut self) -> Self {
+        self.read_only = true;
+        self
+    }
+
+    /// Returns whether only saved responses are used.
+    pub fn is_replay(&self) -> bool {
+        self.replay
+    }
+
+    /// Returns the file the response of a url is saved in.
+    pub fn path(&self, url: &str) -> PathBuf {
+        let hash: String = Sha1::digest(url.as_bytes())
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect();
+
+        self.dir.join(format!("{}.json", hash))
+    }
+
+    /// Returns the saved response body of a url, `None` if there is none or it has expired.
+    pub fn get(&self, url: &str) -> Option<String> {
+        let file = fs::read_to_string(self.path(url)).ok()?;
+        let response: CachedResponse = serde_json::from_str(&file).ok()?;
+        if response.url != url {
+            return None;
+        }
+
+        let expired = self
+            .ttl
+            .is_some_and(|ttl| now().saturating_sub(response.fetched) >= ttl.as_secs());
+        if expired && !self.replay {
+            return None;
+        }
+
+        Some(response.body)
+    }
+
+    /// Returns whether a url has a saved response that has not expired.
+    pub fn contains(&self, url: &str) -> bool {
+        self.get(url).is_some()
+    }
+
+    /// Saves the response body of a url, creating the directory if needed. Does nothing if the
+    /// cache is read-only.
+    pub fn store(&self, url: &str, body: &str) -> io::Result<()> {
+        if self.read_only {
+            return Ok(());
+        }
+
+        fs::create_dir_all(&self.dir)?;
+
+        let response = CachedResponse {
+            url: url.to_owned(),
+            fetched: now(),
+            body: body.to_owned(),
+        };
+
+        fs::write(self.path(url), serde_json::to_string(&response)?)
+    }
+}
+
+/// Returns the current time in seconds since the Unix epoch.
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |time| time.as_secs())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tatoeba::tatoeba_search::TatoebaSearch;
+
+    /// Tests saving responses, expiring them and replaying them offline.
+    #[test]
+    fn cache() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path().join("cache");
+
+        let search = TatoebaSearch::new("jpn", "eng");
+        let url = search.to_string("食べる", None);
+        let cache = TatoebaCache::new(&dir, Some(Duration::from_secs(60)));
+        assert!(!cache.contains(&url));
+
+        let read_only = cache.clone().read_only();
+        read_only
+            .store(&url, include_str!("./test_data.json"))
+            .unwrap();
+        assert!(!read_only.contains(&url));
+        assert!(!dir.exists());
+
+        cache.store(&url, include_str!("./test_data.json")).unwrap();
+        assert!(cache.contains(&url));
+        assert!(!TatoebaCache::new(&dir, Some(Duration::ZERO)).contains(&url));
+        assert!(TatoebaCache::new(&dir, None).contains(&url));
+
+        let replay = TatoebaCache::replay(&dir);
+        let response = search.search_cached(&replay, "食べる", None).unwrap();
+        assert_eq!(response.data.len(), 10);
+        assert!(search.search_cached(&replay, "飲む", None).is_err());
+
+        // The second page is not saved, so replaying ends with an error after the first
+        let entries: Vec<_> = search.search_iter_cached(&replay, "食べる", None).collect();
+        assert_eq!(entries.len(), 11);
+        assert!(entries[..10].iter().all(Result::is_ok));
+        assert!(entries[10].is_err());
+    }
+}
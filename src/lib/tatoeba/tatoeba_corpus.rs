@@ -0,0 +1,768 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fs::File,
+    io::{self, BufRead, BufReader},
+    path::Path,
+};
+
+use crate::japanese::JapaneseStr;
+
+use super::tatoeba_search::{
+    TatoebaAudio, TatoebaEntry, TatoebaSearch, TatoebaSort, TatoebaTranscription,
+    TatoebaTranslation,
+};
+
+/// The license of the sentences of the exports.
+pub const LICENSE: &str = "CC BY 2.0 FR";
+
+/// The languages written without spaces, whose sentence length is counted in characters.
+const UNSPACED_LANGUAGES: [&str; 10] = [
+    "jpn", "cmn", "yue", "wuu", "lzh", "tha", "khm", "lao", "mya", "bod",
+];
+
+/// A sentence of the corpus.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CorpusSentence {
+    /// The sentence id.
+    pub id: u32,
+    /// The language code, e.g. `jpn`.
+    pub lang: String,
+    /// The text of the sentence.
+    pub text: String,
+    /// The username of the owner, `None` for orphan sentences.
+    pub owner: Option<String>,
+}
+
+/// The Tatoeba sentences, read from the official exports at <https://tatoeba.org/downloads>,
+/// answering [`TatoebaSearch`] filters without the API.
+///
+/// The corpus directory holds the extracted tab-separated files:
+/// * `sentences_detailed.csv` or `sentences.csv` - The sentences. Only the detailed file has
+///   their owners, which the orphan, owner and native filters need. Without it these filters
+///   pass every sentence.
+/// * `links.csv` - The translation links.
+/// * `jpn_indices.csv` - Optional, the dictionary forms of the words of Japanese sentences,
+///   so inflected words are found.
+/// * `sentences_with_audio.csv` - Optional, the audio recordings.
+/// * `user_languages.csv` - Optional, the languages of the users, for the native filter.
+/// * `transcriptions.csv` - Optional, the furigana of Japanese sentences.
+///
+/// Sentences are looked up through an index of their words, or of their characters and character
+/// pairs for languages without spaces, and then checked to contain the query.
+///
+/// The `origin`, `tag` and `list` filters are not part of the exports and are ignored, as are
+/// unapproved sentences, which the exports leave out. The `limit` is the page size of the API:
+/// every match is returned.
+///
+/// # Example
+/// ```no_run
+/// use std::path::Path;
+///
+/// use anki_utill::tatoeba::{tatoeba_corpus::TatoebaCorpus, tatoeba_search::TatoebaSearch};
+///
+/// let corpus = TatoebaCorpus::open(Path::new("input/tatoeba"), &["jpn", "eng"]).unwrap();
+/// for entry in corpus.search(&TatoebaSearch::new("jpn", "eng"), "猫") {
+///     println!("{}", entry.text);
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct TatoebaCorpus {
+    /// The languages read, empty for every language.
+    languages: HashSet<String>,
+    /// The sentences by their id.
+    sentences: HashMap<u32, CorpusSentence>,
+    /// The ids of the sentences of every language, in id order.
+    by_lang: HashMap<String, Vec<u32>>,
+    /// The ids of the sentences using a word in its dictionary form.
+    by_word: HashMap<String, HashSet<u32>>,
+    /// The ids of the sentences containing a term, see [`terms`], in id order.
+    by_term: HashMap<String, Vec<u32>>,
+    /// Whether the owners of the sentences are known.
+    has_owners: bool,
+    /// The translations of every sentence, in both directions.
+    links: HashMap<u32, Vec<u32>>,
+    /// The audio recordings by sentence id.
+    audios: HashMap<u32, Vec<TatoebaAudio>>,
+    /// The transcriptions by sentence id.
+    transcriptions: HashMap<u32, Vec<TatoebaTranscription>>,
+    /// The `(language, username)` of self-identified native speakers.
+    natives: HashSet<(String, String)>,
+}
+
+/// Returns the value of a column, `None` for the empty and `\N` values of the exports.
+fn column(value: Option<&str>) -> Option<&str> {
+    value.filter(|value| !value.is_empty() && *value != "\\N")
+}
+
+/// Calls `f` with the columns of every line of a tab-separated file.
+fn read_rows<R, F>(reader: R, mut f: F) -> io::Result<()>
+where
+    R: BufRead,
+    F: FnMut(&[&str]),
+{
+    for line in reader.lines() {
+        let line = line?;
+        let columns: Vec<&str> = line.split('\t').collect();
+        f(&columns);
+    }
+
+    Ok(())
+}
+
+impl TatoebaCorpus {
+    /// Reads the exports in a directory, keeping the sentences of the given languages, or of
+    /// every language if none are given.
+    pub fn open(dir: &Path, languages: &[&str]) -> io::Result<Self> {
+        let mut corpus = Self::new(languages);
+        let open = |name: &str| -> io::Result<Option<BufReader<File>>> {
+            let path = dir.join(name);
+            Ok(if path.is_file() {
+                Some(BufReader::new(File::open(path)?))
+            } else {
+                None
+            })
+        };
+
+        let sentences = match open("sentences_detailed.csv")? {
+            Some(file) => file,
+            None => open("sentences.csv")?.ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::NotFound,
+                    format!("No sentences.csv in {}", dir.display()),
+                )
+            })?,
+        };
+        corpus.read_sentences(sentences)?;
+
+        if let Some(file) = open("links.csv")? {
+            corpus.read_links(file)?;
+        }
+        if let Some(file) = open("jpn_indices.csv")? {
+            corpus.read_indices(file)?;
+        }
+        if let Some(file) = open("sentences_with_audio.csv")? {
+            corpus.read_audio(file)?;
+        }
+        if let Some(file) = open("user_languages.csv")? {
+            corpus.read_user_languages(file)?;
+        }
+        if let Some(file) = open("transcriptions.csv")? {
+            corpus.read_transcriptions(file)?;
+        }
+
+        Ok(corpus)
+    }
+
+    /// Creates an empty corpus keeping the sentences of the given languages, or of every
+    /// language if none are given.
+    pub fn new(languages: &[&str]) -> Self {
+        Self {
+            languages: languages.iter().map(|lang| lang.to_string()).collect(),
+            ..Default::default()
+        }
+    }
+
+    /// Returns the number of sentences.
+    pub fn len(&self) -> usize {
+        self.sentences.len()
+    }
+
+    /// Returns whether there are no sentences.
+    pub fn is_empty(&self) -> bool {
+        self.sentences.is_empty()
+    }
+
+    /// Returns a sentence by its id.
+    pub fn get(&self, id: u32) -> Option<&CorpusSentence> {
+        self.sentences.get(&id)
+    }
+
+    /// Reads `sentences.csv` (id, language, text) or `sentences_detailed.csv`, which adds the
+    /// owner and dates.
+    pub fn read_sentences<R: BufRead>(&mut self, reader: R) -> io::Result<()> {
+        read_rows(reader, |columns| {
+            let (Some(id), Some(lang), Some(text)) = (
+                columns.first().and_then(|id| id.parse().ok()),
+                column(columns.get(1).copied()),
+                columns.get(2),
+            ) else {
+                return;
+            };
+            if !self.languages.is_empty() && !self.languages.contains(lang) {
+                return;
+            }
+
+            self.by_lang.entry(lang.to_owned()).or_default().push(id);
+            for term in terms(lang, text) {
+                self.by_term.entry(term).or_default().push(id);
+            }
+            self.has_owners |= columns.len() > 3;
+            self.sentences.insert(
+                id,
+                CorpusSentence {
+                    id,
+                    lang: lang.to_owned(),
+                    text: text.to_string(),
+                    owner: column(columns.get(3).copied()).map(str::to_owned),
+                },
+            );
+        })?;
+
+        self.by_lang
+            .values_mut()
+            .chain(self.by_term.values_mut())
+            .for_each(|ids| ids.sort_unstable());
+        Ok(())
+    }
+
+    /// Reads `links.csv` (sentence id, translation id), keeping the links between read
+    /// sentences.
+    pub fn read_links<R: BufRead>(&mut self, reader: R) -> io::Result<()> {
+        read_rows(reader, |columns| {
+            let (Some(Ok(from)), Some(Ok(to))) = (
+                columns.first().map(|id| id.parse()),
+                columns.get(1).map(|id| id.parse()),
+            ) else {
+                return;
+            };
+            if !self.sentences.contains_key(&from) || !self.sentences.contains_key(&to) {
+                return;
+            }
+
+            let links = self.links.entry(from).or_default();
+            if !links.contains(&to) {
+                links.push(to);
+            }
+            let links = self.links.entry(to).or_default();
+            if !links.contains(&from) {
+                links.push(from);
+            }
+        })
+    }
+
+    /// Reads `jpn_indices.csv` (sentence id, meaning id, B-line). The dictionary form of every
+    /// word is the part of its B-line token before the reading, sense, form or `~` marks, e.g.
+    /// `食べる` of `食べる{食べた}`.
+    pub fn read_indices<R: BufRead>(&mut self, reader: R) -> io::Result<()> {
+        read_rows(reader, |columns| {
+            let (Some(Ok(id)), Some(line)) =
+                (columns.first().map(|id| id.parse::<u32>()), columns.get(2))
+            else {
+                return;
+            };
+            if !self.sentences.contains_key(&id) {
+                return;
+            }
+
+            for token in line.split_whitespace() {
+                let word = token.split(['(', '[', '{', '~']).next().unwrap_or_default();
+                if !word.is_empty() {
+                    self.by_word.entry(word.to_owned()).or_default().insert(id);
+                }
+            }
+        })
+    }
+
+    /// Reads `sentences_with_audio.csv`, either (sentence id, audio id, username, license,
+    /// attribution url) or the older (sentence id, username, license, attribution url).
+    pub fn read_audio<R: BufRead>(&mut self, reader: R) -> io::Result<()> {
+        read_rows(reader, |columns| {
+            let Some(Ok(id)) = columns.first().map(|id| id.parse::<u32>()) else {
+                return;
+            };
+            if !self.sentences.contains_key(&id) {
+                return;
+            }
+
+            let audio_id = columns.get(1).and_then(|id| id.parse::<u32>().ok());
+            let rest = if audio_id.is_some() {
+                &columns[2..]
+            } else {
+                &columns[1..]
+            };
+            let value = |i: usize| column(rest.get(i).copied()).unwrap_or_default().to_owned();
+
+            self.audios.entry(id).or_default().push(TatoebaAudio {
+                author: value(0),
+                license: value(1),
+                attribution_url: value(2),
+                download_url: audio_id
+                    .map(|audio_id| format!("https://tatoeba.org/audio/download/{}", audio_id))
+                    .unwrap_or_default(),
+            });
+        })
+    }
+
+    /// Reads `user_languages.csv` (language, skill level, username, details), where level 5
+    /// marks a native speaker.
+    pub fn read_user_languages<R: BufRead>(&mut self, reader: R) -> io::Result<()> {
+        read_rows(reader, |columns| {
+            if let (Some(lang), Some("5"), Some(user)) = (
+                column(columns.first().copied()),
+                column(columns.get(1).copied()),
+                column(columns.get(2).copied()),
+            ) {
+                self.natives.insert((lang.to_owned(), user.to_owned()));
+            }
+        })
+    }
+
+    /// Reads `transcriptions.csv` (sentence id, language, script, username, transcription),
+    /// where transcriptions without a username are not reviewed.
+    pub fn read_transcriptions<R: BufRead>(&mut self, reader: R) -> io::Result<()> {
+        read_rows(reader, |columns| {
+            let (Some(Ok(id)), Some(script), Some(text)) = (
+                columns.first().map(|id| id.parse::<u32>()),
+                column(columns.get(2).copied()),
+                columns.get(4),
+            ) else {
+                return;
+            };
+            if !self.sentences.contains_key(&id) {
+                return;
+            }
+
+            self.transcriptions
+                .entry(id)
+                .or_default()
+                .push(TatoebaTranscription {
+                    script: script.to_owned(),
+                    text: text.to_string(),
+                    needsReview: column(columns.get(3).copied()).is_none(),
+                    type_: "altscript".to_owned(),
+                    html: text.to_string(),
+                });
+        })
+    }
+
+    /// Returns whether the owner of a sentence is a self-identified native speaker.
+    fn is_native(&self, sentence: &CorpusSentence) -> bool {
+        sentence.owner.as_ref().is_some_and(|owner| {
+            self.natives
+                .contains(&(sentence.lang.clone(), owner.clone()))
+        })
+    }
+
+    /// Returns whether a sentence passes the owner, orphan and native filters. Every sentence
+    /// passes them when the owners are not known.
+    fn is_owner_match(
+        &self,
+        owners: &HashSet<String>,
+        is_orphan: Option<bool>,
+        is_native: Option<bool>,
+        sentence: &CorpusSentence,
+    ) -> bool {
+        if !self.has_owners {
+            return true;
+        }
+
+        (owners.is_empty()
+            || sentence
+                .owner
+                .as_ref()
+                .is_some_and(|owner| owners.contains(owner)))
+            && is_orphan.is_none_or(|orphan| orphan == sentence.owner.is_none())
+            && is_native.is_none_or(|native| native == self.is_native(sentence))
+    }
+
+    /// Returns whether a translation passes the translation filters of a search.
+    fn is_translation_match(&self, search: &TatoebaSearch, translation: &CorpusSentence) -> bool {
+        let has_audio = self.audios.contains_key(&translation.id);
+
+        (search.trans_lang.is_empty() || search.trans_lang.contains(&translation.lang))
+            && self.is_owner_match(
+                &search.trans_owner,
+                search.trans_is_orphan,
+                None,
+                translation,
+            )
+            && search.trans_is_unapproved != Some(true)
+            && search
+                .trans_has_audio
+                .is_none_or(|audio| audio == has_audio)
+    }
+
+    /// Returns the direct and indirect translations of a sentence.
+    fn translations(&self, id: u32) -> (Vec<&CorpusSentence>, Vec<&CorpusSentence>) {
+        let direct: Vec<u32> = self.links.get(&id).cloned().unwrap_or_default();
+
+        let mut seen: HashSet<u32> = direct.iter().copied().collect();
+        seen.insert(id);
+        let mut indirect = Vec::new();
+        for translation in &direct {
+            for &link in self.links.get(translation).into_iter().flatten() {
+                if seen.insert(link) {
+                    indirect.push(link);
+                }
+            }
+        }
+
+        let lookup = |ids: Vec<u32>| -> Vec<&CorpusSentence> {
+            ids.iter().filter_map(|id| self.get(*id)).collect()
+        };
+        (lookup(direct), lookup(indirect))
+    }
+
+    /// Returns whether a sentence passes the filters of a search.
+    fn is_match(&self, search: &TatoebaSearch, sentence: &CorpusSentence) -> bool {
+        let (min, max) = search.word_count;
+        let count = word_count(&sentence.lang, &sentence.text);
+
+        let sentence_match = (search.lang.is_empty() || search.lang.contains(&sentence.lang))
+            && min.is_none_or(|min| count >= min)
+            && max.is_none_or(|max| count <= max)
+            && self.is_owner_match(&search.owner, search.is_orphan, search.is_native, sentence)
+            && search.is_unapproved != Some(true)
+            && search
+                .has_audio
+                .is_none_or(|audio| audio == self.audios.contains_key(&sentence.id));
+        if !sentence_match {
+            return false;
+        }
+
+        let (direct, indirect) = self.translations(sentence.id);
+        let translations: Vec<&CorpusSentence> = match search.trans_is_direct {
+            Some(true) => direct,
+            Some(false) => indirect,
+            None => direct.into_iter().chain(indirect).collect(),
+        };
+        let matching = translations
+            .iter()
+            .any(|translation| self.is_translation_match(search, translation));
+
+        match search.trans_count {
+            Some(false) => translations.is_empty(),
+            Some(true) => matching,
+            None => {
+                let filtered = !search.trans_lang.is_empty()
+                    || !search.trans_owner.is_empty()
+                    || search.trans_is_direct.is_some()
+                    || search.trans_is_orphan.is_some()
+                    || search.trans_is_unapproved.is_some()
+                    || search.trans_has_audio.is_some();
+                !filtered || matching
+            }
+        }
+    }
+
+    /// Converts a sentence into the entry the API would return, with its direct and indirect
+    /// translations in the languages of `trans_lang`.
+    fn entry(&self, search: &TatoebaSearch, sentence: &CorpusSentence) -> TatoebaEntry {
+        let shown = |translation: &&CorpusSentence| {
+            search.trans_lang.is_empty() || search.trans_lang.contains(&translation.lang)
+        };
+        let (direct, indirect) = self.translations(sentence.id);
+
+        TatoebaEntry {
+            id: sentence.id,
+            text: sentence.text.clone(),
+            lang: sentence.lang.clone(),
+            script: None,
+            license: LICENSE.to_owned(),
+            owner: sentence.owner.clone().unwrap_or_default(),
+            transcriptions: self
+                .transcriptions
+                .get(&sentence.id)
+                .cloned()
+                .unwrap_or_default(),
+            audios: self.audios.get(&sentence.id).cloned().unwrap_or_default(),
+            translations: [direct, indirect]
+                .map(|translations| {
+                    translations
+                        .into_iter()
+                        .filter(shown)
+                        .map(|translation| self.translation(translation))
+                        .collect::<Vec<_>>()
+                })
+                .to_vec(),
+        }
+    }
+
+    /// Converts a sentence into a translation of an entry.
+    fn translation(&self, sentence: &CorpusSentence) -> TatoebaTranslation {
+        TatoebaTranslation {
+            id: sentence.id,
+            text: sentence.text.clone(),
+            lang: sentence.lang.clone(),
+            script: None,
+            license: LICENSE.to_owned(),
+            owner: sentence.owner.clone().unwrap_or_default(),
+            transcriptions: self
+                .transcriptions
+                .get(&sentence.id)
+                .cloned()
+                .unwrap_or_default(),
+            audios: self.audios.get(&sentence.id).cloned().unwrap_or_default(),
+        }
+    }
+
+    /// Returns the ids of the sentences that have every term of a query in the given language,
+    /// or `None` if the query has no terms and every sentence must be checked.
+    fn candidates(&self, lang: &str, query: &str) -> Option<Vec<u32>> {
+        let mut lists: Vec<&[u32]> = query_terms(lang, query)
+            .iter()
+            .map(|term| self.by_term.get(term).map_or(&[][..], Vec::as_slice))
+            .collect();
+        if lists.is_empty() {
+            return None;
+        }
+
+        // Intersect starting from the rarest term
+        lists.sort_unstable_by_key(|list| list.len());
+        let (first, rest) = lists.split_first()?;
+
+        Some(
+            first
+                .iter()
+                .copied()
+                .filter(|id| rest.iter().all(|list| list.binary_search(id).is_ok()))
+                .collect(),
+        )
+    }
+
+    /// Returns every sentence containing the query or, for Japanese, using it in its dictionary
+    /// form, that passes the filters of a search, in the order of `sort`. Furigana in the query,
+    /// e.g. `日本[にほん]`, is ignored.
+    ///
+    /// Sentences are ordered by id for `created` and `modified`, and shuffled in a fixed order
+    /// for `random`.
+    pub fn search(&self, search: &TatoebaSearch, query: &str) -> Vec<TatoebaEntry> {
+        let query = query.to_kanji().split_whitespace().collect::<String>();
+
+        let mut ids: HashSet<u32> = HashSet::new();
+        for (lang, sentences) in &self.by_lang {
+            if !search.lang.is_empty() && !search.lang.contains(lang) {
+                continue;
+            }
+
+            match self.candidates(lang, &query) {
+                Some(candidates) => ids.extend(candidates),
+                None => ids.extend(sentences),
+            }
+        }
+
+        let indexed = self.by_word.get(&query);
+        ids.extend(indexed.into_iter().flatten());
+
+        let mut sentences: Vec<&CorpusSentence> = ids
+            .into_iter()
+            .filter_map(|id| self.get(id))
+            .filter(|sentence| search.lang.is_empty() || search.lang.contains(&sentence.lang))
+            .filter(|sentence| {
+                sentence.text.contains(&query)
+                    || indexed.is_some_and(|indexed| indexed.contains(&sentence.id))
+            })
+            .filter(|sentence| self.is_match(search, sentence))
+            .collect();
+
+        let length = |sentence: &CorpusSentence| word_count(&sentence.lang, &sentence.text);
+        match search.sort.unwrap_or(TatoebaSort::Relevance) {
+            TatoebaSort::Relevance => sentences.sort_by_key(|sentence| {
+                let exact = sentence.text.contains(&query);
+                (!exact, length(sentence), sentence.id)
+            }),
+            TatoebaSort::Shortest => {
+                sentences.sort_by_key(|sentence| (length(sentence), sentence.id))
+            }
+            TatoebaSort::Longest => {
+                sentences.sort_by_key(|sentence| (std::cmp::Reverse(length(sentence)), sentence.id))
+            }
+            TatoebaSort::Newest | TatoebaSort::Modified => {
+                sentences.sort_by_key(|sentence| std::cmp::Reverse(sentence.id))
+            }
+            TatoebaSort::Oldest => sentences.sort_by_key(|sentence| sentence.id),
+            TatoebaSort::Random => {
+                sentences.sort_by_key(|sentence| sentence.id.wrapping_mul(2_654_435_761))
+            }
+        }
+
+        sentences
+            .into_iter()
+            .map(|sentence| self.entry(search, sentence))
+            .collect()
+    }
+}
+
+/// Returns the terms a sentence is indexed by: its lowercase words for languages with spaces,
+/// and its characters and pairs of characters for languages without.
+fn terms(lang: &str, text: &str) -> HashSet<String> {
+    if !UNSPACED_LANGUAGES.contains(&lang) {
+        return words(text).collect();
+    }
+
+    let chars: Vec<char> = text.chars().collect();
+    chars
+        .iter()
+        .map(char::to_string)
+        .chain(chars.windows(2).map(|pair| pair.iter().collect()))
+        .collect()
+}
+
+/// Returns the terms a sentence containing the query must have, see [`terms`]. In languages
+/// with spaces the words of the query must be whole words of the sentence, as for the API.
+fn query_terms(lang: &str, query: &str) -> HashSet<String> {
+    if !UNSPACED_LANGUAGES.contains(&lang) {
+        return words(query).collect();
+    }
+
+    let chars: Vec<char> = query.chars().collect();
+    if chars.len() == 1 {
+        return HashSet::from([query.to_owned()]);
+    }
+    chars.windows(2).map(|pair| pair.iter().collect()).collect()
+}
+
+/// Returns the lowercase words of a text, without punctuation.
+fn words(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+}
+
+/// Returns the length of a sentence as the API counts it: words for languages with spaces,
+/// letters and digits for languages without.
+///
+/// # Example
+/// ```
+/// use anki_utill::tatoeba::tatoeba_corpus::word_count;
+///
+/// assert_eq!(word_count("eng", "I have a cat."), 4);
+/// assert_eq!(word_count("jpn", "猫が好き。"), 4);
+/// ```
+pub fn word_count(lang: &str, text: &str) -> usize {
+    if UNSPACED_LANGUAGES.contains(&lang) {
+        text.chars().filter(|c| c.is_alphanumeric()).count()
+    } else {
+        text.split_whitespace().count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Creates a small corpus of Japanese sentences and their English translations.
+    fn corpus() -> TatoebaCorpus {
+        let mut corpus = TatoebaCorpus::new(&["jpn", "eng"]);
+        corpus
+            .read_sentences(
+                "1\tjpn\t猫が好きです。\tken\t\\N\t\\N
+2\teng\tI like cats.\tamy\t\\N\t\\N
+3\tjpn\t猫を食べた。\t\\N\t\\N\t\\N
+4\teng\tI ate a cat.\tamy\t\\N\t\\N
+5\tjpn\t昨日、魚を食べました。\tyuki\t\\N\t\\N
+6\tfra\tJ'ai mangé du poisson hier.\tluc\t\\N\t\\N
+7\tjpn\t犬です。\tyuki\t\\N\t\\N
+"
+                .as_bytes(),
+            )
+            .unwrap();
+        corpus
+            .read_links("1\t2\n2\t1\n3\t4\n5\t6\n7\t4\n".as_bytes())
+            .unwrap();
+        corpus
+            .read_indices(
+                "3\t4\t猫(ねこ) を 食べる{食べた}~\n5\t-1\t昨日(きのう) 魚 を 食べる{食べました}\n"
+                    .as_bytes(),
+            )
+            .unwrap();
+        corpus
+            .read_audio("1\t99\tken\tCC BY 4.0\t\\N\n".as_bytes())
+            .unwrap();
+        corpus
+            .read_user_languages("jpn\t5\tken\t\\N\njpn\t3\tyuki\t\\N\n".as_bytes())
+            .unwrap();
+        corpus
+            .read_transcriptions("1\tjpn\tHrkt\t\\N\t[猫|ねこ]が[好|す]きです。\n".as_bytes())
+            .unwrap();
+        corpus
+    }
+
+    /// Tests reading the exports, skipping sentences of other languages.
+    #[test]
+    fn read() {
+        let corpus = corpus();
+
+        assert_eq!(corpus.len(), 6);
+        assert_eq!(corpus.get(3).unwrap().owner, None);
+        assert_eq!(corpus.get(6), None);
+        assert_eq!(corpus.links[&4], vec![3, 7]);
+        assert!(corpus.by_word["食べる"].contains(&5));
+        assert_eq!(corpus.by_term["猫"], vec![1, 3]);
+        assert_eq!(corpus.by_term["cats"], vec![2]);
+        assert_eq!(corpus.candidates("jpn", "食べた"), Some(vec![3]));
+        assert_eq!(corpus.candidates("jpn", "鳥"), Some(Vec::new()));
+        assert_eq!(corpus.candidates("jpn", ""), None);
+        assert_eq!(
+            corpus.audios[&1][0].download_url,
+            "https://tatoeba.org/audio/download/99"
+        );
+    }
+
+    /// Tests answering the filters of a search.
+    #[test]
+    fn search() {
+        let corpus = corpus();
+        let ids = |search: &TatoebaSearch, query: &str| -> Vec<u32> {
+            corpus
+                .search(search, query)
+                .iter()
+                .map(|entry| entry.id)
+                .collect()
+        };
+
+        let mut search = TatoebaSearch::new("jpn", "eng");
+        search.sort = Some(TatoebaSort::Shortest);
+        assert_eq!(ids(&search, "猫[ねこ]"), vec![3, 1]);
+        // Found by the dictionary form, but without an English translation
+        assert_eq!(ids(&search, "食べる"), vec![3]);
+
+        let entry = corpus.search(&search, "猫").remove(1);
+        assert_eq!(entry.translations[0][0].text, "I like cats.");
+        assert_eq!(entry.transcriptions[0].text, "[猫|ねこ]が[好|す]きです。");
+
+        search.is_native = Some(true);
+        assert_eq!(ids(&search, "猫"), vec![1]);
+
+        search.is_native = None;
+        search.is_orphan = Some(false);
+        search.has_audio = Some(false);
+        assert_eq!(ids(&search, "猫"), Vec::<u32>::new());
+
+        // Indirect translations are only shown when not limited to direct ones
+        let mut search = TatoebaSearch::new("jpn", "jpn");
+        assert_eq!(ids(&search, "犬"), vec![7]);
+        assert_eq!(corpus.search(&search, "犬")[0].translations[1][0].id, 3);
+        search.trans_is_direct = Some(true);
+        assert_eq!(ids(&search, "犬"), Vec::<u32>::new());
+
+        search.word_count = (Some(3), Some(4));
+        search.trans_lang.clear();
+        search.trans_is_direct = None;
+        assert_eq!(ids(&search, "犬"), vec![7]);
+        search.word_count = (Some(4), None);
+        assert_eq!(ids(&search, "犬"), Vec::<u32>::new());
+    }
+
+    /// Tests that the owner filters pass every sentence when only `sentences.csv` is read.
+    #[test]
+    fn unknown_owners() {
+        let mut corpus = TatoebaCorpus::new(&["jpn"]);
+        corpus
+            .read_sentences("1\tjpn\t猫が好きです。\n3\tjpn\t猫を食べた。\n".as_bytes())
+            .unwrap();
+
+        let mut search = TatoebaSearch::new("jpn", "eng");
+        search.trans_lang.clear();
+        search.is_orphan = Some(false);
+        search.is_native = Some(true);
+        search.sort = Some(TatoebaSort::Oldest);
+
+        let ids: Vec<u32> = corpus
+            .search(&search, "猫")
+            .iter()
+            .map(|entry| entry.id)
+            .collect();
+        assert_eq!(ids, vec![1, 3]);
+    }
+}
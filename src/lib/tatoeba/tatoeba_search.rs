@@ -7,6 +7,8 @@ use std::{
 use reqwest::Method;
 use serde::{Deserialize, Serialize};
 
+use super::tatoeba_cache::TatoebaCache;
+
 /// Limit according to sentence origin. All sentences fall in two sets: *unknown* and *known*.
 /// The set *known* is composed of two subsets: *original* + *translation*.
 ///
@@ -312,11 +314,13 @@ fn insert_search_hashset(
     value: &HashSet<String>,
 ) {
     if !value.is_empty() {
-        let value = value
+        let mut value = value
             .iter()
             .map(|lang| lang.as_str())
-            .collect::<Vec<&str>>()
-            .join(",");
+            .collect::<Vec<&str>>();
+        // Sorted so equal searches have equal urls
+        value.sort_unstable();
+        let value = value.join(",");
 
         out.insert(key, value);
     }
@@ -422,11 +426,13 @@ impl TatoebaSearch {
             params.insert("after", after.to_owned());
         }
 
-        let params = params
+        // Sorted so equal searches have equal urls, which key the cache
+        let mut params = params
             .iter()
             .map(|(key, value)| format!("{key}={value}"))
-            .collect::<Vec<String>>()
-            .join("&");
+            .collect::<Vec<String>>();
+        params.sort_unstable();
+        let params = params.join("&");
 
         format!("https://api.tatoeba.org/unstable/sentences?q={querry}&{params}")
     }
@@ -440,16 +446,59 @@ impl TatoebaSearch {
         &self,
         querry: &str,
         after: Option<&str>,
+    ) -> Result<TatoebaResponse, Box<dyn std::error::Error>> {
+        self.search_with(None, querry, after)
+    }
+
+    /// Like [`TatoebaSearch::search`], but answers from the cache when it holds the page and
+    /// saves fetched pages in it. A replaying cache never fetches.
+    pub fn search_cached(
+        &self,
+        cache: &TatoebaCache,
+        querry: &str,
+        after: Option<&str>,
+    ) -> Result<TatoebaResponse, Box<dyn std::error::Error>> {
+        self.search_with(Some(cache), querry, after)
+    }
+
+    /// Fetches a single page of results, through the cache if there is one.
+    fn search_with(
+        &self,
+        cache: Option<&TatoebaCache>,
+        querry: &str,
+        after: Option<&str>,
     ) -> Result<TatoebaResponse, Box<dyn std::error::Error>> {
         let url = self.to_string(querry, after);
         /* println!("\nTatoeba url: {}", &url); */
 
+        if let Some(body) = cache.and_then(|cache| cache.get(&url)) {
+            return Ok(serde_json::from_str(&body)?);
+        }
+
+        self.fetch(cache, &url)
+    }
+
+    /// Requests a page the cache does not hold and saves it in the cache.
+    fn fetch(
+        &self,
+        cache: Option<&TatoebaCache>,
+        url: &str,
+    ) -> Result<TatoebaResponse, Box<dyn std::error::Error>> {
+        if cache.is_some_and(|cache| cache.is_replay()) {
+            return Err(format!("No saved response for {}", url).into());
+        }
+
         let client = reqwest::blocking::Client::new();
-        let response = client.request(Method::GET, &url).send()?;
-        let response = response.text()?;
-        let response: TatoebaResponse = serde_json::from_str(response.as_str())?;
+        let response = client.request(Method::GET, url).send()?;
+        let response = response.error_for_status()?.text()?;
+        let parsed: TatoebaResponse = serde_json::from_str(response.as_str())?;
+
+        // Only responses that parse are saved
+        if let Some(cache) = cache {
+            cache.store(url, &response)?;
+        }
 
-        Ok(response)
+        Ok(parsed)
     }
 
     /// Returns an iterator that lazily fetches pages of results.
@@ -462,14 +511,26 @@ impl TatoebaSearch {
         query: &'a str,
         delay: Option<Duration>,
     ) -> TatoebaSearchIter<'a> {
-        TatoebaSearchIter::from(self, query, delay)
+        TatoebaSearchIter::from(self, query, None, delay)
+    }
+
+    /// Like [`TatoebaSearch::search_iter`], but fetches the pages through the cache. The
+    /// delay is skipped for pages the cache holds.
+    pub fn search_iter_cached<'a>(
+        &'a self,
+        cache: &'a TatoebaCache,
+        query: &'a str,
+        delay: Option<Duration>,
+    ) -> TatoebaSearchIter<'a> {
+        TatoebaSearchIter::from(self, query, Some(cache), delay)
     }
 }
 
 pub struct TatoebaSearchIter<'a> {
     search: &'a TatoebaSearch,
     querry: &'a str,
-    response: Option<TatoebaResponse>,
+    cache: Option<&'a TatoebaCache>,
+    response: Option<Result<TatoebaResponse, Box<dyn std::error::Error>>>,
     delay: Option<Duration>,
 }
 
@@ -478,22 +539,42 @@ impl<'a> TatoebaSearchIter<'a> {
     ///
     /// The first request is performed immediately (after an optional sleep).
     /// Subsequent pages are fetched lazily inside the `Iterator` implementation.
-    fn from(search: &'a TatoebaSearch, querry: &'a str, delay: Option<Duration>) -> Self {
-        if let Some(delay) = delay {
-            thread::sleep(delay);
-        }
-
-        TatoebaSearchIter {
+    fn from(
+        search: &'a TatoebaSearch,
+        querry: &'a str,
+        cache: Option<&'a TatoebaCache>,
+        delay: Option<Duration>,
+    ) -> Self {
+        let mut iter = TatoebaSearchIter {
             search,
             querry,
-            response: search.search(querry, None).ok(),
+            cache,
+            response: None,
             delay,
+        };
+
+        iter.response = Some(iter.load(None));
+        iter
+    }
+
+    /// Fetches a page, sleeping for the delay first unless the cache holds it.
+    fn load(&self, after: Option<&str>) -> Result<TatoebaResponse, Box<dyn std::error::Error>> {
+        let url = self.search.to_string(self.querry, after);
+
+        match self.cache.and_then(|cache| cache.get(&url)) {
+            Some(body) => Ok(serde_json::from_str(&body)?),
+            None => {
+                if let Some(delay) = self.delay {
+                    thread::sleep(delay);
+                }
+                self.search.fetch(self.cache, &url)
+            }
         }
     }
 }
 
 impl<'a> Iterator for TatoebaSearchIter<'a> {
-    type Item = TatoebaEntry;
+    type Item = Result<TatoebaEntry, Box<dyn std::error::Error>>;
 
     /// Pulls the next `TatoebaEntry` from the iterator.
     ///
@@ -501,15 +582,18 @@ impl<'a> Iterator for TatoebaSearchIter<'a> {
     /// 1. If the current page is exhausted (`response.data.is_empty()`), it fetches
     ///    the next page using the cursor returned by the API.
     /// 2. Entries are returned one by one via `pop()`.  
+    /// 3. A page that fails to load is returned as an error, which ends the iteration.
     ///
     /// **Note:** `Vec::pop` removes from the back; if you want FIFO order,
     /// consider reversing the vector or using `remove(0)`
     fn next(&mut self) -> Option<Self::Item> {
-        let response = self.response.as_mut()?;
+        let mut response = match self.response.take()? {
+            Ok(response) => response,
+            Err(err) => return Some(Err(err)),
+        };
 
         // End of all pages.
         if response.data.is_empty() {
-            self.response = None;
             return None;
         }
 
@@ -517,19 +601,14 @@ impl<'a> Iterator for TatoebaSearchIter<'a> {
         let out = response.data.pop();
 
         // If the current page is now empty, fetch the next one (if any).
-        if response.data.is_empty() {
-            self.response = if let Some(cursor_end) = &response.paging.cursor_end {
-                if let Some(delay) = self.delay {
-                    thread::sleep(delay);
-                }
-
-                self.search.search(self.querry, Some(cursor_end)).ok()
-            } else {
-                None
-            }
-        }
+        self.response = if response.data.is_empty() {
+            let cursor_end = response.paging.cursor_end.take();
+            cursor_end.map(|cursor_end| self.load(Some(&cursor_end)))
+        } else {
+            Some(Ok(response))
+        };
 
-        out
+        out.map(Ok)
     }
 }
 
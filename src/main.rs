@@ -1,3 +1,190 @@
-// cargo run
+// cargo run -- <command>
 
-fn main() {}
+mod commands;
+
+use std::{path::PathBuf, process::ExitCode};
+
+use anki_utill::config::{Config, ConfigError};
+use clap::{Args, Parser, Subcommand};
+
+use commands::{Options, Stage};
+
+/// Generates and maintains an Anki deck for learning Japanese.
+#[derive(Parser, Debug)]
+#[command(name = "anki-deck")]
+struct Cli {
+    /// The config file to use instead of `./config.toml` or `./config.json`.
+    #[arg(long, global = true)]
+    config: Option<PathBuf>,
+
+    #[command(flatten)]
+    options: OptionArgs,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+/// The flags shared by every command.
+#[derive(Args, Debug)]
+struct OptionArgs {
+    /// The deck to use instead of the configured one.
+    #[arg(long, global = true)]
+    deck: Option<String>,
+
+    /// Only report what would be changed instead of writing to Anki or to disk.
+    #[arg(long, global = true)]
+    dry_run: bool,
+
+    /// The maximum number of notes to add, or to process for `strokes`, `audio`, `examples` and
+    /// `export`.
+    #[arg(long, global = true)]
+    limit: Option<usize>,
+
+    /// Extra Anki search terms narrowing the word notes processed by `strokes`, `order`, `audio`
+    /// and `examples`.
+    #[arg(long, global = true)]
+    query: Option<String>,
+
+    /// Print the changes planned by `add --dry-run` as JSON instead of a diff.
+    #[arg(long, global = true)]
+    json: bool,
+}
+
+#[derive(Subcommand, Debug, Clone, Copy, PartialEq, Eq)]
+enum Command {
+    /// Builds the word and kanji lists from the dictionaries.
+    Dictionary,
+    /// Adds new words to Anki and updates the existing notes. With `--dry-run` only prints the
+    /// planned changes.
+    Add,
+    /// Adds the kanji used by the word notes to Anki and updates the existing kanji notes.
+    Kanji,
+    /// Attaches the KanjiVG stroke order diagrams to the word and kanji notes that have a stroke
+    /// order field.
+    Strokes,
+    /// Reorders the new cards by JLPT level and kanji complexity, each kanji before the first
+    /// word using it.
+    Order,
+    /// Adds audio to notes without any.
+    Audio,
+    /// Adds example sentences from Tatoeba.
+    Examples,
+    /// Writes the word list to an offline `.apkg` package.
+    Export,
+    /// Deletes the audio and stroke order files no note of the deck references anymore.
+    CleanMedia,
+    /// Runs `dictionary`, `add`, `kanji`, `strokes`, `order`, `audio` and `examples` in order, stopping
+    /// at the first stage that fails.
+    All,
+}
+
+impl Command {
+    /// Returns the stages run by the command.
+    fn stages(self) -> Vec<Stage> {
+        match self {
+            Command::Dictionary => vec![Stage::Dictionary],
+            Command::Add => vec![Stage::Add],
+            Command::Kanji => vec![Stage::Kanji],
+            Command::Strokes => vec![Stage::Strokes],
+            Command::Order => vec![Stage::Order],
+            Command::Audio => vec![Stage::Audio],
+            Command::Examples => vec![Stage::Examples],
+            Command::Export => vec![Stage::Export],
+            Command::CleanMedia => vec![Stage::CleanMedia],
+            Command::All => Stage::PIPELINE.to_vec(),
+        }
+    }
+}
+
+impl Cli {
+    /// Loads the config and applies the overrides given on the command line.
+    fn config(&self) -> Result<Config, ConfigError> {
+        let mut config = match &self.config {
+            Some(path) => Config::load(path)?,
+            None => Config::load_default()?,
+        };
+
+        if let Some(deck) = &self.options.deck {
+            config.deck.name = deck.clone();
+        }
+
+        Ok(config)
+    }
+
+    /// Returns the options passed to every stage.
+    fn options(&self) -> Options {
+        Options {
+            dry_run: self.options.dry_run,
+            limit: self.options.limit,
+            query: self.options.query.clone(),
+            json: self.options.json,
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+
+    let config = match cli.config() {
+        Ok(config) => config,
+        Err(err) => {
+            eprintln!("{err}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    match commands::run_all(&cli.command.stages(), &config, &cli.options()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("\n{err}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::CommandFactory;
+
+    use super::*;
+
+    /// Tests that the shared flags are accepted before and after the command.
+    #[test]
+    fn parse_arguments() {
+        Cli::command().debug_assert();
+
+        let cli = Cli::try_parse_from([
+            "anki-deck",
+            "--dry-run",
+            "audio",
+            "--limit",
+            "10",
+            "--query",
+            "tag:JLPT-N5",
+            "--deck",
+            "Japanese",
+            "--config",
+            concat!(env!("CARGO_MANIFEST_DIR"), "/config.example.toml"),
+        ])
+        .unwrap();
+
+        assert_eq!(cli.command, Command::Audio);
+        assert_eq!(
+            cli.options(),
+            Options {
+                dry_run: true,
+                limit: Some(10),
+                query: Some("tag:JLPT-N5".to_owned()),
+                json: false,
+            }
+        );
+        assert_eq!(
+            cli.config,
+            Some(PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("config.example.toml"))
+        );
+        assert_eq!(cli.config().unwrap().deck.name, "Japanese");
+
+        assert!(Cli::try_parse_from(["anki-deck"]).is_err());
+        assert_eq!(Command::All.stages(), Stage::PIPELINE.to_vec());
+    }
+}